@@ -1,41 +1,208 @@
-use crate::models::CtfBox;
+use crate::config::Config;
+use crate::keymap::{Action, KeyContext, Keymap};
+use crate::models::{CtfBox, Flag, FlagKind};
+use crate::redact::{RedactMode, Redactor};
+use crate::ui::input::TextInput;
+use crate::ui::theme::Theme;
 use std::collections::HashMap;
 use std::process::Command;
 use std::time::Instant;
 
+/// The current screen, with the state of the form it shows
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppView {
     List,
+    Dashboard,
+    Stats,
     Details(i32),
-    AddBox,
+    AddBox(AddBoxForm),
+    AddFlag(FlagForm),
     DeleteBox(i32),
-    EditEnvVars(i32),
-    EditNotes(i32),
+    /// Confirmation before ending the box's tmux session
+    KillTmux(i32),
+    /// The form is open while a variable is being added
+    EditEnvVars(i32, Option<EnvVarForm>),
+    /// The category picker is open before a note is written
+    EditNotes(i32, Option<NoteForm>),
     WriteupExport(i32),
+    Loot(i32),
+    Timeline(i32),
+    /// The template picker is open while a checklist is being added
+    Checklists(i32, Option<TemplatePicker>),
+    /// The input is open while the picked command is reviewed before it runs
+    Suggestions(i32, Option<TextInput>),
+    Snippets(i32, SnippetPicker),
+    Payloads(i32),
+    /// Preview of the hosts file block, for a box (None after one was
+    /// deleted). The input is open while its hostnames are edited.
+    Hosts(Option<i32>, Option<TextInput>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AddBoxForm {
-    pub title: String,
-    pub platform: String,
-    pub ip: String,
-    pub tags: String,
+    pub title: TextInput,
+    pub platform: TextInput,
+    pub ip: TextInput,
+    pub tags: TextInput,
     pub current_field: usize,
 }
 
-#[derive(Debug, Clone)]
+impl AddBoxForm {
+    /// The input that receives typed keys
+    pub fn active_input(&mut self) -> &mut TextInput {
+        match self.current_field {
+            0 => &mut self.title,
+            1 => &mut self.platform,
+            2 => &mut self.ip,
+            _ => &mut self.tags,
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.current_field = (self.current_field + 1) % 4;
+    }
+
+    pub fn previous_field(&mut self) {
+        if self.current_field == 0 {
+            self.current_field = 3;
+        } else {
+            self.current_field -= 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct EnvVarForm {
-    pub key: String,
-    pub value: String,
+    pub key: TextInput,
+    pub value: TextInput,
     pub current_field: usize,
 }
 
-#[derive(Debug, Clone)]
+impl EnvVarForm {
+    /// The input that receives typed keys
+    pub fn active_input(&mut self) -> &mut TextInput {
+        if self.current_field == 0 {
+            &mut self.key
+        } else {
+            &mut self.value
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.current_field = (self.current_field + 1) % 2;
+    }
+
+    pub fn previous_field(&mut self) {
+        self.current_field = if self.current_field == 0 { 1 } else { 0 };
+    }
+}
+
+/// Category picker shown before the note is written in $EDITOR
+#[derive(Debug, Clone, PartialEq)]
 pub struct NoteForm {
-    pub content: String,
     pub category_index: usize,
+    // Index of the note being edited, None when adding a new one
+    pub editing: Option<usize>,
+}
+
+/// Flag popup, over the details or the dashboard
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlagForm {
+    pub box_id: i32,
+    pub kind: FlagKind,
+    pub value: TextInput,
+    pub from_dashboard: bool,
+}
+
+impl FlagForm {
+    pub fn next_kind(&mut self) {
+        let all = FlagKind::ALL;
+        let current = all.iter().position(|k| *k == self.kind).unwrap_or(0);
+        self.kind = all[(current + 1) % all.len()];
+    }
+}
+
+/// Checklist templates offered for a box
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplatePicker {
+    pub templates: Vec<crate::checklist::Template>,
+    pub selected: usize,
+}
+
+/// Fuzzy search over the snippets, then the values of the picked one's
+/// placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnippetPicker {
+    pub snippets: Vec<crate::snippets::Snippet>,
+    pub query: TextInput,
+    pub selected: usize,
+    pub form: Option<SnippetForm>,
+}
+
+/// Placeholders of the picked snippet that the box has no value for
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnippetForm {
+    pub snippet: crate::snippets::Snippet,
+    pub fields: Vec<(String, TextInput)>,
+    pub current: usize,
+}
+
+impl SnippetForm {
+    /// The command with the values entered so far
+    pub fn preview(&self, values: &HashMap<String, String>) -> String {
+        let mut values = values.clone();
+        for (name, input) in &self.fields {
+            if !input.value().is_empty() {
+                values.insert(name.to_lowercase(), input.value().to_string());
+            }
+        }
+        crate::snippets::fill(&self.snippet.command, &values)
+    }
+}
+
+/// Pane of the dashboard, in focus order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashboardPane {
+    Boxes,
+    Info,
+    Feed,
 }
 
+/// Focus, layout and live data of the dashboard view
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    pub focus: DashboardPane,
+    /// Width of the boxes, info and feed panes, in percent
+    pub widths: [u16; 3],
+    pub info_scroll: usize,
+    pub feed_scroll: usize,
+    /// Commands logged by box shells and not imported yet: (box id, action)
+    pub live: Vec<(i32, crate::models::Action)>,
+    pub refreshed: Option<Instant>,
+}
+
+/// Seconds between two reads of the shell logs while the dashboard is shown
+const LIVE_REFRESH_SECS: u64 = 2;
+/// Values remembered per input field
+const HISTORY_LIMIT: usize = 100;
+/// Smallest width of a dashboard pane, in percent
+const MIN_PANE_WIDTH: u16 = 10;
+/// Failed probes in a row before an outage is recorded, so that a single
+/// lost check (packet loss, a box busy with a scan) is not
+const OUTAGE_AFTER_FAILURES: u32 = 3;
+
+/// Command palette: fuzzy search over the actions of the view it was opened from
+#[derive(Debug, Clone)]
+pub struct Palette {
+    pub context: KeyContext,
+    pub input: TextInput,
+    /// Index in the filtered matches
+    pub selected: usize,
+}
+
+/// First line of the temp file opened in $EDITOR, stripped when reading it back
+const NOTE_HEADER: &str = "<!-- ctf-brain:";
+
 /// Message type for the status bar
 #[derive(Debug, Clone, PartialEq)]
 pub enum StatusKind {
@@ -51,13 +218,86 @@ pub struct App {
     pub should_quit: bool,
     pub selected_env_var: Option<usize>,
     pub selected_note: Option<usize>,
+    pub selected_attachment: Option<usize>,
+    /// Item of the box's checklists, counted across all of them
+    pub selected_check: Option<usize>,
+    pub selected_suggestion: Option<usize>,
+    /// Knowledge base of the suggestions view, read when it opens
+    pub suggestion_rules: Vec<crate::suggest::Rule>,
+    pub selected_payload: Option<usize>,
+    /// Catalog of the payloads view, read when it opens
+    pub payloads: Vec<crate::payloads::Payload>,
+    pub payload_encoding: crate::payloads::Encoding,
+    /// Address detected when the payloads view opened, or why there is none
+    pub lhost: Result<String, String>,
+    /// Hosts file as read when the hosts view opened or was last written
+    pub hosts_content: Result<String, String>,
+    /// First visible item of the notes and actions panes of the details view
+    pub notes_scroll: usize,
+    pub actions_scroll: usize,
+    /// First visible item of the timeline view
+    pub timeline_scroll: usize,
+    /// Clickable areas of the last drawn frame
+    pub clickables: Vec<(ratatui::layout::Rect, crate::ui::Clickable)>,
     pub status_message: Option<(String, StatusKind, Instant)>,
-    pub writeup_path: String,
+    pub writeup_path: TextInput,
+    pub redact_mode: RedactMode,
+    /// What the current redaction mode would hide: (rule, masked sample, count)
+    pub redaction_preview: Vec<(String, String, usize)>,
+    /// Invalid lines of `redact.rules`, left out of the redaction
+    pub redaction_problems: Vec<String>,
+    /// Statistics of the stats view, computed when it opens
+    pub stats: Option<crate::stats::Stats>,
+    pub graphics: crate::ui::graphics::GraphicsProtocol,
+    /// Values submitted in each input field, recalled with Up/Down
+    pub input_history: HashMap<String, Vec<String>>,
+    pub config: Config,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    pub palette: Option<Palette>,
+    pub show_help: bool,
+    pub dashboard: Dashboard,
+    /// Back from a box returns to the dashboard instead of the list
+    pub dashboard_mode: bool,
+    /// `ctf-brain listen` processes running, shown in the footer
+    pub listeners: Vec<crate::listener::ActiveListener>,
+    pub listeners_refreshed: Option<Instant>,
+    /// Last reachability check of each box
+    pub reachability: HashMap<i32, crate::reachability::Probe>,
+    /// Failed probes in a row of each box, with the first of them
+    pub failed_probes: HashMap<i32, (u32, crate::reachability::Probe)>,
+    /// Check running in the background
+    pub probing: Option<std::sync::mpsc::Receiver<crate::reachability::Probe>>,
+    pub probed: Option<Instant>,
+    /// tmux sessions running, to mark the boxes that have one
+    pub tmux_sessions: Vec<String>,
+    pub tmux_refreshed: Option<Instant>,
+    /// Session list being read in the background
+    pub tmux_listing: Option<std::sync::mpsc::Receiver<Vec<String>>>,
+    /// Effects queued while handling the current message (saves, imports)
+    pub pending_effects: Vec<crate::update::Effect>,
 }
 
 impl App {
-    pub fn new(boxes: Vec<CtfBox>) -> Self {
+    pub fn new(boxes: Vec<CtfBox>, config: Config) -> Self {
         let selected_box_id = if !boxes.is_empty() { Some(0) } else { None };
+        let (keymap, key_problems) = Keymap::from_config(&config.keys);
+        let theme = Theme::by_name(&config.ui.theme).unwrap_or_default();
+        let dashboard = Dashboard {
+            focus: DashboardPane::Boxes,
+            widths: config.ui.dashboard_panes,
+            info_scroll: 0,
+            feed_scroll: 0,
+            live: Vec::new(),
+            refreshed: None,
+        };
+        let status_message = (!key_problems.is_empty()).then(|| {
+            (
+                format!("Config: {}", key_problems.join("; ")),
+                StatusKind::Error,
+                Instant::now(),
+            )
+        });
         Self {
             view: AppView::List,
             boxes,
@@ -65,22 +305,76 @@ impl App {
             should_quit: false,
             selected_env_var: None,
             selected_note: None,
-            status_message: None,
-            writeup_path: String::new(),
+            selected_attachment: None,
+            selected_check: None,
+            selected_suggestion: None,
+            suggestion_rules: Vec::new(),
+            selected_payload: None,
+            payloads: Vec::new(),
+            payload_encoding: Default::default(),
+            lhost: Err(String::new()),
+            hosts_content: Ok(String::new()),
+            notes_scroll: 0,
+            actions_scroll: 0,
+            timeline_scroll: 0,
+            clickables: Vec::new(),
+            status_message,
+            writeup_path: TextInput::new(),
+            redact_mode: RedactMode::Placeholder,
+            redaction_preview: Vec::new(),
+            redaction_problems: Vec::new(),
+            stats: None,
+            graphics: crate::ui::graphics::detect(),
+            input_history: HashMap::new(),
+            config,
+            keymap,
+            theme,
+            palette: None,
+            show_help: false,
+            dashboard,
+            dashboard_mode: false,
+            listeners: Vec::new(),
+            listeners_refreshed: None,
+            reachability: HashMap::new(),
+            failed_probes: HashMap::new(),
+            probing: None,
+            probed: None,
+            tmux_sessions: Vec::new(),
+            tmux_refreshed: None,
+            tmux_listing: None,
+            pending_effects: Vec::new(),
         }
     }
 
-    /// Set a status message that auto-expires after 4 seconds
+    /// Set a status message that auto-expires after `ui.status_timeout_secs`
     pub fn set_status(&mut self, msg: impl Into<String>, kind: StatusKind) {
         self.status_message = Some((msg.into(), kind, Instant::now()));
     }
 
     /// Clear the status message if it has expired
     pub fn tick_status(&mut self) {
-        if let Some((_, _, when)) = &self.status_message {
-            if when.elapsed().as_secs() >= 4 {
-                self.status_message = None;
-            }
+        if let Some((_, _, when)) = &self.status_message
+            && when.elapsed().as_secs() >= self.config.ui.status_timeout_secs
+        {
+            self.status_message = None;
+        }
+    }
+
+    /// History of an input field, oldest first
+    pub fn history(&self, field: &str) -> Vec<String> {
+        self.input_history.get(field).cloned().unwrap_or_default()
+    }
+
+    /// Remember a submitted value (duplicates move to the end)
+    pub fn record_history(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            return;
+        }
+        let entries = self.input_history.entry(field.to_string()).or_default();
+        entries.retain(|v| v != value);
+        entries.push(value.to_string());
+        if entries.len() > HISTORY_LIMIT {
+            entries.remove(0);
         }
     }
 
@@ -109,42 +403,128 @@ impl App {
     }
 
     pub fn select_current(&mut self) {
-        if let Some(idx) = self.selected_box_id {
-            if let Some(ctf_box) = self.boxes.get(idx as usize) {
-                self.view = AppView::Details(ctf_box.id);
+        if let Some(idx) = self.selected_box_id
+            && let Some(ctf_box) = self.boxes.get(idx as usize)
+        {
+            self.view = AppView::Details(ctf_box.id);
+            self.notes_scroll = 0;
+            self.actions_scroll = 0;
+        }
+    }
+
+    /// Select the first or last item of the list shown in the current view
+    pub fn select_edge(&mut self, last: bool) {
+        let pick = |count: usize| match count {
+            0 => None,
+            _ if last => Some(count - 1),
+            _ => Some(0),
+        };
+        let current_box = |id: i32| self.boxes.iter().find(|b| b.id == id);
+        match self.view {
+            AppView::List | AppView::Dashboard => {
+                self.selected_box_id = pick(self.boxes.len()).map(|i| i as i32)
+            }
+            AppView::EditEnvVars(id, _) => {
+                self.selected_env_var = pick(current_box(id).map_or(0, |b| b.env_vars.len()))
+            }
+            AppView::EditNotes(id, _) => {
+                self.selected_note = pick(current_box(id).map_or(0, |b| b.notes.len()))
             }
+            AppView::Loot(id) => {
+                self.selected_attachment = pick(current_box(id).map_or(0, |b| b.attachments.len()))
+            }
+            AppView::Timeline(id) => self.timeline_scroll = pick(self.timeline_len(id)).unwrap_or(0),
+            AppView::Checklists(id, None) => {
+                self.selected_check = pick(current_box(id).map_or(0, check_count))
+            }
+            AppView::Suggestions(id, None) => {
+                self.selected_suggestion = pick(self.suggestions(id).len())
+            }
+            AppView::Payloads(_) => self.selected_payload = pick(self.payloads.len()),
+            _ => {}
         }
     }
 
-    pub fn go_back(&mut self) {
-        self.view = AppView::List;
+    pub fn open_palette(&mut self, context: KeyContext) {
+        self.palette = Some(Palette {
+            context,
+            input: TextInput::new(),
+            selected: 0,
+        });
     }
 
-    pub fn quit(&mut self) {
-        self.should_quit = true;
+    /// Actions matching the palette query, best first, with the matched
+    /// character positions in their description
+    pub fn palette_matches(&self) -> Vec<(Action, Vec<usize>)> {
+        let Some(palette) = &self.palette else {
+            return Vec::new();
+        };
+        let actions: Vec<Action> = crate::keymap::actions_for(palette.context)
+            .into_iter()
+            .map(|(_, action)| action)
+            .filter(|a| *a != Action::Palette)
+            .collect();
+        crate::fuzzy::rank(palette.input.value(), &actions, |a| a.description().to_string())
+            .into_iter()
+            .map(|(action, positions)| (*action, positions))
+            .collect()
     }
 
-    pub fn next_field(&mut self, form: &mut AddBoxForm) {
-        form.current_field = (form.current_field + 1) % 4;
+    pub fn palette_next(&mut self) {
+        let count = self.palette_matches().len();
+        if let Some(palette) = &mut self.palette
+            && count > 0
+        {
+            palette.selected = (palette.selected + 1) % count;
+        }
     }
 
-    pub fn previous_field(&mut self, form: &mut AddBoxForm) {
-        if form.current_field == 0 {
-            form.current_field = 3;
-        } else {
-            form.current_field -= 1;
+    pub fn palette_previous(&mut self) {
+        let count = self.palette_matches().len();
+        if let Some(palette) = &mut self.palette
+            && count > 0
+        {
+            palette.selected = (palette.selected + count - 1) % count;
         }
     }
 
-    pub fn start_add_box(&mut self) -> AddBoxForm {
-        self.view = AppView::AddBox;
-        AddBoxForm {
-            title: String::new(),
-            platform: String::from("HTB"),
-            ip: String::new(),
-            tags: String::new(),
+    /// Close the palette and return the selected action
+    pub fn take_palette_action(&mut self) -> Option<Action> {
+        let selected = self.palette.as_ref()?.selected;
+        let action = self.palette_matches().get(selected).map(|(a, _)| *a);
+        self.palette = None;
+        action
+    }
+
+    /// Switch to the next color preset (for this session)
+    pub fn cycle_theme(&mut self) {
+        let names = Theme::NAMES;
+        let current = names.iter().position(|n| *n == self.theme.name).unwrap_or(0);
+        let next = names[(current + 1) % names.len()];
+        self.theme = Theme::by_name(next).unwrap_or_default();
+        self.set_status(format!("Theme: {}", next), StatusKind::Info);
+    }
+
+    pub fn go_back(&mut self) {
+        self.view = if self.dashboard_mode {
+            AppView::Dashboard
+        } else {
+            AppView::List
+        };
+    }
+
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+
+    pub fn start_add_box(&mut self) {
+        self.view = AppView::AddBox(AddBoxForm {
+            title: TextInput::new().with_history(self.history("title")),
+            platform: TextInput::with_value(self.config.platforms.default.clone()).with_history(self.history("platform")),
+            ip: TextInput::new().with_history(self.history("ip")),
+            tags: TextInput::new().with_history(self.history("tags")),
             current_field: 0,
-        }
+        });
     }
 
     pub fn cancel_form(&mut self) {
@@ -153,12 +533,14 @@ impl App {
 
     pub fn submit_add_box(&mut self, form: &AddBoxForm) -> Result<(), String> {
         // Validation
-        if form.title.trim().is_empty() {
+        if form.title.value().trim().is_empty() {
             return Err("Title cannot be empty".to_string());
         }
 
         let ip_addr = form
             .ip
+            .value()
+            .trim()
             .parse()
             .map_err(|_| "Invalid IP address".to_string())?;
 
@@ -168,6 +550,7 @@ impl App {
         // Parse tags
         let tags: Vec<String> = form
             .tags
+            .value()
             .split(',')
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
@@ -176,8 +559,8 @@ impl App {
         // Create new box
         let new_box = CtfBox {
             id: new_id,
-            title: form.title.trim().to_string(),
-            platform: form.platform.clone(),
+            title: form.title.value().trim().to_string(),
+            platform: form.platform.value().trim().to_string(),
             ip_address: ip_addr,
             tags,
             created_date: chrono::Utc::now(),
@@ -185,11 +568,22 @@ impl App {
             actions: Vec::new(),
             notes: Vec::new(),
             env_vars: HashMap::new(),
+            attachments: Vec::new(),
+            flags: Vec::new(),
+            sessions: Vec::new(),
+            checklists: Vec::new(),
+            hostnames: Vec::new(),
+            outages: Vec::new(),
         };
 
         self.boxes.push(new_box);
         self.view = AppView::List;
 
+        self.record_history("title", form.title.value());
+        self.record_history("platform", form.platform.value());
+        self.record_history("ip", form.ip.value());
+        self.record_history("tags", form.tags.value());
+
         Ok(())
     }
 
@@ -224,16 +618,15 @@ impl App {
         self.view = AppView::List;
     }
 
-    pub fn start_edit_env_vars(&mut self, box_id: i32) -> Option<EnvVarForm> {
+    /// Open the variables of a box with an empty add form
+    pub fn start_edit_env_vars(&mut self, box_id: i32) {
         if self.boxes.iter().any(|b| b.id == box_id) {
-            self.view = AppView::EditEnvVars(box_id);
-            Some(EnvVarForm {
-                key: String::new(),
-                value: String::new(),
+            let form = EnvVarForm {
+                key: TextInput::new().with_history(self.history("env_key")),
+                value: TextInput::new().with_history(self.history("env_value")),
                 current_field: 0,
-            })
-        } else {
-            None
+            };
+            self.view = AppView::EditEnvVars(box_id, Some(form));
         }
     }
 
@@ -243,11 +636,14 @@ impl App {
             return Err("Key cannot be empty".to_string());
         }
 
-        // Only alphanumeric and underscore allowed
-        if !key.chars().all(|c| c.is_alphanumeric() || c == '_') {
-            return Err("Key must be alphanumeric with underscores only".to_string());
+        // A shell variable name: ASCII letters, digits and underscores
+        if !crate::quote::is_name(key.trim()) {
+            return Err("Key must be alphanumeric with underscores only, not starting with a digit".to_string());
         }
 
+        self.record_history("env_key", &key);
+        self.record_history("env_value", &value);
+
         // Add to box
         if let Some(ctf_box) = self.boxes.iter_mut().find(|b| b.id == box_id) {
             ctf_box.env_vars.insert(key.trim().to_uppercase(), value);
@@ -272,14 +668,6 @@ impl App {
         }
     }
 
-    pub fn next_env_field(&mut self, form: &mut EnvVarForm) {
-        form.current_field = (form.current_field + 1) % 2;
-    }
-
-    pub fn previous_env_field(&mut self, form: &mut EnvVarForm) {
-        form.current_field = if form.current_field == 0 { 1 } else { 0 };
-    }
-
     // ========== Env Var Navigation ==========
 
     pub fn next_env_var(&mut self, box_id: i32) {
@@ -315,7 +703,9 @@ impl App {
             .iter_mut()
             .find(|b| b.id == box_id)
             .ok_or("Box not found")?;
-        let keys: Vec<String> = ctf_box.env_vars.keys().cloned().collect();
+        // Listed by name, a HashMap has no stable order
+        let mut keys: Vec<String> = ctf_box.env_vars.keys().cloned().collect();
+        keys.sort();
         if selected >= keys.len() {
             return Err("Invalid selection".to_string());
         }
@@ -335,7 +725,7 @@ impl App {
 
     pub fn start_edit_notes(&mut self, box_id: i32) {
         if self.boxes.iter().any(|b| b.id == box_id) {
-            self.view = AppView::EditNotes(box_id);
+            self.view = AppView::EditNotes(box_id, None);
             self.selected_note = None;
         }
     }
@@ -360,6 +750,7 @@ impl App {
                 category: categories[category_index].clone(),
                 content: content.trim().to_string(),
                 created_date: chrono::Utc::now(),
+                images: Vec::new(),
             });
             ctf_box.updated_date = chrono::Utc::now();
             Ok(())
@@ -368,6 +759,124 @@ impl App {
         }
     }
 
+    /// Open the category picker for a new note, or for the selected note when editing
+    pub fn start_note_form(&self, box_id: i32, edit_selected: bool) -> Result<NoteForm, String> {
+        if !edit_selected {
+            return Ok(NoteForm {
+                category_index: 0,
+                editing: None,
+            });
+        }
+
+        let selected = self.selected_note.ok_or("No note selected")?;
+        let note = self
+            .boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .and_then(|b| b.notes.get(selected))
+            .ok_or("Invalid selection")?;
+        let category_index = Self::note_categories()
+            .iter()
+            .position(|c| *c == note.category)
+            .unwrap_or(0);
+
+        Ok(NoteForm {
+            category_index,
+            editing: Some(selected),
+        })
+    }
+
+    /// Write the note in $EDITOR (the TUI must be suspended by the caller).
+    /// Returns None when the editor leaves the note empty.
+    pub fn compose_note(&self, box_id: i32, form: &NoteForm) -> Result<Option<String>, String> {
+        use std::io::Write;
+
+        let ctf_box = self
+            .boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        let categories = Self::note_categories();
+        let category = categories
+            .get(form.category_index)
+            .ok_or("Invalid category")?;
+        let initial = form
+            .editing
+            .and_then(|i| ctf_box.notes.get(i))
+            .map(|n| n.content.clone())
+            .unwrap_or_default();
+
+        let header = format!(
+            "{} {:?} note for {}. Save and quit to keep it, leave it empty to cancel. -->",
+            NOTE_HEADER, category, ctf_box.title
+        );
+        // Random name, created exclusively and readable by the user only
+        let mut file = tempfile::Builder::new()
+            .prefix("ctf-brain-note-")
+            .suffix(".md")
+            .tempfile()
+            .map_err(|e| format!("Failed to create temp note: {}", e))?;
+        write!(file, "{}\n{}", header, initial).map_err(|e| format!("Failed to write temp note: {}", e))?;
+        let path = file.path().to_path_buf();
+
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let mut parts = editor.split_whitespace();
+        let program = parts.next().unwrap_or("vi");
+        let status = Command::new(program)
+            .args(parts)
+            .arg(&path)
+            .status()
+            .map_err(|e| format!("Failed to spawn {}: {}", program, e));
+
+        // Read by path: editors may replace the file instead of writing to it
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read temp note: {}", e));
+        drop(file);
+
+        if !status?.success() {
+            return Err("Editor exited with an error, note discarded".to_string());
+        }
+        let content: String = content?
+            .lines()
+            .filter(|l| !l.starts_with(NOTE_HEADER))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let content = content.trim().to_string();
+
+        Ok(if content.is_empty() { None } else { Some(content) })
+    }
+
+    pub fn update_note(
+        &mut self,
+        box_id: i32,
+        index: usize,
+        category_index: usize,
+        content: String,
+    ) -> Result<(), String> {
+        if content.trim().is_empty() {
+            return Err("Content cannot be empty".to_string());
+        }
+
+        let categories = Self::note_categories();
+        let category = categories
+            .get(category_index)
+            .ok_or("Invalid category")?
+            .clone();
+
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        let note = ctf_box.notes.get_mut(index).ok_or("Invalid selection")?;
+        note.category = category;
+        note.content = content.trim().to_string();
+        ctf_box.updated_date = chrono::Utc::now();
+        Ok(())
+    }
+
     pub fn delete_selected_note(&mut self, box_id: i32) -> Result<(), String> {
         let selected = self.selected_note.ok_or("No note selected")?;
         let ctf_box = self
@@ -429,134 +938,1287 @@ impl App {
         ]
     }
 
-    // ========== Actions Import ==========
+    // ========== Dashboard ==========
 
-    /// Import actions from shell logs into the box
-    pub fn import_shell_logs(&mut self, box_id: i32) -> Result<usize, String> {
-        let actions = crate::storage::import_shell_logs(box_id)
-            .map_err(|e| format!("Failed to import logs: {}", e))?;
-        
-        let count = actions.len();
-        
-        if let Some(ctf_box) = self.boxes.iter_mut().find(|b| b.id == box_id) {
-            // Merge actions, avoiding duplicates based on timestamp and command
-            for action in actions {
-                let exists = ctf_box.actions.iter().any(|a| 
-                    a.timestamp == action.timestamp && a.command == action.command
-                );
-                if !exists {
-                    ctf_box.actions.push(action);
-                }
-            }
-            ctf_box.updated_date = chrono::Utc::now();
-            
-            // Sort actions by timestamp
-            ctf_box.actions.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-        }
-        
-        // Clear the logs after import
-        let _ = crate::storage::clear_shell_logs(box_id);
-        
-        Ok(count)
+    pub fn open_stats(&mut self) {
+        self.stats = Some(crate::stats::Stats::compute(&self.boxes));
+        self.view = AppView::Stats;
     }
 
-    // ========== Write-up Generation ==========
+    pub fn open_dashboard(&mut self) {
+        self.view = AppView::Dashboard;
+        self.dashboard_mode = true;
+        self.refresh_live_feed();
+    }
 
-    /// Start the write-up export flow with a default path
-    pub fn start_writeup_export(&mut self, box_id: i32) {
-        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) {
-            let filename = format!("{}-writeup.md",
-                ctf_box.title.to_lowercase().replace(' ', "-")
+    pub fn close_dashboard(&mut self) {
+        self.dashboard_mode = false;
+        self.view = AppView::List;
+    }
+
+    /// Re-read the shell logs of every box, without importing them
+    pub fn refresh_live_feed(&mut self) {
+        let mut live = Vec::new();
+        for ctf_box in &self.boxes {
+            let Ok(actions) = crate::storage::import_shell_logs(ctf_box.id, &self.config) else {
+                continue;
+            };
+            live.extend(
+                actions
+                    .into_iter()
+                    .filter(|action| {
+                        !ctf_box.actions.iter().any(|a| {
+                            a.timestamp == action.timestamp && a.command == action.command
+                        })
+                    })
+                    .map(|action| (ctf_box.id, action)),
             );
-            self.writeup_path = filename;
-            self.view = AppView::WriteupExport(box_id);
         }
+        self.dashboard.live = live;
+        self.dashboard.refreshed = Some(Instant::now());
     }
 
-    /// Generate and save a write-up for a box to the given path
-    pub fn generate_writeup(&mut self, box_id: i32) -> Result<std::path::PathBuf, String> {
-        let ctf_box = self
-            .boxes
+    /// Refresh the live feed if it is getting old
+    pub fn tick_dashboard(&mut self) {
+        let stale = self
+            .dashboard
+            .refreshed
+            .is_none_or(|when| when.elapsed().as_secs() >= LIVE_REFRESH_SECS);
+        if self.view == AppView::Dashboard && stale {
+            self.refresh_live_feed();
+        }
+    }
+
+    /// Re-read the running listeners. Returns the sessions that just ended
+    /// as (box, port): their transcript is in the box's log.
+    pub fn tick_listeners(&mut self) -> Vec<(i32, u16)> {
+        if self
+            .listeners_refreshed
+            .is_some_and(|when| when.elapsed().as_secs() < LIVE_REFRESH_SECS)
+        {
+            return Vec::new();
+        }
+        self.listeners_refreshed = Some(Instant::now());
+        let listeners = crate::listener::active(&self.config);
+        let ended: Vec<(i32, u16)> = self
+            .listeners
             .iter()
-            .find(|b| b.id == box_id)
-            .ok_or("Box not found")?;
+            .filter(|l| l.peer.is_some() && !listeners.iter().any(|n| n.pid == l.pid))
+            .map(|l| (l.box_id, l.port))
+            .collect();
+        self.listeners = listeners;
+        ended
+    }
 
-        let path = std::path::PathBuf::from(&self.writeup_path);
+    /// Re-read the tmux sessions in the background if the list is getting old
+    pub fn tick_tmux(&mut self) {
+        use std::sync::mpsc::TryRecvError;
 
-        // Create parent directories if needed
-        if let Some(parent) = path.parent() {
-            if !parent.as_os_str().is_empty() {
-                std::fs::create_dir_all(parent)
-                    .map_err(|e| format!("Failed to create directory: {}", e))?;
+        if let Some(listing) = &self.tmux_listing {
+            match listing.try_recv() {
+                Ok(sessions) => {
+                    self.tmux_listing = None;
+                    self.tmux_sessions = sessions;
+                    return;
+                }
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => self.tmux_listing = None,
             }
         }
 
-        let content = crate::storage::generate_writeup(ctf_box);
-        std::fs::write(&path, &content)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
+        let stale = self
+            .tmux_refreshed
+            .is_none_or(|when| when.elapsed().as_secs() >= LIVE_REFRESH_SECS);
+        if stale {
+            self.tmux_listing = Some(crate::tmux::spawn_sessions());
+            self.tmux_refreshed = Some(Instant::now());
+        }
+    }
 
-        Ok(path)
+    /// Re-read the tmux sessions now, after the user opened or ended one
+    pub fn refresh_tmux_sessions(&mut self) {
+        self.tmux_listing = None;
+        self.tmux_sessions = crate::tmux::sessions();
+        self.tmux_refreshed = Some(Instant::now());
     }
 
-    /// Launch a shell with the box environment loaded
-    /// This function replaces the current process with bash
-    /// Launch a shell with the box environment loaded
-    pub fn launch_box_shell(&self, box_id: i32) -> Result<(), String> {
+    /// Name of the box's tmux session, if it is running
+    pub fn tmux_session(&self, box_id: i32) -> Option<String> {
+        crate::tmux::find(&self.tmux_sessions, box_id)
+    }
+
+    /// Create or attach the box's tmux session (the TUI must be suspended
+    /// by the caller)
+    pub fn open_tmux(&self, box_id: i32) -> Result<(), String> {
         let ctf_box = self
             .boxes
             .iter()
             .find(|b| b.id == box_id)
             .ok_or("Box not found")?;
+        crate::tmux::open(ctf_box, &self.config)
+    }
 
-        crate::storage::create_box_environment(ctf_box)
-            .map_err(|e| format!("Failed to create environment: {}", e))?;
+    pub fn start_kill_tmux(&mut self, box_id: i32) {
+        if self.tmux_session(box_id).is_some() {
+            self.view = AppView::KillTmux(box_id);
+        } else {
+            self.set_status("No tmux session for this box", StatusKind::Info);
+        }
+    }
 
-        let env_file = dirs::home_dir()
-            .ok_or("No home directory")?
-            .join(format!(".ctf-brain/boxes/box-{}.env", box_id));
+    pub fn confirm_kill_tmux(&mut self, box_id: i32) -> Result<(), String> {
+        self.view = AppView::Details(box_id);
+        let name = self.tmux_session(box_id).ok_or("No tmux session for this box")?;
+        let killed = crate::tmux::kill(&name);
+        self.refresh_tmux_sessions();
+        killed
+    }
 
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
+    /// Box being worked on: the one shown, else the one selected in the list
+    pub fn current_box(&self) -> Option<i32> {
+        match &self.view {
+            AppView::Details(id)
+            | AppView::DeleteBox(id)
+            | AppView::KillTmux(id)
+            | AppView::EditEnvVars(id, _)
+            | AppView::EditNotes(id, _)
+            | AppView::WriteupExport(id)
+            | AppView::Loot(id)
+            | AppView::Checklists(id, _)
+            | AppView::Suggestions(id, _)
+            | AppView::Payloads(id)
+            | AppView::Snippets(id, _)
+            | AppView::Timeline(id)
+            | AppView::Hosts(Some(id), _) => Some(*id),
+            AppView::AddFlag(form) => Some(form.box_id),
+            AppView::List | AppView::Dashboard | AppView::Stats | AppView::AddBox(_) | AppView::Hosts(None, _) => self
+                .selected_box_id
+                .and_then(|idx| self.boxes.get(idx as usize))
+                .map(|b| b.id),
+        }
+    }
 
-        println!("\n\x1b[32m╔══════════════════════════════════════╗");
-        println!("║  🧠 CTF Brain Shell - {}", ctf_box.title);
-        println!("║  📡 IP: {}", ctf_box.ip_address);
-        println!("║  Tapez 'exit' pour revenir à CTF Brain");
-        println!("╚══════════════════════════════════════╝\x1b[0m\n");
+    /// Check the current box every `network.probe_interval_secs`, in the
+    /// background. Returns true when an outage started or ended.
+    pub fn tick_reachability(&mut self) -> bool {
+        use std::sync::mpsc::TryRecvError;
 
-        let status = if shell.contains("zsh") {
-            let boxes_dir = dirs::home_dir()
-                .ok_or("No home directory")?
-                .join(".ctf-brain/boxes");
-            let zdotdir = boxes_dir.join(format!("zsh-{}", box_id));
-            std::fs::create_dir_all(&zdotdir)
-                .map_err(|e| format!("Failed to create zdotdir: {}", e))?;
-
-            let custom_zshrc = format!(
-                "# Source user's original .zshrc\n\
-                 [ -f \"$HOME/.zshrc\" ] && source \"$HOME/.zshrc\"\n\
-                 # Then apply CTF Brain environment (overrides prompt)\n\
-                 source {}\n",
-                env_file.display()
-            );
-            std::fs::write(zdotdir.join(".zshrc"), custom_zshrc)
-                .map_err(|e| format!("Failed to write custom .zshrc: {}", e))?;
+        if let Some(probing) = &self.probing {
+            match probing.try_recv() {
+                Ok(probe) => {
+                    self.probing = None;
+                    return self.record_probe(probe);
+                }
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => self.probing = None,
+            }
+        }
 
-            Command::new(&shell)
-                .env("ZDOTDIR", &zdotdir)
-                .status()
-                .map_err(|e| format!("Failed to spawn zsh: {}", e))?
-        } else {
-            Command::new(&shell)
-                .arg("--rcfile")
-                .arg(&env_file)
-                .status()
-                .map_err(|e| format!("Failed to spawn bash: {}", e))?
+        let interval = self.config.network.probe_interval_secs;
+        let Some(ctf_box) = self
+            .current_box()
+            .and_then(|id| self.boxes.iter().find(|b| b.id == id))
+        else {
+            return false;
         };
+        // A box not checked yet goes first
+        let due = self.probed.is_none_or(|when| when.elapsed().as_secs() >= interval)
+            || !self.reachability.contains_key(&ctf_box.id);
+        if interval == 0 || !due {
+            return false;
+        }
+        let ports = crate::recon::box_services(ctf_box)
+            .into_iter()
+            .filter(|s| s.protocol == "tcp")
+            .map(|s| s.port)
+            .collect();
+        self.probing = Some(crate::reachability::spawn(
+            ctf_box.id,
+            ctf_box.ip_address,
+            ports,
+            self.config.network.lhost_interface.clone(),
+        ));
+        self.probed = Some(Instant::now());
+        false
+    }
 
-        if !status.success() {
-            return Err(format!("Shell exited with status: {}", status));
+    /// Keep the probe and open or close the box's outage. An outage opens
+    /// after `OUTAGE_AFTER_FAILURES` failed probes in a row, from the first.
+    fn record_probe(&mut self, probe: crate::reachability::Probe) -> bool {
+        if probe.is_up() {
+            self.failed_probes.remove(&probe.box_id);
+        } else {
+            self.failed_probes
+                .entry(probe.box_id)
+                .or_insert_with(|| (0, probe.clone()))
+                .0 += 1;
         }
 
-        Ok(())
+        let mut changed = false;
+        if let Some(ctf_box) = self.boxes.iter_mut().find(|b| b.id == probe.box_id) {
+            let ongoing = ctf_box.outages.last_mut().filter(|o| o.ended.is_none());
+            match (ongoing, probe.is_up()) {
+                (None, false) => {
+                    if let Some((failures, first)) = self.failed_probes.get(&probe.box_id)
+                        && *failures >= OUTAGE_AFTER_FAILURES
+                    {
+                        ctf_box.outages.push(crate::models::Outage {
+                            started: first.checked,
+                            ended: None,
+                            vpn_down: !first.vpn_up,
+                        });
+                        changed = true;
+                    }
+                }
+                (Some(outage), true) => {
+                    outage.ended = Some(probe.checked);
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+        self.reachability.insert(probe.box_id, probe);
+        changed
+    }
+
+    /// Actions of every box, newest first: (box, action, not imported yet)
+    pub fn action_feed(&self) -> Vec<(&CtfBox, &crate::models::Action, bool)> {
+        let mut feed: Vec<_> = self
+            .boxes
+            .iter()
+            .flat_map(|b| b.actions.iter().map(move |a| (b, a, false)))
+            .chain(self.dashboard.live.iter().filter_map(|(id, action)| {
+                let ctf_box = self.boxes.iter().find(|b| b.id == *id)?;
+                Some((ctf_box, action, true))
+            }))
+            .collect();
+        feed.sort_by_key(|entry| std::cmp::Reverse(entry.1.timestamp));
+        feed
+    }
+
+    pub fn focus_pane(&mut self, forward: bool) {
+        let panes = [DashboardPane::Boxes, DashboardPane::Info, DashboardPane::Feed];
+        let current = panes
+            .iter()
+            .position(|p| *p == self.dashboard.focus)
+            .unwrap_or(0);
+        let next = if forward { current + 1 } else { current + panes.len() - 1 };
+        self.dashboard.focus = panes[next % panes.len()];
+    }
+
+    /// Widen or narrow the focused pane by 5%, taking from (or giving to)
+    /// the widest (or narrowest) other pane
+    pub fn resize_pane(&mut self, grow: bool) {
+        const STEP: u16 = 5;
+        let widths = &mut self.dashboard.widths;
+        let focused = self.dashboard.focus as usize;
+        let others = (0..widths.len()).filter(|i| *i != focused);
+        if grow {
+            let Some(donor) = others.max_by_key(|i| widths[*i]) else {
+                return;
+            };
+            if widths[donor] >= MIN_PANE_WIDTH + STEP {
+                widths[donor] -= STEP;
+                widths[focused] += STEP;
+            }
+        } else if widths[focused] >= MIN_PANE_WIDTH + STEP {
+            let Some(receiver) = others.min_by_key(|i| widths[*i]) else {
+                return;
+            };
+            widths[receiver] += STEP;
+            widths[focused] -= STEP;
+        }
+    }
+
+    /// Down/Up in the dashboard move in the focused pane
+    pub fn dashboard_move(&mut self, down: bool) {
+        match self.dashboard.focus {
+            DashboardPane::Boxes => {
+                if down {
+                    self.next();
+                } else {
+                    self.previous();
+                }
+                self.dashboard.info_scroll = 0;
+            }
+            DashboardPane::Info => {
+                // Only the services table scrolls
+                let count = self
+                    .selected_box_id
+                    .and_then(|idx| self.boxes.get(idx as usize))
+                    .map_or(0, |b| crate::recon::box_services(b).len());
+                self.dashboard.info_scroll = scroll(self.dashboard.info_scroll, down, count);
+            }
+            DashboardPane::Feed => {
+                let count = self.action_feed().len();
+                self.dashboard.feed_scroll = scroll(self.dashboard.feed_scroll, down, count);
+            }
+        }
+    }
+
+    // ========== Flags ==========
+
+    pub fn start_add_flag(&mut self, box_id: i32) {
+        if self.boxes.iter().any(|b| b.id == box_id) {
+            self.view = AppView::AddFlag(FlagForm {
+                box_id,
+                kind: FlagKind::User,
+                value: TextInput::new(),
+                from_dashboard: self.view == AppView::Dashboard,
+            });
+        }
+    }
+
+    /// Record the flag, replacing a previous user or root flag
+    pub fn submit_flag(&mut self, form: &FlagForm) -> Result<(), String> {
+        let value = form.value.value().trim();
+        if value.is_empty() {
+            return Err("Flag cannot be empty".to_string());
+        }
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == form.box_id)
+            .ok_or("Box not found")?;
+        if form.kind != FlagKind::Other {
+            ctf_box.flags.retain(|f| f.kind != form.kind);
+        }
+        ctf_box.flags.push(Flag {
+            kind: form.kind,
+            value: value.to_string(),
+            captured_date: chrono::Utc::now(),
+        });
+        ctf_box.updated_date = chrono::Utc::now();
+        self.close_flag_form(form);
+        Ok(())
+    }
+
+    pub fn close_flag_form(&mut self, form: &FlagForm) {
+        self.view = if form.from_dashboard {
+            AppView::Dashboard
+        } else {
+            AppView::Details(form.box_id)
+        };
+    }
+
+    // ========== Actions Import ==========
+
+    /// Merge actions read from the shell logs into the box
+    pub fn merge_actions(&mut self, box_id: i32, actions: Vec<crate::models::Action>) -> usize {
+        let count = actions.len();
+        
+        if let Some(ctf_box) = self.boxes.iter_mut().find(|b| b.id == box_id) {
+            // Merge actions, avoiding duplicates based on timestamp and command
+            for action in actions {
+                let exists = ctf_box.actions.iter().any(|a| 
+                    a.timestamp == action.timestamp && a.command == action.command
+                );
+                if !exists {
+                    ctf_box.actions.push(action);
+                }
+            }
+            ctf_box.updated_date = chrono::Utc::now();
+            
+            // Sort actions by timestamp
+            ctf_box.actions.sort_by_key(|a| a.timestamp);
+        }
+        
+        count
+    }
+
+    // ========== Checklists ==========
+
+    pub fn start_checklists(&mut self, box_id: i32) {
+        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) {
+            self.selected_check = (check_count(ctf_box) > 0).then_some(0);
+            self.view = AppView::Checklists(box_id, None);
+        }
+    }
+
+    pub fn open_template_picker(&mut self, box_id: i32) {
+        let templates = crate::checklist::templates(&self.config);
+        if templates.is_empty() {
+            self.set_status("No checklist templates found", StatusKind::Error);
+            return;
+        }
+        self.view = AppView::Checklists(box_id, Some(TemplatePicker { templates, selected: 0 }));
+    }
+
+    pub fn move_template_picker(&mut self, down: bool) {
+        if let AppView::Checklists(_, Some(picker)) = &mut self.view {
+            let count = picker.templates.len();
+            picker.selected = if down {
+                (picker.selected + 1) % count
+            } else {
+                (picker.selected + count - 1) % count
+            };
+        }
+    }
+
+    /// Add the checklist picked in the template picker, ticking what the
+    /// logged commands already cover
+    pub fn add_picked_checklist(&mut self, box_id: i32) -> Result<String, String> {
+        let AppView::Checklists(_, Some(picker)) = &self.view else {
+            return Err("No template selected".to_string());
+        };
+        let template = picker.templates[picker.selected].clone();
+        self.view = AppView::Checklists(box_id, None);
+
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        if ctf_box.checklists.iter().any(|c| c.template == template.id) {
+            return Err(format!("{} is already on this box", template.name));
+        }
+        ctf_box.checklists.push(template.instantiate());
+        let ticked = crate::checklist::auto_tick(ctf_box);
+        ctf_box.updated_date = chrono::Utc::now();
+        self.selected_check = Some(check_count(ctf_box) - template.items.len());
+        Ok(format!("{} added ({} item(s) already done)", template.name, ticked))
+    }
+
+    /// Checklist and item indexes of the selected item
+    fn selected_check_position(&self, box_id: i32) -> Option<(usize, usize)> {
+        let ctf_box = self.boxes.iter().find(|b| b.id == box_id)?;
+        let mut index = self.selected_check?;
+        for (c, checklist) in ctf_box.checklists.iter().enumerate() {
+            if index < checklist.items.len() {
+                return Some((c, index));
+            }
+            index -= checklist.items.len();
+        }
+        None
+    }
+
+    pub fn toggle_selected_check(&mut self, box_id: i32) -> Result<(), String> {
+        let (c, i) = self
+            .selected_check_position(box_id)
+            .ok_or("No item selected")?;
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        let item = &mut ctf_box.checklists[c].items[i];
+        item.done = !item.done;
+        ctf_box.updated_date = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Remove the checklist of the selected item
+    pub fn delete_selected_checklist(&mut self, box_id: i32) -> Result<String, String> {
+        let (c, _) = self
+            .selected_check_position(box_id)
+            .ok_or("No checklist selected")?;
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        let removed = ctf_box.checklists.remove(c);
+        ctf_box.updated_date = chrono::Utc::now();
+        let count = check_count(ctf_box);
+        self.selected_check = match self.selected_check {
+            _ if count == 0 => None,
+            Some(i) => Some(i.min(count - 1)),
+            None => None,
+        };
+        Ok(format!("{} removed", removed.name))
+    }
+
+    pub fn next_check(&mut self, box_id: i32) {
+        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) {
+            let count = check_count(ctf_box);
+            if count == 0 {
+                return;
+            }
+            self.selected_check = Some(match self.selected_check {
+                Some(i) => (i + 1) % count,
+                None => 0,
+            });
+        }
+    }
+
+    pub fn previous_check(&mut self, box_id: i32) {
+        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) {
+            let count = check_count(ctf_box);
+            if count == 0 {
+                return;
+            }
+            self.selected_check = Some(match self.selected_check {
+                Some(0) | None => count - 1,
+                Some(i) => i - 1,
+            });
+        }
+    }
+
+    // ========== Exploits ==========
+
+    /// Add a Foothold note for each exploit candidate not noted yet.
+    /// Returns how many were added.
+    pub fn add_exploit_notes(&mut self, box_id: i32) -> Result<usize, String> {
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        let candidates = crate::exploits::candidates(ctf_box, &self.config);
+        if candidates.is_empty() {
+            return Err("No exploit candidates for the known service versions".to_string());
+        }
+
+        let mut added = 0;
+        for candidate in candidates {
+            let reference = candidate.exploit.reference();
+            if ctf_box.notes.iter().any(|n| n.content.contains(&reference)) {
+                continue;
+            }
+            ctf_box.notes.push(crate::models::Note {
+                category: crate::models::NoteCategory::Foothold,
+                content: candidate.note(),
+                created_date: chrono::Utc::now(),
+                images: Vec::new(),
+            });
+            added += 1;
+        }
+        if added > 0 {
+            ctf_box.updated_date = chrono::Utc::now();
+        }
+        Ok(added)
+    }
+
+    // ========== Suggestions ==========
+
+    /// Suggestions for the box's services, from the rules read when the
+    /// view opened
+    pub fn suggestions(&self, box_id: i32) -> Vec<crate::suggest::Suggestion> {
+        self.boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .map(|b| crate::suggest::for_box(b, &self.suggestion_rules, &self.config))
+            .unwrap_or_default()
+    }
+
+    pub fn start_suggestions(&mut self, box_id: i32) {
+        if self.boxes.iter().any(|b| b.id == box_id) {
+            self.suggestion_rules = crate::suggest::rules(&self.config);
+            let count = self.suggestions(box_id).len();
+            self.selected_suggestion = (count > 0).then_some(0);
+            self.view = AppView::Suggestions(box_id, None);
+        }
+    }
+
+    pub fn next_suggestion(&mut self, box_id: i32) {
+        let count = self.suggestions(box_id).len();
+        if count == 0 {
+            return;
+        }
+        self.selected_suggestion = Some(match self.selected_suggestion {
+            Some(i) => (i + 1) % count,
+            None => 0,
+        });
+    }
+
+    pub fn previous_suggestion(&mut self, box_id: i32) {
+        let count = self.suggestions(box_id).len();
+        if count == 0 {
+            return;
+        }
+        self.selected_suggestion = Some(match self.selected_suggestion {
+            Some(0) | None => count - 1,
+            Some(i) => i - 1,
+        });
+    }
+
+    /// Open the selected command in an input, to adjust it before it runs
+    pub fn edit_selected_suggestion(&mut self, box_id: i32) {
+        let suggestions = self.suggestions(box_id);
+        let Some(suggestion) = self.selected_suggestion.and_then(|i| suggestions.get(i)) else {
+            self.set_status("No suggestion selected", StatusKind::Error);
+            return;
+        };
+        self.view = AppView::Suggestions(box_id, Some(TextInput::with_value(&suggestion.command)));
+    }
+
+    // ========== Snippets ==========
+
+    pub fn start_snippets(&mut self, box_id: i32) {
+        let snippets = crate::snippets::snippets(&self.config);
+        if snippets.is_empty() {
+            self.set_status("No snippets found", StatusKind::Error);
+            return;
+        }
+        self.view = AppView::Snippets(
+            box_id,
+            SnippetPicker {
+                snippets,
+                query: TextInput::new(),
+                selected: 0,
+                form: None,
+            },
+        );
+    }
+
+    /// Snippets matching the query, best first, with the matched positions
+    pub fn snippet_matches(picker: &SnippetPicker) -> Vec<(&crate::snippets::Snippet, Vec<usize>)> {
+        crate::fuzzy::rank(picker.query.value(), &picker.snippets, |s| s.search_text())
+    }
+
+    pub fn move_snippet_selection(&mut self, down: bool) {
+        if let AppView::Snippets(_, picker) = &mut self.view {
+            let count = Self::snippet_matches(picker).len();
+            if count == 0 {
+                return;
+            }
+            picker.selected = if down {
+                (picker.selected + 1) % count
+            } else {
+                (picker.selected + count - 1) % count
+            };
+        }
+    }
+
+    /// Values of the box for the placeholders
+    pub fn snippet_values(&self, box_id: i32) -> HashMap<String, String> {
+        self.boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .map(|b| crate::snippets::box_variables(b, &self.config))
+            .unwrap_or_default()
+    }
+
+    /// Pick the selected snippet: its command when the box fills every
+    /// placeholder, otherwise the form asking for the others opens
+    pub fn pick_snippet(&mut self, box_id: i32) -> Option<String> {
+        let values = self.snippet_values(box_id);
+        let history = self.history("snippet");
+        let AppView::Snippets(_, picker) = &mut self.view else {
+            return None;
+        };
+        let snippet = Self::snippet_matches(picker)
+            .get(picker.selected)
+            .map(|(snippet, _)| (*snippet).clone())?;
+        let missing = crate::snippets::missing(&snippet.command, &values);
+        if missing.is_empty() {
+            return Some(crate::snippets::fill(&snippet.command, &values));
+        }
+        let fields = missing
+            .into_iter()
+            .map(|name| (name, TextInput::new().with_history(history.clone())))
+            .collect();
+        picker.form = Some(SnippetForm {
+            snippet,
+            fields,
+            current: 0,
+        });
+        None
+    }
+
+    /// Enter in the placeholder form: next field, or the filled command
+    /// after the last one
+    pub fn submit_snippet_field(&mut self, box_id: i32) -> Option<String> {
+        let values = self.snippet_values(box_id);
+        let AppView::Snippets(_, picker) = &mut self.view else {
+            return None;
+        };
+        let form = picker.form.as_mut()?;
+        if form.current + 1 < form.fields.len() {
+            form.current += 1;
+            return None;
+        }
+        if let Some((name, _)) = form.fields.iter().find(|(_, input)| input.value().is_empty()) {
+            let message = format!("No value for <{}>", name);
+            self.set_status(message, StatusKind::Error);
+            return None;
+        }
+        let command = form.preview(&values);
+        let entered: Vec<String> = form.fields.iter().map(|(_, i)| i.value().to_string()).collect();
+        picker.form = None;
+        for value in entered {
+            self.record_history("snippet", &value);
+        }
+        Some(command)
+    }
+
+    // ========== Payloads ==========
+
+    pub fn start_payloads(&mut self, box_id: i32) {
+        let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) else {
+            return;
+        };
+        self.lhost = crate::payloads::lhost(ctf_box, &self.config);
+        self.payloads = crate::payloads::payloads(&self.config);
+        self.selected_payload = (!self.payloads.is_empty()).then_some(0);
+        self.view = AppView::Payloads(box_id);
+    }
+
+    pub fn next_payload(&mut self) {
+        let count = self.payloads.len();
+        if count == 0 {
+            return;
+        }
+        self.selected_payload = Some(match self.selected_payload {
+            Some(i) => (i + 1) % count,
+            None => 0,
+        });
+    }
+
+    pub fn previous_payload(&mut self) {
+        let count = self.payloads.len();
+        if count == 0 {
+            return;
+        }
+        self.selected_payload = Some(match self.selected_payload {
+            Some(0) | None => count - 1,
+            Some(i) => i - 1,
+        });
+    }
+
+    pub fn cycle_payload_encoding(&mut self) {
+        self.payload_encoding = self.payload_encoding.next();
+    }
+
+    /// A payload for the box's listener, in the current encoding. Without
+    /// a detected address, `LHOST` is left for the user to replace.
+    pub fn payload_text(&self, box_id: i32, payload: &crate::payloads::Payload) -> String {
+        let lport = self
+            .boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .map(|b| crate::payloads::lport(b, &self.config))
+            .unwrap_or_else(|| self.config.network.listener_port.to_string());
+        let lhost = self.lhost.as_deref().unwrap_or("LHOST");
+        self.payload_encoding.encode(&payload.fill(lhost, &lport))
+    }
+
+    pub fn copy_selected_payload(&mut self, box_id: i32) {
+        let Some(payload) = self.selected_payload.and_then(|i| self.payloads.get(i)) else {
+            self.set_status("No payload selected", StatusKind::Error);
+            return;
+        };
+        let text = self.payload_text(box_id, payload);
+        let name = payload.name.clone();
+        match crate::clipboard::copy(&text) {
+            Ok(via) => self.set_status(format!("Copied {} ({})", name, via), StatusKind::Success),
+            Err(e) => self.set_status(e, StatusKind::Error),
+        }
+    }
+
+    // ========== Hosts file ==========
+
+    pub fn start_hosts(&mut self, box_id: Option<i32>) {
+        self.hosts_content = crate::hosts::read(&self.config.hosts_path());
+        self.view = AppView::Hosts(box_id, None);
+    }
+
+    /// How the ctf-brain block of the hosts file would change
+    pub fn hosts_preview(&self) -> Vec<(char, String)> {
+        let current = self
+            .hosts_content
+            .as_deref()
+            .map(crate::hosts::current_entries)
+            .unwrap_or_default();
+        crate::hosts::preview(&current, &crate::hosts::entries(&self.boxes))
+    }
+
+    /// The hosts file does not match the boxes' hostnames (read again)
+    pub fn hosts_out_of_date(&self) -> bool {
+        crate::hosts::read(&self.config.hosts_path()).is_ok_and(|content| {
+            crate::hosts::current_entries(&content) != crate::hosts::entries(&self.boxes)
+        })
+    }
+
+    /// Open the hostnames of the box in an input
+    pub fn edit_hostnames(&mut self, box_id: i32) {
+        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) {
+            let input = TextInput::with_value(ctf_box.hostnames.join(", "));
+            self.view = AppView::Hosts(Some(box_id), Some(input));
+        }
+    }
+
+    /// Replace the box's hostnames with the ones typed
+    pub fn submit_hostnames(&mut self, box_id: i32) -> Result<(), String> {
+        let AppView::Hosts(_, Some(input)) = &self.view else {
+            return Err("No hostnames typed".to_string());
+        };
+        let hostnames = crate::hosts::parse_hostnames(input.value())?;
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        ctf_box.hostnames = hostnames;
+        ctf_box.updated_date = chrono::Utc::now();
+        self.view = AppView::Hosts(Some(box_id), None);
+        Ok(())
+    }
+
+    /// The hosts file with the block regenerated, None when nothing changes
+    pub fn updated_hosts(&self) -> Result<Option<String>, String> {
+        let content = self.hosts_content.clone()?;
+        let updated = crate::hosts::update(&content, &crate::hosts::entries(&self.boxes))?;
+        Ok((updated != content).then_some(updated))
+    }
+
+    // ========== Loot ==========
+
+    /// Open the loot view, pulling in files added from the CLI or the box shell
+    pub fn start_loot_view(&mut self, box_id: i32) {
+        if self.boxes.iter().any(|b| b.id == box_id) {
+            self.view = AppView::Loot(box_id);
+            self.selected_attachment = None;
+        }
+    }
+
+    /// Show the box's loot as read from its index, returns how many were new
+    pub fn set_loot(&mut self, box_id: i32, index: Vec<crate::models::Attachment>) -> Result<usize, String> {
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+
+        let added = index.len().saturating_sub(ctf_box.attachments.len());
+        if added > 0 {
+            ctf_box.updated_date = chrono::Utc::now();
+        }
+        ctf_box.attachments = index;
+        Ok(added)
+    }
+
+    /// Add notes queued from outside the TUI (screenshots), returns how many
+    pub fn merge_notes(&mut self, box_id: i32, notes: Vec<crate::models::Note>) -> Result<usize, String> {
+        let count = notes.len();
+
+        if let Some(ctf_box) = self.boxes.iter_mut().find(|b| b.id == box_id) {
+            if count > 0 {
+                ctf_box.notes.extend(notes);
+                ctf_box.updated_date = chrono::Utc::now();
+            }
+            Ok(count)
+        } else {
+            Err("Box not found".to_string())
+        }
+    }
+
+    /// Merge everything recorded outside the TUI for a box (loot, queued
+    /// notes, shell logs). Returns how many commands were imported.
+    pub fn merge_activity(&mut self, box_id: i32, activity: crate::storage::BoxActivity) -> usize {
+        let _ = self.set_loot(box_id, activity.loot);
+        let _ = self.merge_notes(box_id, activity.notes);
+        let count = self.merge_actions(box_id, activity.actions);
+        if let Some(ctf_box) = self.boxes.iter_mut().find(|b| b.id == box_id) {
+            crate::checklist::auto_tick(ctf_box);
+        }
+        count
+    }
+
+    /// Remember a shell session that just ended, for the timeline
+    pub fn record_session(&mut self, box_id: i32, started: chrono::DateTime<chrono::Utc>) {
+        if let Some(ctf_box) = self.boxes.iter_mut().find(|b| b.id == box_id) {
+            ctf_box.sessions.push(crate::models::ShellSession {
+                started,
+                ended: chrono::Utc::now(),
+            });
+        }
+    }
+
+    pub fn next_attachment(&mut self, box_id: i32) {
+        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) {
+            let count = ctf_box.attachments.len();
+            if count == 0 {
+                return;
+            }
+            self.selected_attachment = Some(match self.selected_attachment {
+                Some(i) => (i + 1) % count,
+                None => 0,
+            });
+        }
+    }
+
+    pub fn previous_attachment(&mut self, box_id: i32) {
+        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) {
+            let count = ctf_box.attachments.len();
+            if count == 0 {
+                return;
+            }
+            self.selected_attachment = Some(match self.selected_attachment {
+                Some(0) | None => count.saturating_sub(1),
+                Some(i) => i - 1,
+            });
+        }
+    }
+
+    /// The selected attachment and the path of its blob
+    pub fn selected_attachment_file(
+        &self,
+        box_id: i32,
+    ) -> Result<(crate::models::Attachment, std::path::PathBuf), String> {
+        let selected = self.selected_attachment.ok_or("No file selected")?;
+        let ctf_box = self
+            .boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        let attachment = ctf_box
+            .attachments
+            .get(selected)
+            .ok_or("Invalid selection")?
+            .clone();
+        let path = crate::storage::loot_blob_path(box_id, &attachment.sha256, &self.config)
+            .map_err(|e| e.to_string())?;
+        Ok((attachment, path))
+    }
+
+    /// Open the selected attachment in $PAGER, as a hex dump for binary files
+    pub fn open_attachment(&self, box_id: i32, force_hex: bool) -> Result<(), String> {
+        let (attachment, path) = self.selected_attachment_file(box_id)?;
+
+        let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+        let mut parts = pager.split_whitespace();
+        let program = parts.next().unwrap_or("less");
+        let mut cmd = Command::new(program);
+        cmd.args(parts);
+
+        let status = if attachment.is_text() && !force_hex {
+            cmd.arg(&path)
+                .status()
+                .map_err(|e| format!("Failed to spawn {}: {}", program, e))?
+        } else {
+            let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+            let mut child = cmd
+                .stdin(std::process::Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("Failed to spawn {}: {}", program, e))?;
+            if let Some(mut stdin) = child.stdin.take() {
+                use std::io::Write;
+                // The pager may quit before reading everything, ignore broken pipes
+                let _ = stdin.write_all(crate::storage::hexdump(&bytes).as_bytes());
+            }
+            child
+                .wait()
+                .map_err(|e| format!("Failed to wait for {}: {}", program, e))?
+        };
+
+        if !status.success() {
+            return Err(format!("Pager exited with status: {}", status));
+        }
+        Ok(())
+    }
+
+    pub fn delete_selected_attachment(&mut self, box_id: i32) -> Result<(), String> {
+        let selected = self.selected_attachment.ok_or("No file selected")?;
+        let ctf_box = self
+            .boxes
+            .iter_mut()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        if selected >= ctf_box.attachments.len() {
+            return Err("Invalid selection".to_string());
+        }
+        ctf_box.attachments = crate::storage::remove_loot(box_id, &ctf_box.attachments[selected], &self.config)
+            .map_err(|e| format!("Failed to remove file: {}", e))?;
+        ctf_box.updated_date = chrono::Utc::now();
+
+        let new_count = ctf_box.attachments.len();
+        if new_count == 0 {
+            self.selected_attachment = None;
+        } else if selected >= new_count {
+            self.selected_attachment = Some(new_count - 1);
+        }
+        Ok(())
+    }
+
+    // ========== Timeline ==========
+
+    pub fn start_timeline(&mut self, box_id: i32) {
+        if self.boxes.iter().any(|b| b.id == box_id) {
+            self.view = AppView::Timeline(box_id);
+            self.timeline_scroll = 0;
+        }
+    }
+
+    fn timeline_len(&self, box_id: i32) -> usize {
+        self.boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .map_or(0, |b| crate::timeline::build(b).len())
+    }
+
+    pub fn scroll_timeline(&mut self, box_id: i32, down: bool) {
+        self.timeline_scroll = scroll(self.timeline_scroll, down, self.timeline_len(box_id));
+    }
+
+    /// Start the write-up export flow with a default path
+    pub fn start_writeup_export(&mut self, box_id: i32) {
+        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) {
+            let filename = format!("{}-writeup.md",
+                ctf_box.title.to_lowercase().replace(' ', "-")
+            );
+            self.writeup_path = TextInput::with_value(filename).with_history(self.history("writeup_path"));
+            self.view = AppView::WriteupExport(box_id);
+            self.refresh_redaction_preview(box_id);
+        }
+    }
+
+    /// Cycle the redaction mode used by exports (Off → Placeholder → Mask)
+    pub fn cycle_redact_mode(&mut self, box_id: i32) {
+        self.redact_mode = self.redact_mode.next();
+        self.refresh_redaction_preview(box_id);
+    }
+
+    /// Recompute the list of secrets the export would redact
+    pub fn refresh_redaction_preview(&mut self, box_id: i32) {
+        self.redaction_preview = Vec::new();
+        self.redaction_problems = Vec::new();
+        if let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id)
+            && self.redact_mode != RedactMode::Off
+        {
+            let redactor = Redactor::for_box(ctf_box, self.redact_mode, &self.config);
+            self.redaction_preview = redactor.preview(&crate::storage::generate_writeup(ctf_box));
+            self.redaction_problems = redactor.problems;
+        }
+    }
+
+    /// Apply the current redaction mode to text leaving the app
+    pub fn redact_export(&self, ctf_box: &CtfBox, text: &str) -> String {
+        Redactor::for_box(ctf_box, self.redact_mode, &self.config).redact(text)
+    }
+
+    /// Generate and save a write-up for a box to the given path
+    pub fn generate_writeup(&mut self, box_id: i32) -> Result<std::path::PathBuf, String> {
+        let ctf_box = self
+            .boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+
+        let path = crate::ui::input::expand_home_path(self.writeup_path.value());
+
+        // Create parent directories if needed
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        let content = self.redact_export(ctf_box, &crate::storage::generate_writeup(ctf_box));
+        std::fs::write(&path, &content)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+
+        // Copy screenshots referenced by notes next to the write-up
+        let images: Vec<&crate::models::Attachment> = ctf_box
+            .attachments
+            .iter()
+            .filter(|a| ctf_box.notes.iter().any(|n| n.images.contains(&a.sha256)))
+            .collect();
+        if !images.is_empty() {
+            let images_dir = path
+                .parent()
+                .unwrap_or(std::path::Path::new(""))
+                .join("images");
+            std::fs::create_dir_all(&images_dir)
+                .map_err(|e| format!("Failed to create images directory: {}", e))?;
+            for attachment in images {
+                let blob = crate::storage::loot_blob_path(box_id, &attachment.sha256, &self.config)
+                    .map_err(|e| e.to_string())?;
+                std::fs::copy(&blob, images_dir.join(crate::storage::writeup_image_name(attachment)))
+                    .map_err(|e| format!("Failed to copy {}: {}", attachment.name, e))?;
+            }
+        }
+
+        let typed = self.writeup_path.value().to_string();
+        self.record_history("writeup_path", &typed);
+
+        Ok(path)
+    }
+
+    /// Launch a shell with the box environment loaded
+    pub fn launch_box_shell(&self, box_id: i32) -> Result<(), String> {
+        let ctf_box = self
+            .boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+        let mut command = crate::storage::shell_command(ctf_box, &self.config)
+            .map_err(|e| format!("Failed to prepare the shell: {}", e))?;
+
+        println!("\n\x1b[32m╔══════════════════════════════════════╗");
+        println!("║  🧠 CTF Brain Shell - {}", ctf_box.title);
+        println!("║  📡 IP: {}", ctf_box.ip_address);
+        println!("║  Tapez 'exit' pour revenir à CTF Brain");
+        println!("╚══════════════════════════════════════╝\x1b[0m\n");
+
+        let status = command
+            .status()
+            .map_err(|e| format!("Failed to spawn {}: {}", command.get_program().display(), e))?;
+        if !status.success() {
+            return Err(format!("Shell exited with status: {}", status));
+        }
+
+        Ok(())
+    }
+
+    /// Run a command through the `ctf` wrapper in the box environment, so it
+    /// is logged like the ones typed in the box shell. Waits for Enter before
+    /// returning to the TUI so the output can be read.
+    pub fn run_in_box_shell(&self, box_id: i32, command: &str) -> Result<(), String> {
+        let ctf_box = self
+            .boxes
+            .iter()
+            .find(|b| b.id == box_id)
+            .ok_or("Box not found")?;
+
+        crate::storage::create_box_environment(ctf_box, &self.config)
+            .map_err(|e| format!("Failed to create environment: {}", e))?;
+        let env_file = crate::storage::box_env_path(box_id, crate::storage::ShellKind::Bash, &self.config)
+            .map_err(|e| format!("Failed to locate environment: {}", e))?;
+
+        // The env file prints a welcome banner, keep only the command output.
+        // bash whatever the configured shell: the wrapper relies on PIPESTATUS.
+        let script = format!(
+            "source \"$1\" >/dev/null\nctf {}\necho\nread -r -p 'Press Enter to return to CTF Brain '",
+            command
+        );
+        Command::new("bash")
+            .arg("-c")
+            .arg(script)
+            .arg("ctf-brain")
+            .arg(&env_file)
+            .status()
+            .map_err(|e| format!("Failed to spawn bash: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Move a scroll offset by one line, within `0..count`
+fn scroll(offset: usize, down: bool, count: usize) -> usize {
+    if down {
+        (offset + 1).min(count.saturating_sub(1))
+    } else {
+        offset.saturating_sub(1)
+    }
+}
+
+/// Items of all the checklists of a box
+fn check_count(ctf_box: &CtfBox) -> usize {
+    ctf_box.checklists.iter().map(|c| c.items.len()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reachability::{Health, Probe};
+    use chrono::{Duration, Utc};
+
+    fn app() -> App {
+        App::new(vec![CtfBox::sample(1, "Lame")], Config::default())
+    }
+
+    fn probe(up: bool, minutes: i64) -> Probe {
+        Probe {
+            box_id: 1,
+            checked: Utc::now() + Duration::minutes(minutes),
+            health: if up {
+                Health::Up {
+                    via: "22/tcp".to_string(),
+                    rtt: std::time::Duration::from_millis(30),
+                }
+            } else {
+                Health::Down
+            },
+            vpn_up: true,
+        }
+    }
+
+    #[test]
+    fn single_failed_probe_opens_no_outage() {
+        let mut app = app();
+        assert!(!app.record_probe(probe(false, 0)));
+        assert!(!app.record_probe(probe(true, 1)));
+        assert!(!app.record_probe(probe(false, 2)));
+        assert!(!app.record_probe(probe(false, 3)));
+        assert!(app.boxes[0].outages.is_empty());
+    }
+
+    #[test]
+    fn outage_opens_from_the_first_of_consecutive_failures() {
+        let mut app = app();
+        let first = probe(false, 0);
+        assert!(!app.record_probe(first.clone()));
+        assert!(!app.record_probe(probe(false, 1)));
+        assert!(app.record_probe(probe(false, 2)));
+
+        let outages = &app.boxes[0].outages;
+        assert_eq!(outages.len(), 1);
+        assert_eq!(outages[0].started, first.checked);
+        assert_eq!(outages[0].ended, None);
+
+        // Still down: the same outage goes on
+        assert!(!app.record_probe(probe(false, 3)));
+        assert_eq!(app.boxes[0].outages.len(), 1);
+    }
+
+    #[test]
+    fn outage_closes_on_the_first_answer() {
+        let mut app = app();
+        for minute in 0..3 {
+            app.record_probe(probe(false, minute));
+        }
+        let back = probe(true, 5);
+        assert!(app.record_probe(back.clone()));
+        assert_eq!(app.boxes[0].outages[0].ended, Some(back.checked));
+        assert!(app.failed_probes.is_empty());
+
+        // A new streak is needed for the next one
+        assert!(!app.record_probe(probe(false, 6)));
+        assert_eq!(app.boxes[0].outages.len(), 1);
+    }
+
+    #[test]
+    fn vpn_down_is_taken_from_the_first_failure() {
+        let mut app = app();
+        let mut first = probe(false, 0);
+        first.vpn_up = false;
+        app.record_probe(first);
+        app.record_probe(probe(false, 1));
+        app.record_probe(probe(false, 2));
+        assert!(app.boxes[0].outages[0].vpn_down);
+    }
+
+    #[test]
+    fn imported_commands_tick_the_box_checklists() {
+        let mut app = app();
+        let dir = tempfile::tempdir().unwrap();
+        app.config.paths.base_dir = dir.path().to_string_lossy().to_string();
+        let smb = crate::checklist::templates(&app.config)
+            .into_iter()
+            .find(|t| t.id == "smb")
+            .unwrap();
+        app.boxes[0].checklists.push(smb.instantiate());
+
+        let action = |command: &str| crate::models::Action {
+            timestamp: Utc::now(),
+            command: command.to_string(),
+            result: crate::models::ActionResult::Success,
+            note: None,
+            output: None,
+        };
+        let activity = crate::storage::BoxActivity {
+            actions: vec![action("whoami"), action("enum4linux -a 10.10.10.5")],
+            ..Default::default()
+        };
+        assert_eq!(app.merge_activity(1, activity), 2);
+
+        let done: Vec<&str> = app.boxes[0].checklists[0]
+            .items
+            .iter()
+            .filter(|i| i.done)
+            .map(|i| i.text.as_str())
+            .collect();
+        assert_eq!(done, ["Enumerate with enum4linux"]);
     }
 }
@@ -0,0 +1,277 @@
+use crate::config::Config;
+use crate::models::{Checklist, ChecklistItem, CtfBox};
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+
+/// Templates shipped with ctf-brain (`data/checklists`)
+const BUILTIN: &[&str] = &[
+    include_str!("../data/checklists/linux-privesc.toml"),
+    include_str!("../data/checklists/windows-privesc.toml"),
+    include_str!("../data/checklists/web-enum.toml"),
+    include_str!("../data/checklists/active-directory.toml"),
+    include_str!("../data/checklists/smb.toml"),
+    include_str!("../data/checklists/ftp.toml"),
+    include_str!("../data/checklists/http.toml"),
+];
+
+/// A reusable checklist, as written in a data file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Template {
+    pub id: String,
+    pub name: String,
+    pub items: Vec<TemplateItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TemplateItem {
+    pub text: String,
+    /// Regexes (case-insensitive) of the commands that tick the item
+    #[serde(default)]
+    pub auto: Vec<String>,
+}
+
+impl Template {
+    /// Copy of the template for a box, with nothing ticked
+    pub fn instantiate(&self) -> Checklist {
+        Checklist {
+            template: self.id.clone(),
+            name: self.name.clone(),
+            items: self
+                .items
+                .iter()
+                .map(|item| ChecklistItem {
+                    text: item.text.clone(),
+                    auto: item.auto.clone(),
+                    done: false,
+                    matched: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Built-in templates, then the user's from `checklists/*.toml` in the
+/// base dir (`~/.ctf-brain` by default). A user template with the id of a
+/// built-in one replaces it. Invalid files are skipped.
+pub fn templates(config: &Config) -> Vec<Template> {
+    let mut templates: Vec<Template> = BUILTIN
+        .iter()
+        .filter_map(|content| toml::from_str(content).ok())
+        .collect();
+
+    let mut user_files: Vec<_> = std::fs::read_dir(config.base_dir().join("checklists"))
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    user_files.sort();
+
+    for path in user_files {
+        let Some(template) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| toml::from_str::<Template>(&content).ok())
+        else {
+            continue;
+        };
+        match templates.iter_mut().find(|t| t.id == template.id) {
+            Some(existing) => *existing = template,
+            None => templates.push(template),
+        }
+    }
+    templates
+}
+
+fn compile(pattern: &str) -> Option<Regex> {
+    RegexBuilder::new(pattern).case_insensitive(true).build().ok()
+}
+
+/// Tick the items of the box's checklists matched by a logged command.
+/// Returns how many were ticked.
+pub fn auto_tick(ctf_box: &mut CtfBox) -> usize {
+    let mut ticked = 0;
+    for item in ctf_box.checklists.iter_mut().flat_map(|c| c.items.iter_mut()) {
+        if item.done || item.matched.is_some() {
+            continue;
+        }
+        let patterns: Vec<Regex> = item.auto.iter().filter_map(|p| compile(p)).collect();
+        if let Some(action) = ctf_box
+            .actions
+            .iter()
+            .find(|a| patterns.iter().any(|re| re.is_match(&a.command)))
+        {
+            item.done = true;
+            item.matched = Some(action.command.clone());
+            ticked += 1;
+        }
+    }
+    ticked
+}
+
+/// Methodology section of the write-up
+pub fn markdown(ctf_box: &CtfBox) -> String {
+    let mut md = String::new();
+    for checklist in &ctf_box.checklists {
+        md.push_str(&format!(
+            "### {} ({}/{})\n\n",
+            checklist.name,
+            checklist.done_count(),
+            checklist.items.len()
+        ));
+        for item in &checklist.items {
+            let mark = if item.done { "x" } else { " " };
+            match (&item.matched, item.done) {
+                (Some(command), true) => {
+                    md.push_str(&format!("- [{}] {} (`{}`)\n", mark, item.text, command))
+                }
+                _ => md.push_str(&format!("- [{}] {}\n", mark, item.text)),
+            }
+        }
+        md.push('\n');
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Action, ActionResult};
+
+    fn config(base_dir: &std::path::Path) -> Config {
+        let mut config = Config::default();
+        config.paths.base_dir = base_dir.to_string_lossy().to_string();
+        config
+    }
+
+    fn builtin(id: &str) -> Template {
+        let dir = tempfile::tempdir().unwrap();
+        templates(&config(dir.path()))
+            .into_iter()
+            .find(|t| t.id == id)
+            .unwrap()
+    }
+
+    fn log(ctf_box: &mut CtfBox, command: &str) {
+        ctf_box.actions.push(Action {
+            timestamp: chrono::Utc::now(),
+            command: command.to_string(),
+            result: ActionResult::Success,
+            note: None,
+            output: None,
+        });
+    }
+
+    fn ticked(ctf_box: &CtfBox) -> Vec<(&str, Option<&str>)> {
+        ctf_box.checklists[0]
+            .items
+            .iter()
+            .filter(|i| i.done)
+            .map(|i| (i.text.as_str(), i.matched.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn builtin_templates_parse_with_valid_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        let templates = templates(&config(dir.path()));
+        assert_eq!(templates.len(), BUILTIN.len());
+
+        let mut ids: Vec<&str> = templates.iter().map(|t| t.id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), templates.len());
+
+        for template in &templates {
+            assert!(!template.items.is_empty(), "{}", template.id);
+            for pattern in template.items.iter().flat_map(|i| &i.auto) {
+                assert!(compile(pattern).is_some(), "{}: {}", template.id, pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn user_templates_replace_or_add_and_broken_ones_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("checklists");
+        std::fs::create_dir(&user).unwrap();
+        std::fs::write(
+            user.join("smb.toml"),
+            "id = \"smb\"\nname = \"My SMB\"\n[[items]]\ntext = \"Shares\"\nauto = ['smbmap']\n",
+        )
+        .unwrap();
+        std::fs::write(user.join("redis.toml"), "id = \"redis\"\nname = \"Redis\"\nitems = []\n").unwrap();
+        std::fs::write(user.join("broken.toml"), "id = \"broken\"\nname = ").unwrap();
+        std::fs::write(user.join("notes.txt"), "id = \"txt\"\nname = \"Txt\"\nitems = []\n").unwrap();
+
+        let templates = templates(&config(dir.path()));
+        assert_eq!(templates.len(), BUILTIN.len() + 1);
+        let smb = templates.iter().find(|t| t.id == "smb").unwrap();
+        assert_eq!(smb.name, "My SMB");
+        assert_eq!(smb.items.len(), 1);
+        assert_eq!(templates.last().unwrap().id, "redis");
+    }
+
+    #[test]
+    fn logged_commands_tick_the_matching_items_only() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.checklists.push(builtin("smb").instantiate());
+        log(&mut ctf_box, "nmap -sV 10.10.10.5");
+        log(&mut ctf_box, "SMBCLIENT -N -L //10.10.10.5");
+        log(&mut ctf_box, "enum4linux -a 10.10.10.5");
+        log(&mut ctf_box, "smbclient -N -L //10.10.10.5/tmp");
+
+        assert_eq!(auto_tick(&mut ctf_box), 2);
+        assert_eq!(
+            ticked(&ctf_box),
+            [
+                ("List shares anonymously", Some("SMBCLIENT -N -L //10.10.10.5")),
+                ("Enumerate with enum4linux", Some("enum4linux -a 10.10.10.5")),
+            ]
+        );
+        // Nothing new: nothing more is ticked
+        assert_eq!(auto_tick(&mut ctf_box), 0);
+    }
+
+    #[test]
+    fn items_unticked_by_hand_stay_unticked() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.checklists.push(builtin("smb").instantiate());
+        log(&mut ctf_box, "smbmap -H 10.10.10.5");
+        auto_tick(&mut ctf_box);
+        ctf_box.checklists[0].items[0].done = false;
+
+        log(&mut ctf_box, "smbclient -L //10.10.10.5");
+        assert_eq!(auto_tick(&mut ctf_box), 0);
+        assert!(ticked(&ctf_box).is_empty());
+    }
+
+    #[test]
+    fn invalid_patterns_tick_nothing() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.checklists.push(Checklist {
+            template: "custom".to_string(),
+            name: "Custom".to_string(),
+            items: vec![ChecklistItem {
+                text: "Broken".to_string(),
+                auto: vec!["(".to_string()],
+                done: false,
+                matched: None,
+            }],
+        });
+        log(&mut ctf_box, "(");
+        assert_eq!(auto_tick(&mut ctf_box), 0);
+    }
+
+    #[test]
+    fn markdown_marks_the_done_items() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.checklists.push(builtin("smb").instantiate());
+        log(&mut ctf_box, "enum4linux -a 10.10.10.5");
+        auto_tick(&mut ctf_box);
+        let md = markdown(&ctf_box);
+        assert!(md.starts_with("### SMB (139/445) (1/"), "{}", md);
+        assert!(md.contains("- [x] Enumerate with enum4linux (`enum4linux -a 10.10.10.5`)\n"));
+        assert!(md.contains("- [ ] List shares anonymously\n"));
+    }
+}
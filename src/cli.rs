@@ -0,0 +1,506 @@
+use crate::config::Config;
+use crate::models::{Note, NoteCategory};
+use crate::redact::{RedactMode, Redactor};
+use crate::snippets::Snippet;
+use crate::stats::Stats;
+use crate::storage;
+use crate::ui::input::TextInput;
+use anyhow::{Context, Result, bail};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use ratatui::{
+    Terminal, TerminalOptions, Viewport,
+    backend::CrosstermBackend,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState},
+};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process::Command;
+
+const USAGE: &str = "\
+Usage: ctf-brain [command]
+
+Without a command, the TUI is started.
+
+Commands:
+  loot add <box-id> <file> [--note <text>] [--source <origin>]
+                        Store a file in the box's loot directory
+  loot list <box-id>    List the files stored for a box
+  screenshot [image] [--box <box-id>] [--caption <text>] [--category <name>]
+                        Attach an image (or the clipboard image) to a new note
+  listen [port] [--box <box-id>]
+                        Wait for a reverse shell (network.listener_port by
+                        default) and log the session on the box
+  serve [dir] [--box <box-id>] [--port <port>] [--bind <address>] [--upload]
+                        Serve a directory over HTTP on LHOST (network.http_port
+                        by default), log the requests on the box and print
+                        download one-liners; --upload stores PUT/POST bodies
+                        in the box's loot
+  snip [query] [--box <box-id>]
+                        Pick a snippet, fill its placeholders and print
+                        the command (box from $CTF_ID in a box shell)
+  tmux [box-id] [--kill]
+                        Open the box's tmux session (shell, listener, notes
+                        and server windows), creating it when missing;
+                        --kill ends it
+  stats [--json]        Show statistics over every box
+                        (--json for dashboards and scripts, with
+                        secrets redacted)
+  config [--print]      Validate ~/.config/ctf-brain/config.toml
+                        (--print shows the effective configuration)
+  help                  Show this message";
+
+/// Run a command-line subcommand (everything except the TUI)
+pub fn run(args: &[String]) -> Result<()> {
+    // `config` reports an invalid file itself instead of falling back
+    let command = args.first().map(String::as_str);
+    match command {
+        Some("config") => return config(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        None => return Ok(()),
+        _ => {}
+    }
+
+    let config = Config::load_or_default();
+    match command {
+        Some("loot") => loot(&args[1..], &config),
+        Some("screenshot") => screenshot(&args[1..], &config),
+        Some("listen") => listen(&args[1..], &config),
+        Some("serve") => serve(&args[1..], &config),
+        Some("snip") => snip(&args[1..], &config),
+        Some("tmux") => tmux(&args[1..], &config),
+        Some("stats") => stats(&args[1..], &config),
+        Some(other) => bail!("Unknown command '{}'\n\n{}", other, USAGE),
+        None => Ok(()),
+    }
+}
+
+fn loot(args: &[String], config: &Config) -> Result<()> {
+    match args.first().map(String::as_str) {
+        Some("add") => {
+            let box_id = parse_box_id(args.get(1))?;
+            let file = args.get(2).context("Missing <file>")?;
+            let note = flag_value(args, "--note");
+            let source = flag_value(args, "--source");
+
+            let attachment = storage::add_loot(box_id, Path::new(file), source, note, config)?;
+            println!(
+                "📦 Stored {} ({} bytes, {}) → sha256 {}",
+                attachment.name, attachment.size, attachment.mime, attachment.sha256
+            );
+            Ok(())
+        }
+        Some("list") => {
+            let box_id = parse_box_id(args.get(1))?;
+            let entries = storage::load_loot_index(box_id, config)?;
+            if entries.is_empty() {
+                println!("No loot stored for box {}", box_id);
+            }
+            for a in entries {
+                println!(
+                    "{}  {:>10}  {:<28} {}",
+                    storage::short_hash(&a.sha256),
+                    a.size,
+                    a.mime,
+                    a.name
+                );
+            }
+            Ok(())
+        }
+        _ => bail!("Usage: ctf-brain loot <add|list> <box-id> [file]"),
+    }
+}
+
+fn screenshot(args: &[String], config: &Config) -> Result<()> {
+    let box_id = parse_box_id(flag_value(args, "--box").as_ref())?;
+    let caption = flag_value(args, "--caption");
+    let category = match flag_value(args, "--category") {
+        Some(name) => NoteCategory::parse(&name)
+            .with_context(|| format!("Unknown note category '{}'", name))?,
+        None => NoteCategory::Misc,
+    };
+
+    let path = positional(args, &["--box", "--caption", "--category"]);
+    let (name, bytes, source) = match path {
+        Some(path) => {
+            let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path))?;
+            let name = Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "screenshot.png".to_string());
+            (name, bytes, "screenshot".to_string())
+        }
+        None => {
+            let bytes = read_clipboard_image()?;
+            let name = format!("screenshot-{}.png", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+            (name, bytes, "clipboard".to_string())
+        }
+    };
+
+    let mime = storage::detect_mime(&bytes, &name);
+    if !mime.starts_with("image/") {
+        bail!("{} is not an image ({})", name, mime);
+    }
+
+    let attachment = storage::add_loot_bytes(box_id, &name, &bytes, source, caption.clone(), config)?;
+    let note = Note {
+        category,
+        content: caption.unwrap_or_else(|| format!("Screenshot: {}", attachment.name)),
+        created_date: chrono::Utc::now(),
+        images: vec![attachment.sha256.clone()],
+    };
+    storage::queue_note(box_id, &note, config)?;
+
+    println!(
+        "🖼️  Attached {} to a new {:?} note on box {}",
+        attachment.name, note.category, box_id
+    );
+    Ok(())
+}
+
+fn listen(args: &[String], config: &Config) -> Result<()> {
+    let box_id = parse_box_id(flag_value(args, "--box").as_ref())?;
+    let boxes = storage::load_boxes()?;
+    if !boxes.iter().any(|b| b.id == box_id) {
+        bail!("Box {} not found", box_id);
+    }
+    let port = match args.first().filter(|a| !a.starts_with("--")) {
+        Some(port) => port.parse().with_context(|| format!("Invalid port '{}'", port))?,
+        None => config.network.listener_port,
+    };
+    crate::listener::listen(box_id, port, config)
+}
+
+fn serve(args: &[String], config: &Config) -> Result<()> {
+    let box_id = parse_box_id(flag_value(args, "--box").as_ref())?;
+    let boxes = storage::load_boxes()?;
+    let ctf_box = boxes
+        .iter()
+        .find(|b| b.id == box_id)
+        .with_context(|| format!("Box {} not found", box_id))?;
+
+    let dir = positional(args, &["--box", "--port", "--bind"]);
+    let root = std::fs::canonicalize(dir.unwrap_or("."))
+        .with_context(|| format!("Failed to open {}", dir.unwrap_or(".")))?;
+
+    let address = match flag_value(args, "--bind") {
+        Some(address) => address
+            .parse()
+            .with_context(|| format!("Invalid address '{}'", address))?,
+        None => crate::payloads::lhost(ctf_box, config)
+            .map_err(anyhow::Error::msg)
+            .and_then(|lhost| Ok(lhost.parse()?))
+            .context("No LHOST to bind to (use --bind 0.0.0.0)")?,
+    };
+    let port = match flag_value(args, "--port") {
+        Some(port) => port.parse().with_context(|| format!("Invalid port '{}'", port))?,
+        None => config.network.http_port,
+    };
+
+    let server = crate::fileserver::Server {
+        box_id,
+        root,
+        address,
+        port,
+        uploads: args.iter().any(|a| a == "--upload"),
+        config: config.clone(),
+    };
+    server.print_one_liners()?;
+    server.run()
+}
+
+fn snip(args: &[String], config: &Config) -> Result<()> {
+    let box_id = parse_box_id(flag_value(args, "--box").as_ref())?;
+    let boxes = storage::load_boxes()?;
+    let ctf_box = boxes
+        .iter()
+        .find(|b| b.id == box_id)
+        .with_context(|| format!("Box {} not found", box_id))?;
+
+    // Everything but `--box <id>` is the query
+    let mut query = Vec::new();
+    let mut words = args.iter();
+    while let Some(word) = words.next() {
+        if word == "--box" {
+            words.next();
+        } else {
+            query.push(word.as_str());
+        }
+    }
+
+    let snippets = crate::snippets::snippets(config);
+    let Some(snippet) = pick_snippet(&snippets, &query.join(" "), config)? else {
+        bail!("No snippet picked");
+    };
+
+    // Placeholders the box has no value for are asked on the terminal
+    let mut values = crate::snippets::box_variables(ctf_box, config);
+    let stdin = io::stdin();
+    for name in crate::snippets::missing(&snippet.command, &values) {
+        eprint!("{}: ", name);
+        io::stderr().flush()?;
+        let mut value = String::new();
+        stdin.lock().read_line(&mut value)?;
+        values.insert(name.to_lowercase(), value.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    println!("{}", crate::snippets::fill(&snippet.command, &values));
+    Ok(())
+}
+
+/// Raw mode until dropped, so that an error does not leave the terminal in it
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        enable_raw_mode()?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+/// Fuzzy picker drawn below the prompt on stderr, so that only the
+/// command goes to stdout (`cmd=$(ctf-brain snip)`)
+fn pick_snippet(snippets: &[Snippet], query: &str, config: &Config) -> Result<Option<Snippet>> {
+    let theme = crate::ui::theme::Theme::by_name(&config.ui.theme).unwrap_or_default();
+    let _raw_mode = RawMode::enable()?;
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(io::stderr()),
+        TerminalOptions {
+            viewport: Viewport::Inline(12),
+        },
+    )?;
+
+    let mut input = TextInput::with_value(query);
+    let mut selected = 0;
+    let picked = loop {
+        let matches = crate::fuzzy::rank(input.value(), snippets, |s| s.search_text());
+        selected = selected.min(matches.len().saturating_sub(1));
+        terminal.draw(|f| {
+            let area = f.area();
+            let (text, cursor) = input.visible(area.width.saturating_sub(2) as usize);
+            f.render_widget(
+                Line::from(vec![
+                    Span::styled("> ", Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+                    Span::raw(text),
+                    Span::styled(
+                        format!("  {}/{}", matches.len(), snippets.len()),
+                        Style::default().fg(theme.muted),
+                    ),
+                ]),
+                area,
+            );
+            f.set_cursor_position((area.x + 2 + cursor as u16, area.y));
+
+            let items: Vec<ListItem> = matches
+                .iter()
+                .map(|(snippet, _)| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(snippet.title.clone()),
+                        Span::styled(format!("  {}", snippet.command), Style::default().fg(theme.muted)),
+                    ]))
+                })
+                .collect();
+            let list = List::new(items)
+                .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD))
+                .highlight_symbol("▶ ");
+            let mut state = ListState::default().with_selected((!matches.is_empty()).then_some(selected));
+            let mut list_area = area;
+            list_area.y += 1;
+            list_area.height = list_area.height.saturating_sub(1);
+            f.render_stateful_widget(list, list_area, &mut state);
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => break None,
+            KeyCode::Char('c') if ctrl => break None,
+            KeyCode::Enter => break matches.get(selected).map(|(s, _)| (*s).clone()),
+            KeyCode::Down | KeyCode::Tab => selected = (selected + 1) % matches.len().max(1),
+            KeyCode::Char('n') if ctrl => selected = (selected + 1) % matches.len().max(1),
+            KeyCode::Up | KeyCode::BackTab => selected = selected.checked_sub(1).unwrap_or(matches.len().saturating_sub(1)),
+            KeyCode::Char('p') if ctrl => selected = selected.checked_sub(1).unwrap_or(matches.len().saturating_sub(1)),
+            _ => {
+                if input.handle_key(key) {
+                    selected = 0;
+                }
+            }
+        }
+    };
+
+    terminal.clear()?;
+    Ok(picked)
+}
+
+fn tmux(args: &[String], config: &Config) -> Result<()> {
+    let box_id = parse_box_id(args.iter().find(|a| !a.starts_with("--")))?;
+    let boxes = storage::load_boxes()?;
+    let ctf_box = boxes
+        .iter()
+        .find(|b| b.id == box_id)
+        .with_context(|| format!("Box {} not found", box_id))?;
+
+    if args.iter().any(|a| a == "--kill") {
+        let name = crate::tmux::find(&crate::tmux::sessions(), box_id)
+            .with_context(|| format!("No tmux session for box {}", box_id))?;
+        crate::tmux::kill(&name).map_err(anyhow::Error::msg)?;
+        println!("🗑  Session {} ended", name);
+        return Ok(());
+    }
+    crate::tmux::open(ctf_box, config).map_err(anyhow::Error::msg)
+}
+
+fn stats(args: &[String], config: &Config) -> Result<()> {
+    let boxes = storage::load_boxes()?;
+    let stats = Stats::compute(&boxes);
+
+    // Shared with the team, so secrets go through the same redaction as write-ups
+    if args.iter().any(|a| a == "--json") {
+        let redactor = Redactor::for_boxes(&boxes, RedactMode::Placeholder, config);
+        for problem in &redactor.problems {
+            eprintln!("⚠️  {}", problem);
+        }
+        let json = redactor.redact_json(serde_json::to_value(&stats)?);
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    let counts = |counts: &[(&str, usize)]| {
+        counts
+            .iter()
+            .filter(|(_, n)| *n > 0)
+            .map(|(name, n)| format!("{} {}", name, n))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let platforms: Vec<(&str, usize)> = stats
+        .by_platform
+        .iter()
+        .map(|(name, n)| (name.as_str(), *n))
+        .collect();
+    println!("📦 {} boxes: {}", stats.boxes, counts(&platforms));
+    println!("🏁 Status: {}", counts(&crate::stats::status_counts(&stats)));
+    println!("📈 Difficulty: {}", counts(&crate::stats::difficulty_counts(&stats)));
+    for (label, durations) in [("user", &stats.time_to_user), ("root", &stats.time_to_root)] {
+        if let Some(median) = durations.median_hours {
+            println!(
+                "⏱️  Time to {}: median {} over {} box(es)",
+                label,
+                crate::stats::format_hours(median),
+                durations.hours.len()
+            );
+        }
+    }
+    if !stats.tools.is_empty() {
+        println!("🔧 Tools:");
+        for tool in stats.tools.iter().take(10) {
+            let ratio = tool
+                .success_ratio()
+                .map_or("?".to_string(), |r| format!("{:.0}%", r * 100.0));
+            println!("   {:<16} {:>5} uses  {:>4} success", tool.name, tool.uses, ratio);
+        }
+    }
+    let notes: Vec<(&str, usize)> = stats
+        .notes_by_category
+        .iter()
+        .map(|(name, n)| (name.as_str(), *n))
+        .collect();
+    if !notes.is_empty() {
+        println!("📝 Notes: {}", counts(&notes));
+    }
+    Ok(())
+}
+
+fn config(args: &[String]) -> Result<()> {
+    let path = Config::path().context("Unable to determine config directory")?;
+    let config = Config::load()?;
+
+    if args.iter().any(|a| a == "--print") {
+        print!("{}", toml::to_string_pretty(&config)?);
+        return Ok(());
+    }
+
+    if path.exists() {
+        println!("📄 {}", path.display());
+    } else {
+        println!("📄 {} (not found, using defaults)", path.display());
+    }
+
+    let problems = config.validate();
+    if problems.is_empty() {
+        println!("✅ Configuration is valid");
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("⚠️  {}", problem);
+    }
+    bail!("{} problem(s) found in the configuration", problems.len())
+}
+
+/// Read a PNG image from the clipboard using whichever tool is installed
+fn read_clipboard_image() -> Result<Vec<u8>> {
+    let tools: &[(&str, &[&str])] = &[
+        ("wl-paste", &["--type", "image/png"]),
+        ("xclip", &["-selection", "clipboard", "-t", "image/png", "-o"]),
+        ("pngpaste", &["-"]),
+    ];
+
+    for (program, args) in tools {
+        if let Ok(output) = Command::new(program).args(*args).output()
+            && output.status.success()
+            && !output.stdout.is_empty()
+        {
+            return Ok(output.stdout);
+        }
+    }
+
+    bail!("No image in the clipboard (tried wl-paste, xclip, pngpaste)")
+}
+
+/// Parse a box id argument, falling back to $CTF_ID inside a box shell
+fn parse_box_id(arg: Option<&String>) -> Result<i32> {
+    let raw = match arg {
+        Some(a) => a.clone(),
+        None => std::env::var("CTF_ID").context("Missing <box-id> (and $CTF_ID is not set)")?,
+    };
+    raw.parse()
+        .with_context(|| format!("Invalid box id '{}'", raw))
+}
+
+/// First argument that is neither a flag nor the value of one of the
+/// `valued` flags
+fn positional<'a>(args: &'a [String], valued: &[&str]) -> Option<&'a str> {
+    let mut words = args.iter();
+    while let Some(word) = words.next() {
+        if valued.contains(&word.as_str()) {
+            words.next();
+        } else if !word.starts_with("--") {
+            return Some(word);
+        }
+    }
+    None
+}
+
+/// Value following a `--flag` argument, if present
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
@@ -0,0 +1,40 @@
+use base64::Engine;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Copy text with whichever clipboard tool is installed, or through the
+/// terminal (OSC 52, works over SSH in most terminals). Returns what was used.
+pub fn copy(text: &str) -> Result<&'static str, String> {
+    let tools: &[(&str, &[&str])] = &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("pbcopy", &[]),
+    ];
+
+    for (program, args) in tools {
+        let Ok(mut child) = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        // xclip stays in the background to serve the selection
+        if written && (*program == "xclip" || child.wait().is_ok_and(|s| s.success())) {
+            return Ok(program);
+        }
+    }
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)
+        .and_then(|_| stdout.flush())
+        .map_err(|e| format!("Failed to copy: {}", e))?;
+    Ok("terminal")
+}
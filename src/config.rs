@@ -0,0 +1,318 @@
+use crate::ui::input::expand_home_path;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// User configuration, loaded from `~/.config/ctf-brain/config.toml`.
+/// Every section and key is optional; missing values use the defaults below.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub paths: PathsConfig,
+    pub wordlists: WordlistsConfig,
+    pub network: NetworkConfig,
+    pub platforms: PlatformsConfig,
+    pub shell: ShellConfig,
+    pub ui: UiConfig,
+    pub storage: StorageConfig,
+    /// Key overrides per context: `[keys.list] top = ["g g", "Home"]`
+    pub keys: crate::keymap::KeysConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathsConfig {
+    /// Where env files, the shell hook, loot and pending notes live
+    pub base_dir: String,
+    /// Where the `ctf` wrapper writes its jsonl logs (defaults to `<base_dir>/logs`)
+    pub log_dir: Option<String>,
+    /// exploitdb index searched for the versions found by nmap, when installed
+    pub exploitdb: String,
+    /// Hosts file where the boxes' hostnames are kept in a ctf-brain block
+    pub hosts_file: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WordlistsConfig {
+    /// Used by the `g`, `cg` and `cff` aliases
+    pub directories: String,
+    pub passwords: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Interface whose address is used as LHOST (usually the VPN)
+    pub lhost_interface: String,
+    pub listener_port: u16,
+    pub http_port: u16,
+    /// Seconds between two reachability checks of the current box, 0 to disable
+    pub probe_interval_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlatformsConfig {
+    /// Platform pre-filled in the new box form
+    pub default: String,
+    pub known: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShellConfig {
+    /// Shell launched with `l` (defaults to `$SHELL`, then /bin/bash)
+    pub program: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    pub status_timeout_secs: u64,
+    /// Color preset: dark, light or high-contrast
+    pub theme: String,
+    /// Width of the dashboard panes (boxes, info, feed) in percent
+    pub dashboard_panes: [u16; 3],
+}
+
+/// One key or a list of keys bound to an action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// Number of rotating backups of boxes.json
+    pub backup_count: usize,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        Self {
+            base_dir: "~/.ctf-brain".to_string(),
+            log_dir: None,
+            exploitdb: "/usr/share/exploitdb/files_exploits.csv".to_string(),
+            hosts_file: "/etc/hosts".to_string(),
+        }
+    }
+}
+
+impl Default for WordlistsConfig {
+    fn default() -> Self {
+        Self {
+            directories: "/usr/share/wordlists/dirbuster/directory-list-2.3-medium.txt"
+                .to_string(),
+            passwords: "/usr/share/wordlists/rockyou.txt".to_string(),
+        }
+    }
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            lhost_interface: "tun0".to_string(),
+            listener_port: 4444,
+            http_port: 8000,
+            probe_interval_secs: 30,
+        }
+    }
+}
+
+impl Default for PlatformsConfig {
+    fn default() -> Self {
+        Self {
+            default: "HTB".to_string(),
+            known: ["HTB", "TryHackMe", "picoCTF", "RootMe"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            status_timeout_secs: 4,
+            theme: "dark".to_string(),
+            dashboard_panes: [25, 40, 35],
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self { backup_count: 5 }
+    }
+}
+
+impl Config {
+    /// Path of the config file
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("ctf-brain").join("config.toml"))
+    }
+
+    /// Load the config file, or the defaults if it does not exist
+    pub fn load() -> Result<Config> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    /// Same as `load`, but an invalid file is reported on stderr
+    /// and the defaults are used
+    pub fn load_or_default() -> Config {
+        Self::load().unwrap_or_else(|e| {
+            eprintln!("Warning: {:#}. Using the default configuration.", e);
+            Config::default()
+        })
+    }
+
+    pub fn load_from(path: &Path) -> Result<Config> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid config in {}", path.display()))
+    }
+
+    /// Data directory, with `~` expanded
+    pub fn base_dir(&self) -> PathBuf {
+        expand_home_path(&self.paths.base_dir)
+    }
+
+    /// Log directory of the `ctf` wrapper, with `~` expanded
+    pub fn log_dir(&self) -> PathBuf {
+        match &self.paths.log_dir {
+            Some(dir) => expand_home_path(dir),
+            None => self.base_dir().join("logs"),
+        }
+    }
+
+    /// exploitdb's `files_exploits.csv`, with `~` expanded
+    pub fn exploitdb_path(&self) -> PathBuf {
+        expand_home_path(&self.paths.exploitdb)
+    }
+
+    /// The hosts file, with `~` expanded
+    pub fn hosts_path(&self) -> PathBuf {
+        expand_home_path(&self.paths.hosts_file)
+    }
+
+    /// Shell to launch for a box
+    pub fn shell_program(&self) -> String {
+        self.shell
+            .program
+            .clone()
+            .or_else(|| std::env::var("SHELL").ok())
+            .unwrap_or_else(|| "/bin/bash".to_string())
+    }
+
+    /// Check values that parse fine but would not work.
+    /// Returns one message per problem; an empty list means the config is usable.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (name, path) in [
+            ("wordlists.directories", &self.wordlists.directories),
+            ("wordlists.passwords", &self.wordlists.passwords),
+        ] {
+            if !expand_home_path(path).exists() {
+                problems.push(format!("{}: {} does not exist", name, path));
+            }
+        }
+
+        if !crate::network::interface_up(&self.network.lhost_interface) {
+            problems.push(format!(
+                "network.lhost_interface: interface {} is not up (VPN not connected?)",
+                self.network.lhost_interface
+            ));
+        }
+        if self.network.listener_port == 0 || self.network.http_port == 0 {
+            problems.push("network: ports must be between 1 and 65535".to_string());
+        }
+
+        if self.platforms.default.trim().is_empty() {
+            problems.push("platforms.default: must not be empty".to_string());
+        } else if !self.platforms.known.is_empty()
+            && !self.platforms.known.contains(&self.platforms.default)
+        {
+            problems.push(format!(
+                "platforms.default: {} is not in platforms.known",
+                self.platforms.default
+            ));
+        }
+
+        if let Some(program) = &self.shell.program
+            && !program_exists(program)
+        {
+            problems.push(format!("shell.program: {} does not exist", program));
+        }
+
+        if self.ui.status_timeout_secs == 0 {
+            problems.push("ui.status_timeout_secs: must be at least 1".to_string());
+        }
+        if crate::ui::theme::Theme::by_name(&self.ui.theme).is_none() {
+            problems.push(format!(
+                "ui.theme: unknown theme {} (expected {})",
+                self.ui.theme,
+                crate::ui::theme::Theme::NAMES.join(", ")
+            ));
+        }
+
+        let panes = self.ui.dashboard_panes;
+        if panes.iter().any(|w| *w < 10) || panes.iter().sum::<u16>() != 100 {
+            problems.push(format!(
+                "ui.dashboard_panes: {:?} must add up to 100 with each pane at least 10",
+                panes
+            ));
+        }
+
+        let (_, key_problems) = crate::keymap::Keymap::from_config(&self.keys);
+        problems.extend(key_problems);
+        problems.extend(crate::redact::load_user_rules(self).1);
+
+        problems
+    }
+}
+
+/// A path is checked as is, a bare name is looked up in `PATH`
+/// like the shell would
+pub(crate) fn program_exists(program: &str) -> bool {
+    if program.contains('/') {
+        return expand_home_path(program).exists();
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_shell_program_is_found_through_path() {
+        assert!(program_exists("sh"));
+        assert!(!program_exists("ctf-brain-no-such-shell"));
+    }
+
+    #[test]
+    fn shell_program_path_is_checked_as_is() {
+        assert!(program_exists("/bin/sh"));
+        assert!(!program_exists("/nonexistent/sh"));
+    }
+}
@@ -0,0 +1,467 @@
+use crate::config::Config;
+use crate::models::CtfBox;
+use crate::recon::{self, Service};
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Well-known exploits shipped with ctf-brain
+const BUILTIN: &str = include_str!("../data/exploits.toml");
+
+/// A public exploit or vulnerability
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exploit {
+    /// Exploit-DB id, without the `EDB-` prefix
+    pub edb: Option<String>,
+    pub cves: Vec<String>,
+    pub title: String,
+}
+
+impl Exploit {
+    /// `EDB-49757 CVE-2011-2523`
+    pub fn reference(&self) -> String {
+        self.edb
+            .iter()
+            .map(|id| format!("EDB-{}", id))
+            .chain(self.cves.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// An exploit that may apply to a service of the box
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub service: Service,
+    pub exploit: Exploit,
+}
+
+impl Candidate {
+    /// Content of the Foothold note made from the candidate
+    pub fn note(&self) -> String {
+        format!(
+            "{} ({}/{}): {} - {}",
+            self.service.version,
+            self.service.port,
+            self.service.protocol,
+            self.exploit.reference(),
+            self.exploit.title
+        )
+    }
+}
+
+/// Versions an exploit applies to
+#[derive(Debug, Clone, PartialEq)]
+enum Spec {
+    Exact(Vec<u32>),
+    /// `2.4.x`
+    Prefix(Vec<u32>),
+    /// `< 7.7`, `3.0.20 < 3.0.26`, `<= 1.3`
+    Range {
+        from: Option<Vec<u32>>,
+        to: Vec<u32>,
+        inclusive: bool,
+    },
+}
+
+impl Spec {
+    /// `2.3.4`, `2.4.x`, `< 7.7`, `3.0.20 < 3.0.26`
+    fn parse(text: &str) -> Option<Spec> {
+        specs(&text.split_whitespace().collect::<Vec<_>>(), false).into_iter().next()
+    }
+
+    fn matches(&self, version: &[u32]) -> bool {
+        match self {
+            Spec::Exact(exact) => compare(version, exact) == Ordering::Equal,
+            Spec::Prefix(prefix) => version.starts_with(prefix),
+            Spec::Range { from, to, inclusive } => {
+                from.as_ref().is_none_or(|from| compare(version, from) != Ordering::Less)
+                    && match compare(version, to) {
+                        Ordering::Less => true,
+                        Ordering::Equal => *inclusive,
+                        Ordering::Greater => false,
+                    }
+            }
+        }
+    }
+}
+
+/// Compare versions, missing components counting as 0
+fn compare(a: &[u32], b: &[u32]) -> Ordering {
+    let len = a.len().max(b.len());
+    let at = |v: &[u32], i: usize| v.get(i).copied().unwrap_or(0);
+    (0..len)
+        .map(|i| at(a, i).cmp(&at(b, i)))
+        .find(|o| *o != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Leading version of a token: `4.7p1` → 4.7, `v1` → 1, `3.0.20-Debian`
+/// → 3.0.20. With `dotted`, a bare number is not a version.
+fn parse_version(token: &str, dotted: bool) -> Option<Vec<u32>> {
+    let token = token.strip_prefix(['v', 'V']).unwrap_or(token);
+    let end = token
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(token.len());
+    let numeric = token[..end].trim_end_matches('.');
+    if numeric.is_empty() || !numeric.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    if dotted && !numeric.contains('.') {
+        return None;
+    }
+    numeric.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Version specs in the words of an exploit title
+fn specs(words: &[&str], dotted: bool) -> Vec<Spec> {
+    let mut specs = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        if (word == "<" || word == "<=")
+            && let Some(to) = words.get(i + 1).and_then(|w| parse_version(w, dotted))
+        {
+            // `a < b` replaces the `a` just read
+            let from = match specs.last() {
+                Some(Spec::Exact(from)) if i > 0 && parse_version(words[i - 1], dotted).is_some() => {
+                    let from = from.clone();
+                    specs.pop();
+                    Some(from)
+                }
+                _ => None,
+            };
+            specs.push(Spec::Range { from, to, inclusive: word == "<=" });
+            i += 2;
+            continue;
+        }
+        // `2.2.x`, `1.3.3/1.3.4`
+        for part in word.split('/') {
+            let lower = part.to_lowercase();
+            if let Some(prefix) = lower.strip_suffix(".x") {
+                if let Some(prefix) = parse_version(prefix, false) {
+                    specs.push(Spec::Prefix(prefix));
+                }
+            } else if let Some(version) = parse_version(part, dotted) {
+                specs.push(Spec::Exact(version));
+            }
+        }
+        i += 1;
+    }
+    specs
+}
+
+/// An exploit with what it applies to
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Lowercase, first word of the nmap version string
+    product: String,
+    specs: Vec<Spec>,
+    exploit: Exploit,
+}
+
+#[derive(Debug, Deserialize)]
+struct MapFile {
+    #[serde(default)]
+    exploit: Vec<MapEntry>,
+}
+
+/// An exploit of a mapping file
+#[derive(Debug, Deserialize)]
+struct MapEntry {
+    product: String,
+    versions: Vec<String>,
+    edb: Option<String>,
+    #[serde(default)]
+    cves: Vec<String>,
+    title: String,
+}
+
+fn map_entries(content: &str) -> Vec<Entry> {
+    let Ok(file) = toml::from_str::<MapFile>(content) else {
+        return Vec::new();
+    };
+    file.exploit
+        .into_iter()
+        .map(|e| Entry {
+            product: e.product.to_lowercase(),
+            specs: e.versions.iter().filter_map(|v| Spec::parse(v)).collect(),
+            exploit: Exploit {
+                edb: e.edb,
+                cves: e.cves,
+                title: e.title,
+            },
+        })
+        .collect()
+}
+
+/// Fields of a CSV line, with quoted fields (`"a, ""b"""`)
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().expect("one field").push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().expect("one field").push(c),
+        }
+    }
+    fields
+}
+
+/// Exploits of exploitdb's `files_exploits.csv` whose title starts with a
+/// product and a version: `vsftpd 2.3.4 - Backdoor Command Execution`
+fn exploitdb_entries(content: &str) -> Vec<Entry> {
+    let mut lines = content.lines();
+    let header = lines.next().map(csv_fields).unwrap_or_default();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (Some(id), Some(description)) = (column("id"), column("description")) else {
+        return Vec::new();
+    };
+    let codes = column("codes");
+
+    lines
+        .filter_map(|line| {
+            let fields = csv_fields(line);
+            let title = fields.get(description)?;
+            let affected = title.split(" - ").next()?;
+            let words: Vec<&str> = affected.split_whitespace().collect();
+            let (product, rest) = words.split_first()?;
+            let specs = specs(rest, true);
+            if specs.is_empty() {
+                return None;
+            }
+            let cves = codes
+                .and_then(|c| fields.get(c))
+                .map(|c| {
+                    c.split(';')
+                        .filter(|code| code.starts_with("CVE-"))
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(Entry {
+                product: product.to_lowercase(),
+                specs,
+                exploit: Exploit {
+                    edb: fields.get(id).cloned(),
+                    cves,
+                    title: title.clone(),
+                },
+            })
+        })
+        .collect()
+}
+
+/// Built-in map, the user's `exploits.toml` in the base dir, then
+/// exploitdb when installed (`paths.exploitdb`). Read once.
+fn index(config: &Config) -> &'static [Entry] {
+    static INDEX: OnceLock<Vec<Entry>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut entries = map_entries(BUILTIN);
+        if let Ok(user) = std::fs::read_to_string(config.base_dir().join("exploits.toml")) {
+            entries.extend(map_entries(&user));
+        }
+        if let Ok(csv) = std::fs::read_to_string(config.exploitdb_path()) {
+            entries.extend(exploitdb_entries(&csv));
+        }
+        entries
+    })
+}
+
+/// Exploits for an nmap version string (`vsftpd 2.3.4`), by the first
+/// exploit found for each EDB id. Memoized, the index is large.
+fn lookup(version: &str, config: &Config) -> Vec<Exploit> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Vec<Exploit>>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(found) = cache.lock().ok().and_then(|c| c.get(version).cloned()) {
+        return found;
+    }
+
+    let mut words = version.split_whitespace();
+    let product = words.next().unwrap_or_default().to_lowercase();
+    let found: Vec<Exploit> = match words.find_map(|w| parse_version(w, false)) {
+        Some(parsed) => {
+            let mut found: Vec<Exploit> = Vec::new();
+            for entry in index(config)
+                .iter()
+                .filter(|e| e.product == product && e.specs.iter().any(|s| s.matches(&parsed)))
+            {
+                let duplicate = found.iter().any(|known| match (&known.edb, &entry.exploit.edb) {
+                    (Some(a), Some(b)) => a == b,
+                    // A mapping without EDB id is the same as an exploit for its CVE
+                    _ => known.cves.iter().any(|cve| entry.exploit.cves.contains(cve)),
+                });
+                if !duplicate {
+                    found.push(entry.exploit.clone());
+                }
+            }
+            found
+        }
+        None => Vec::new(),
+    };
+
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(version.to_string(), found.clone());
+    }
+    found
+}
+
+/// Exploits matching the versions of the box's services
+pub fn candidates(ctf_box: &CtfBox, config: &Config) -> Vec<Candidate> {
+    recon::box_services(ctf_box)
+        .into_iter()
+        .filter(|s| !s.version.is_empty())
+        .flat_map(|service| {
+            lookup(&service.version, config)
+                .into_iter()
+                .map(move |exploit| Candidate {
+                    service: service.clone(),
+                    exploit,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Only the built-in map: the index is read once for every test
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.paths.base_dir = "/nonexistent/ctf-brain".to_string();
+        config.paths.exploitdb = "/nonexistent/files_exploits.csv".to_string();
+        config
+    }
+
+    fn matches(spec: &str, version: &str) -> bool {
+        let version = parse_version(version, false).unwrap();
+        Spec::parse(spec).unwrap().matches(&version)
+    }
+
+    #[test]
+    fn versions_are_read_from_their_leading_digits() {
+        assert_eq!(parse_version("2.3.4", false), Some(vec![2, 3, 4]));
+        assert_eq!(parse_version("4.7p1", false), Some(vec![4, 7]));
+        assert_eq!(parse_version("v1", false), Some(vec![1]));
+        assert_eq!(parse_version("3.0.20-Debian", false), Some(vec![3, 0, 20]));
+        assert_eq!(parse_version("2.4.", false), Some(vec![2, 4]));
+        assert_eq!(parse_version("8", true), None);
+        assert_eq!(parse_version("httpd", false), None);
+        assert_eq!(parse_version(".5", false), None);
+        assert_eq!(parse_version("1..2", false), None);
+    }
+
+    #[test]
+    fn specs_match_exact_prefix_and_ranges() {
+        assert!(matches("2.3.4", "2.3.4"));
+        assert!(matches("2.3", "2.3.0"));
+        assert!(!matches("2.3.4", "2.3.5"));
+        assert!(matches("2.4.x", "2.4.49"));
+        assert!(!matches("2.4.x", "2.5"));
+        assert!(matches("< 7.7", "7.6.1"));
+        assert!(!matches("< 7.7", "7.7"));
+        assert!(matches("<= 7.7", "7.7"));
+        assert!(matches("3.0.20 < 3.0.26", "3.0.20"));
+        assert!(matches("3.0.20 < 3.0.26", "3.0.25"));
+        assert!(!matches("3.0.20 < 3.0.26", "3.0.19"));
+        assert!(!matches("3.0.20 < 3.0.26", "3.0.26"));
+        assert_eq!(Spec::parse("soon"), None);
+    }
+
+    #[test]
+    fn title_words_give_every_version() {
+        let words = |title: &str| specs(&title.split_whitespace().collect::<Vec<_>>(), true);
+        assert_eq!(
+            words("1.3.3/1.3.4 2.2.x"),
+            [Spec::Exact(vec![1, 3, 3]), Spec::Exact(vec![1, 3, 4]), Spec::Prefix(vec![2, 2])]
+        );
+        assert_eq!(
+            words("3.0.20 < 3.0.25rc3"),
+            [Spec::Range { from: Some(vec![3, 0, 20]), to: vec![3, 0, 25], inclusive: false }]
+        );
+        assert_eq!(words("< 2.4"), [Spec::Range { from: None, to: vec![2, 4], inclusive: false }]);
+        // A bare number is not a version in a title (`Windows 7`)
+        assert!(words("7 SP1").is_empty());
+    }
+
+    #[test]
+    fn csv_fields_with_quotes() {
+        assert_eq!(csv_fields("1,a,b"), ["1", "a", "b"]);
+        assert_eq!(csv_fields(r#"1,"a, ""b""",c"#), ["1", r#"a, "b""#, "c"]);
+        assert_eq!(csv_fields(""), [""]);
+        assert_eq!(csv_fields("a,"), ["a", ""]);
+    }
+
+    #[test]
+    fn exploitdb_titles_name_the_product_and_versions() {
+        let csv = "\
+id,file,description,date_published,author,type,platform,port,date_added,date_updated,verified,codes
+49757,exploits/unix/remote/49757.py,\"vsftpd 2.3.4 - Backdoor Command Execution\",2021-04-12,x,remote,unix,,,,,CVE-2011-2523;OSVDB-73573
+16320,exploits/unix/remote/16320.rb,Samba 3.0.20 < 3.0.25rc3 - 'Username' map script Command Execution (Metasploit),2010-05-09,x,remote,unix,,,,,CVE-2007-2447
+1,exploits/windows/1.txt,Windows 7 - Something,2010-01-01,x,local,windows,,,,,
+";
+        let entries = exploitdb_entries(csv);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].product, "vsftpd");
+        assert_eq!(entries[0].specs, [Spec::Exact(vec![2, 3, 4])]);
+        assert_eq!(entries[0].exploit.reference(), "EDB-49757 CVE-2011-2523");
+        assert_eq!(entries[1].product, "samba");
+        assert!(entries[1].specs[0].matches(&[3, 0, 24]));
+
+        assert!(exploitdb_entries("not,a,header\n1,2,3\n").is_empty());
+    }
+
+    #[test]
+    fn builtin_map_parses() {
+        let entries = map_entries(BUILTIN);
+        assert!(!entries.is_empty());
+        for entry in &entries {
+            assert!(!entry.specs.is_empty(), "{}", entry.exploit.title);
+            assert_eq!(entry.product, entry.product.to_lowercase());
+        }
+        assert!(map_entries("[[exploit]]\nproduct = ").is_empty());
+    }
+
+    #[test]
+    fn box_services_find_their_candidates() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.actions.push(crate::models::Action {
+            timestamp: chrono::Utc::now(),
+            command: "nmap -sV 10.10.10.5".to_string(),
+            result: crate::models::ActionResult::Success,
+            note: None,
+            output: Some(
+                "21/tcp open ftp vsftpd 2.3.4\n\
+                 22/tcp open ssh OpenSSH 9.9p1\n\
+                 139/tcp open netbios-ssn Samba smbd 3.0.20-Debian\n\
+                 80/tcp open http"
+                    .to_string(),
+            ),
+        });
+        let candidates = candidates(&ctf_box, &config());
+        let found: Vec<(u16, String)> = candidates
+            .iter()
+            .map(|c| (c.service.port, c.exploit.reference()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (21, "EDB-49757 CVE-2011-2523".to_string()),
+                (139, "EDB-16320 CVE-2007-2447".to_string()),
+            ]
+        );
+        assert_eq!(
+            candidates[0].note(),
+            "vsftpd 2.3.4 (21/tcp): EDB-49757 CVE-2011-2523 - \
+             vsftpd 2.3.4 - Backdoor Command Execution"
+        );
+    }
+}
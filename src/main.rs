@@ -1,27 +1,58 @@
 mod app;
+mod checklist;
+mod cli;
+mod clipboard;
+mod config;
+mod exploits;
+mod fileserver;
+mod fuzzy;
+mod hosts;
+mod keymap;
+mod listener;
 mod models;
+mod network;
+mod payloads;
+mod quote;
+mod reachability;
+mod recon;
+mod redact;
+mod snippets;
+mod stats;
 mod storage;
+mod suggest;
+mod timeline;
+mod tmux;
 mod ui;
+mod update;
 
 use anyhow::Result;
-use app::{AddBoxForm, App, AppView, EnvVarForm, NoteForm, StatusKind};
+use app::{App, AppView};
 use crossterm::{
     cursor::Show,
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste,
+        EnableMouseCapture, Event,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{
-    Terminal,
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-};
-use std::collections::HashMap;
+use ratatui::{Terminal, backend::CrosstermBackend, layout::Rect};
+use std::collections::{HashMap, VecDeque};
 use std::io;
+use update::{Effect, Message};
 
 fn main() -> Result<()> {
+    // Subcommands (loot, ...) run without the TUI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        return cli::run(&args);
+    }
+
+    // Load ~/.config/ctf-brain/config.toml (defaults if missing)
+    let config = config::Config::load_or_default();
+
     // Install shell hook if not present
-    if let Err(e) = storage::ensure_shell_hook_installed() {
+    if let Err(e) = storage::ensure_shell_hook_installed(&config) {
         eprintln!("Warning: Failed to install shell hook: {}", e);
     }
 
@@ -66,14 +97,22 @@ fn main() -> Result<()> {
                         category: models::NoteCategory::Recon,
                         content: "SMB version is outdated - potential exploit".to_string(),
                         created_date: chrono::Utc::now(),
+                        images: Vec::new(),
                     },
                     models::Note {
                         category: models::NoteCategory::Foothold,
                         content: "Try CVE-2007-2447 for vsftpd".to_string(),
                         created_date: chrono::Utc::now(),
+                        images: Vec::new(),
                     },
                 ],
                 env_vars: HashMap::new(),
+                attachments: Vec::new(),
+                flags: Vec::new(),
+                sessions: Vec::new(),
+                checklists: Vec::new(),
+                hostnames: vec!["lame.htb".to_string()],
+                outages: Vec::new(),
             },
             models::CtfBox {
                 id: 2,
@@ -88,8 +127,15 @@ fn main() -> Result<()> {
                     category: models::NoteCategory::Web,
                     content: "SQL injection vulnerability in login form".to_string(),
                     created_date: chrono::Utc::now(),
+                    images: Vec::new(),
                 }],
                 env_vars: HashMap::new(),
+                attachments: Vec::new(),
+                flags: Vec::new(),
+                sessions: Vec::new(),
+                checklists: Vec::new(),
+                hostnames: Vec::new(),
+                outages: Vec::new(),
             },
             models::CtfBox {
                 id: 3,
@@ -108,439 +154,97 @@ fn main() -> Result<()> {
                 }],
                 notes: vec![],
                 env_vars: HashMap::new(),
+                attachments: Vec::new(),
+                flags: Vec::new(),
+                sessions: Vec::new(),
+                checklists: Vec::new(),
+                hostnames: Vec::new(),
+                outages: Vec::new(),
             },
         ];
     }
 
-    let mut app = App::new(boxes);
-    let mut add_box_form: Option<AddBoxForm> = None;
-    let mut env_var_form: Option<EnvVarForm> = None;
-    let mut note_form: Option<NoteForm> = None;
+    let mut app = App::new(boxes, config);
+    app.input_history = storage::load_input_history();
+
+    // Loot comes from the index, screenshots may have been added from the
+    // CLI while the TUI was closed
+    let listed: Vec<(i32, Vec<models::Attachment>)> =
+        app.boxes.iter().map(|b| (b.id, b.attachments.clone())).collect();
+    for (id, attachments) in listed {
+        if let Ok(loot) = storage::adopt_loot(id, &attachments, &app.config) {
+            let _ = app.set_loot(id, loot);
+        }
+        if let Ok(notes) = storage::take_pending_notes(id, &app.config) {
+            let _ = app.merge_notes(id, notes);
+        }
+    }
 
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    // Screenshot thumbnail currently on screen: (box id, sha256, cells)
+    let mut drawn_thumbnail: Option<(i32, String, Rect)> = None;
+
     // Main loop
     loop {
-        // Expire old status messages
-        app.tick_status();
-
         // Render
-        terminal.draw(|f| {
-            let area = f.area();
+        let mut drawn = (Rect::default(), Vec::new());
+        terminal.draw(|f| drawn = ui::draw(f, &app))?;
+        let (main_area, clickables) = drawn;
+        app.clickables = clickables;
 
-            // Footer height: 3 if status message, 2 otherwise
-            let footer_height = if app.status_message.is_some() { 3 } else { 2 };
-
-            // Create layout with footer
-            let main_chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Min(0),                      // Main content
-                    Constraint::Length(footer_height),        // Footer
-                ])
-                .split(area);
-
-            // Render main view
-            match &app.view {
-                AppView::List => ui::list::render(f, &app, main_chunks[0]),
-                AppView::Details(id) => ui::detail::render(f, &app, main_chunks[0], *id),
-                AppView::DeleteBox(id) => {
-                    ui::list::render(f, &app, main_chunks[0]);
-                    ui::delete_box::render(f, &app, main_chunks[0], *id);
-                }
-                AppView::AddBox => {
-                    ui::list::render(f, &app, main_chunks[0]);
-                    if let Some(form) = &add_box_form {
-                        ui::add_box::render(f, &app, form, main_chunks[0]);
-                    }
-                }
-                AppView::EditEnvVars(id) => {
-                    ui::edit_env_vars::render(f, &app, env_var_form.as_ref(), main_chunks[0], *id);
-                }
-                AppView::EditNotes(id) => {
-                    ui::edit_notes::render(f, &app, note_form.as_ref(), main_chunks[0], *id);
-                }
-                AppView::WriteupExport(id) => {
-                    ui::writeup_export::render(f, &app, main_chunks[0], *id);
+        // Draw the screenshot thumbnail of the Details view over the frame
+        let wanted_thumbnail = match app.view {
+            AppView::Details(id) if app.graphics.is_supported() => app
+                .boxes
+                .iter()
+                .find(|b| b.id == id)
+                .and_then(|b| {
+                    let image = ui::detail::latest_image(b)?;
+                    let cells = ui::detail::thumbnail_area(b, main_area, &app.config)?;
+                    Some((id, image.sha256.clone(), cells))
+                }),
+            _ => None,
+        };
+        if wanted_thumbnail != drawn_thumbnail {
+            if drawn_thumbnail.take().is_some() {
+                ui::graphics::clear_images(&mut io::stdout(), app.graphics)?;
+                if app.graphics != ui::graphics::GraphicsProtocol::Kitty {
+                    // Sixel/iTerm2 pixels stay in the cells until repainted
+                    terminal.clear()?;
+                    continue;
                 }
             }
-
-            // Render footer with shortcuts + optional status
-            ui::footer::render_footer(f, &app.view, app.status_message.as_ref(), main_chunks[1]);
-        })?;
+            if let Some((id, sha, cells)) = &wanted_thumbnail
+                && let Ok(path) = storage::loot_blob_path(*id, sha, &app.config)
+            {
+                let _ = ui::graphics::draw_image(&mut io::stdout(), app.graphics, &path, *cells);
+            }
+            drawn_thumbnail = wanted_thumbnail;
+        }
 
         // Handle input
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                // Only handle key press events, not release
-                if key.kind != KeyEventKind::Press {
-                    continue;
-                }
-                // Handle env vars form
-                if let AppView::EditEnvVars(box_id) = app.view {
-                    if let Some(form) = &mut env_var_form {
-                        match key.code {
-                            KeyCode::Char(c) => {
-                                if form.current_field == 0 {
-                                    form.key.push(c);
-                                } else {
-                                    form.value.push(c);
-                                }
-                            }
-                            KeyCode::Backspace => {
-                                if form.current_field == 0 {
-                                    form.key.pop();
-                                } else {
-                                    form.value.pop();
-                                }
-                            }
-                            KeyCode::Tab => {
-                                app.next_env_field(form);
-                            }
-                            KeyCode::BackTab => {
-                                app.previous_env_field(form);
-                            }
-                            KeyCode::Enter => {
-                                match app.add_env_var(box_id, form.key.clone(), form.value.clone())
-                                {
-                                    Ok(_) => {
-                                        if let Err(e) = storage::save_boxes(&app.boxes) {
-                                            app.set_status(format!("Save failed: {}", e), StatusKind::Error);
-                                        } else {
-                                            app.set_status("Variable added", StatusKind::Success);
-                                        }
-                                        // Reset form
-                                        form.key.clear();
-                                        form.value.clear();
-                                        form.current_field = 0;
-                                    }
-                                    Err(e) => {
-                                        app.set_status(e, StatusKind::Error);
-                                    }
-                                }
-                            }
-                            KeyCode::Esc => {
-                                app.view = AppView::Details(box_id);
-                                env_var_form = None;
-                            }
-                            _ => {}
-                        }
-                    } else {
-                        // No form active, just viewing
-                        match key.code {
-                            KeyCode::Char('a') => {
-                                env_var_form = app.start_edit_env_vars(box_id);
-                            }
-                            KeyCode::Char('j') | KeyCode::Down => {
-                                app.next_env_var(box_id);
-                            }
-                            KeyCode::Char('k') | KeyCode::Up => {
-                                app.previous_env_var(box_id);
-                            }
-                            KeyCode::Char('d') => {
-                                match app.delete_selected_env_var(box_id) {
-                                    Ok(_) => {
-                                        if let Err(e) = storage::save_boxes(&app.boxes) {
-                                            app.set_status(format!("Save failed: {}", e), StatusKind::Error);
-                                        } else {
-                                            app.set_status("Variable deleted", StatusKind::Success);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        app.set_status(e, StatusKind::Error);
-                                    }
-                                }
-                            }
-                            KeyCode::Esc => {
-                                app.view = AppView::Details(box_id);
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-                // Handle EditNotes view
-                else if let AppView::EditNotes(box_id) = app.view {
-                    if let Some(form) = &mut note_form {
-                        match key.code {
-                            KeyCode::Char(c) => {
-                                form.content.push(c);
-                            }
-                            KeyCode::Backspace => {
-                                form.content.pop();
-                            }
-                            KeyCode::Left => {
-                                let categories_len = App::note_categories().len();
-                                if form.category_index == 0 {
-                                    form.category_index = categories_len - 1;
-                                } else {
-                                    form.category_index -= 1;
-                                }
-                            }
-                            KeyCode::Right => {
-                                let categories_len = App::note_categories().len();
-                                form.category_index = (form.category_index + 1) % categories_len;
-                            }
-                            KeyCode::Enter => {
-                                match app.add_note(box_id, form.category_index, form.content.clone()) {
-                                    Ok(_) => {
-                                        if let Err(e) = storage::save_boxes(&app.boxes) {
-                                            app.set_status(format!("Save failed: {}", e), StatusKind::Error);
-                                        } else {
-                                            app.set_status("Note added", StatusKind::Success);
-                                        }
-                                        // Reset form
-                                        form.content.clear();
-                                        form.category_index = 0;
-                                    }
-                                    Err(e) => {
-                                        app.set_status(e, StatusKind::Error);
-                                    }
-                                }
-                            }
-                            KeyCode::Esc => {
-                                app.view = AppView::Details(box_id);
-                                note_form = None;
-                            }
-                            _ => {}
-                        }
-                    } else {
-                        // No form active, just viewing
-                        match key.code {
-                            KeyCode::Char('a') => {
-                                note_form = Some(NoteForm {
-                                    content: String::new(),
-                                    category_index: 0,
-                                });
-                            }
-                            KeyCode::Char('j') | KeyCode::Down => {
-                                app.next_note(box_id);
-                            }
-                            KeyCode::Char('k') | KeyCode::Up => {
-                                app.previous_note(box_id);
-                            }
-                            KeyCode::Char('d') => {
-                                match app.delete_selected_note(box_id) {
-                                    Ok(_) => {
-                                        if let Err(e) = storage::save_boxes(&app.boxes) {
-                                            app.set_status(format!("Save failed: {}", e), StatusKind::Error);
-                                        } else {
-                                            app.set_status("Note deleted", StatusKind::Success);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        app.set_status(e, StatusKind::Error);
-                                    }
-                                }
-                            }
-                            KeyCode::Esc => {
-                                app.view = AppView::Details(box_id);
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-                // Handle WriteupExport view
-                else if let AppView::WriteupExport(box_id) = app.view {
-                    match key.code {
-                        KeyCode::Char(c) => {
-                            app.writeup_path.push(c);
-                        }
-                        KeyCode::Backspace => {
-                            app.writeup_path.pop();
-                        }
-                        KeyCode::Enter => {
-                            match app.generate_writeup(box_id) {
-                                Ok(path) => {
-                                    app.set_status(
-                                        format!("Write-up exported → {}", path.display()),
-                                        StatusKind::Success,
-                                    );
-                                    app.view = AppView::Details(box_id);
-                                }
-                                Err(e) => {
-                                    app.set_status(e, StatusKind::Error);
-                                }
-                            }
-                        }
-                        KeyCode::Esc => {
-                            app.view = AppView::Details(box_id);
-                        }
-                        _ => {}
-                    }
-                }
-                // If in AddBox form, handle text input first
-                else if app.view == AppView::AddBox {
-                    if let Some(form) = &mut add_box_form {
-                        match key.code {
-                            KeyCode::Char(c) => match form.current_field {
-                                0 => form.title.push(c),
-                                1 => form.platform.push(c),
-                                2 => form.ip.push(c),
-                                3 => form.tags.push(c),
-                                _ => {}
-                            },
-                            KeyCode::Tab => app.next_field(form),
-                            KeyCode::BackTab => app.previous_field(form),
-                            KeyCode::Backspace => match form.current_field {
-                                0 => {
-                                    form.title.pop();
-                                }
-                                1 => {
-                                    form.platform.pop();
-                                }
-                                2 => {
-                                    form.ip.pop();
-                                }
-                                3 => {
-                                    form.tags.pop();
-                                }
-                                _ => {}
-                            },
-                            KeyCode::Enter => match app.submit_add_box(form) {
-                                Ok(_) => {
-                                    if let Err(e) = storage::save_boxes(&app.boxes) {
-                                        app.set_status(format!("Save failed: {}", e), StatusKind::Error);
-                                    } else {
-                                        app.set_status("Box added", StatusKind::Success);
-                                    }
-                                    add_box_form = None;
-                                }
-                                Err(e) => {
-                                    app.set_status(e, StatusKind::Error);
-                                }
-                            },
-                            KeyCode::Esc => {
-                                app.cancel_form();
-                                add_box_form = None;
-                            }
-                            _ => {}
-                        }
-                    }
-                } else {
-                    // Handle other views
-                    match key.code {
-                        KeyCode::Char('q') => app.quit(),
-                        KeyCode::Char('a') if app.view == AppView::List => {
-                            add_box_form = Some(app.start_add_box());
-                        }
-                        KeyCode::Char('d') if app.view == AppView::List => app.start_delete_box(),
-                        KeyCode::Char('j') | KeyCode::Down if app.view == AppView::List => {
-                            app.next()
-                        }
-                        KeyCode::Char('k') | KeyCode::Up if app.view == AppView::List => {
-                            app.previous()
-                        }
-                        KeyCode::Enter if app.view == AppView::List => app.select_current(),
-                        // Touche 'e' dans Details pour éditer env vars
-                        KeyCode::Char('e') if matches!(app.view, AppView::Details(_)) => {
-                            if let AppView::Details(id) = app.view {
-                                env_var_form = app.start_edit_env_vars(id);
-                            }
-                        }
-                        // Touche 'n' dans Details pour éditer notes
-                        KeyCode::Char('n') if matches!(app.view, AppView::Details(_)) => {
-                            if let AppView::Details(id) = app.view {
-                                app.start_edit_notes(id);
-                            }
-                        }
-                        // Touche 'w' dans Details pour ouvrir l'export write-up
-                        KeyCode::Char('w') if matches!(app.view, AppView::Details(_)) => {
-                            if let AppView::Details(id) = app.view {
-                                app.start_writeup_export(id);
-                            }
-                        }
-                        // Touche 'l' pour lancer le shell
-                        KeyCode::Char('l') => match &app.view {
-                            AppView::List => {
-                                if let Some(idx) = app.selected_box_id {
-                                    if let Some(ctf_box) = app.boxes.get(idx as usize) {
-                                        let box_id = ctf_box.id;
-                                        disable_raw_mode()?;
-                                        execute!(
-                                            terminal.backend_mut(),
-                                            LeaveAlternateScreen,
-                                            Show
-                                        )?;
-
-                                        if let Err(e) = app.launch_box_shell(box_id) {
-                                            eprintln!("Failed to launch shell: {}", e);
-                                        }
-
-                                        // Import shell logs after returning
-                                        match app.import_shell_logs(box_id) {
-                                            Ok(count) if count > 0 => {
-                                                if let Err(e) = storage::save_boxes(&app.boxes) {
-                                                    eprintln!("Save failed: {}", e);
-                                                }
-                                            }
-                                            _ => {}
-                                        }
-
-                                        enable_raw_mode()?;
-                                        execute!(terminal.backend_mut(), EnterAlternateScreen)?;
-                                        terminal.clear()?;
-
-                                        // Show status in TUI after returning
-                                        app.set_status("Shell session ended — commands imported", StatusKind::Info);
-                                    }
-                                }
-                            }
-                            AppView::Details(id) => {
-                                let box_id = *id;
-                                disable_raw_mode()?;
-                                execute!(terminal.backend_mut(), LeaveAlternateScreen, Show)?;
-
-                                if let Err(e) = app.launch_box_shell(box_id) {
-                                    eprintln!("Failed to launch shell: {}", e);
-                                }
-
-                                // Import shell logs after returning
-                                match app.import_shell_logs(box_id) {
-                                    Ok(count) if count > 0 => {
-                                        if let Err(e) = storage::save_boxes(&app.boxes) {
-                                            eprintln!("Save failed: {}", e);
-                                        }
-                                    }
-                                    _ => {}
-                                }
-
-                                enable_raw_mode()?;
-                                execute!(terminal.backend_mut(), EnterAlternateScreen)?;
-                                terminal.clear()?;
+        let message = if event::poll(std::time::Duration::from_millis(100))? {
+            match event::read()? {
+                Event::Key(key) => Message::Key(key),
+                Event::Paste(text) => Message::Paste(text),
+                Event::Mouse(mouse) => Message::Mouse(mouse),
+                _ => Message::Tick,
+            }
+        } else {
+            Message::Tick
+        };
 
-                                app.set_status("Shell session ended — commands imported", StatusKind::Info);
-                            }
-                            _ => {}
-                        },
-                        KeyCode::Esc => {
-                            if let AppView::DeleteBox(_) = app.view {
-                                app.cancel_delete();
-                            } else {
-                                app.go_back();
-                            }
-                        }
-                        KeyCode::Char(c) => {
-                            if let AppView::DeleteBox(id) = app.view {
-                                if c == 'y' || c == 'Y' {
-                                    app.confirm_delete_box(id);
-                                    if let Err(e) = storage::save_boxes(&app.boxes) {
-                                        app.set_status(format!("Save failed: {}", e), StatusKind::Error);
-                                    } else {
-                                        app.set_status("Box deleted", StatusKind::Success);
-                                    }
-                                } else if c == 'n' || c == 'N' {
-                                    app.cancel_delete();
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
+        // Effects report back with another message
+        let mut messages = VecDeque::from([message]);
+        while let Some(message) = messages.pop_front() {
+            for effect in app.update(message) {
+                messages.push_back(run_effect(&mut terminal, &app, effect)?);
             }
         }
 
@@ -550,13 +254,110 @@ fn main() -> Result<()> {
     }
 
     // Save boxes before exit
-    if let Err(e) = storage::save_boxes(&app.boxes) {
+    if let Err(e) = storage::save_boxes(&app.boxes, &app.config) {
         eprintln!("Warning: Failed to save boxes: {}", e);
     }
+    if let Err(e) = storage::save_input_history(&app.input_history) {
+        eprintln!("Warning: Failed to save input history: {}", e);
+    }
 
     // Cleanup
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableBracketedPaste,
+        DisableMouseCapture
+    )?;
 
     Ok(())
 }
+
+/// Run an effect, with the TUI suspended if it needs the terminal,
+/// returning its outcome
+fn run_effect(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &App,
+    effect: Effect,
+) -> Result<Message> {
+    if !effect.needs_terminal() {
+        return Ok(perform(app, effect));
+    }
+    with_tui_suspended(terminal, || perform(app, effect))
+}
+
+fn perform(app: &App, effect: Effect) -> Message {
+    match effect {
+        Effect::Save(status) => Message::Saved {
+            result: storage::save_boxes(&app.boxes, &app.config).map_err(|e| e.to_string()),
+            status,
+        },
+        Effect::ImportActivity { box_id, report } => Message::ActivityImported {
+            box_id,
+            activity: storage::take_box_activity(box_id, &app.config)
+                .map_err(|e| format!("Failed to import activity: {}", e)),
+            report,
+        },
+        Effect::LaunchShell(box_id) => {
+            let started = chrono::Utc::now();
+            Message::ShellExited {
+                box_id,
+                started,
+                result: app.launch_box_shell(box_id),
+            }
+        }
+        Effect::OpenTmux(box_id) => Message::TmuxDetached {
+            box_id,
+            result: app.open_tmux(box_id),
+        },
+        Effect::ComposeNote(box_id, form) => Message::NoteComposed {
+            box_id,
+            content: app.compose_note(box_id, &form),
+            form,
+        },
+        Effect::OpenAttachment { box_id, force_hex } => {
+            Message::PagerExited(app.open_attachment(box_id, force_hex))
+        }
+        Effect::RunCommand { box_id, command } => Message::CommandFinished {
+            box_id,
+            result: app.run_in_box_shell(box_id, &command),
+        },
+        Effect::WriteHosts(content) => Message::HostsWritten {
+            result: hosts::write(&app.config.hosts_path(), &content),
+            content,
+        },
+        Effect::WriteHostsWithSudo(content) => Message::HostsWritten {
+            result: hosts::write_with_sudo(&app.config.hosts_path(), &content)
+                .map(|()| hosts::Written::Done),
+            content,
+        },
+    }
+}
+
+/// Leave the TUI to run an interactive program (pager, editor...), then restore it
+fn with_tui_suspended<T>(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    run: impl FnOnce() -> T,
+) -> Result<T> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableBracketedPaste,
+        DisableMouseCapture,
+        Show
+    )?;
+
+    let result = run();
+
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableBracketedPaste,
+        EnableMouseCapture
+    )?;
+    terminal.clear()?;
+
+    Ok(result)
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// A file stored in the box's loot directory.
+/// The blob lives at `~/.ctf-brain/boxes/<id>/loot/<sha256>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub name: String,
+    pub sha256: String,
+    pub size: u64,
+    pub mime: String,
+    // Where the file came from (remote path, "screenshot", ...)
+    pub source: String,
+    pub note: Option<String>,
+    #[serde(default = "Utc::now")]
+    pub created_date: DateTime<Utc>,
+}
+
+impl Attachment {
+    /// Whether the file can be shown as text in a pager
+    pub fn is_text(&self) -> bool {
+        self.mime.starts_with("text/")
+            || self.mime == "application/json"
+            || self.mime == "application/x-pem-file"
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use std::net::IpAddr;
 use std::collections::HashMap;
-use super::{Action, Note};
+use super::{Action, Attachment, Checklist, Flag, FlagKind, Note, Outage, ShellSession};
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,4 +20,107 @@ pub struct CtfBox {
     // Default to empty HashMap if not present in JSON (backward compatibility)
     #[serde(default)]
     pub env_vars: HashMap<String, String>,
+
+    // Files grabbed from the target, read from the loot index (see
+    // storage::loot) which is the only place they are saved. Data files
+    // written by older versions still list them, see storage::adopt_loot.
+    #[serde(default, skip_serializing)]
+    pub attachments: Vec<Attachment>,
+
+    #[serde(default)]
+    pub flags: Vec<Flag>,
+
+    #[serde(default)]
+    pub sessions: Vec<ShellSession>,
+
+    #[serde(default)]
+    pub checklists: Vec<Checklist>,
+
+    /// Names the box answers to (`box.htb`), kept in the hosts file
+    #[serde(default)]
+    pub hostnames: Vec<String>,
+
+    #[serde(default)]
+    pub outages: Vec<Outage>,
+}
+
+/// Progress on a box, derived from its flags and activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BoxStatus {
+    New,
+    InProgress,
+    /// Only non user/root flags (jeopardy challenges)
+    Solved,
+    User,
+    Rooted,
+}
+
+impl BoxStatus {
+    pub const ALL: [BoxStatus; 5] = [
+        BoxStatus::New,
+        BoxStatus::InProgress,
+        BoxStatus::Solved,
+        BoxStatus::User,
+        BoxStatus::Rooted,
+    ];
+
+    /// Status reached with some activity (or not) and these flags
+    pub fn of(activity: bool, flags: impl IntoIterator<Item = FlagKind>) -> Self {
+        flags
+            .into_iter()
+            .map(|kind| match kind {
+                FlagKind::Root => BoxStatus::Rooted,
+                FlagKind::User => BoxStatus::User,
+                FlagKind::Other => BoxStatus::Solved,
+            })
+            .max()
+            .unwrap_or(if activity { BoxStatus::InProgress } else { BoxStatus::New })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BoxStatus::New => "new",
+            BoxStatus::InProgress => "in progress",
+            BoxStatus::Solved => "solved",
+            BoxStatus::User => "user",
+            BoxStatus::Rooted => "rooted",
+        }
+    }
+}
+
+impl CtfBox {
+    /// First flag of this kind, if captured
+    pub fn flag(&self, kind: FlagKind) -> Option<&Flag> {
+        self.flags.iter().find(|f| f.kind == kind)
+    }
+
+    pub fn status(&self) -> BoxStatus {
+        let activity = !self.actions.is_empty() || !self.notes.is_empty();
+        BoxStatus::of(activity, self.flags.iter().map(|f| f.kind))
+    }
+}
+
+#[cfg(test)]
+impl CtfBox {
+    /// Box with nothing recorded yet
+    pub fn sample(id: i32, title: &str) -> Self {
+        CtfBox {
+            id,
+            title: title.to_string(),
+            platform: "HTB".to_string(),
+            ip_address: "10.10.10.5".parse().unwrap(),
+            tags: Vec::new(),
+            created_date: Utc::now(),
+            updated_date: Utc::now(),
+            actions: Vec::new(),
+            notes: Vec::new(),
+            env_vars: HashMap::new(),
+            attachments: Vec::new(),
+            flags: Vec::new(),
+            sessions: Vec::new(),
+            checklists: Vec::new(),
+            hostnames: Vec::new(),
+            outages: Vec::new(),
+        }
+    }
 }
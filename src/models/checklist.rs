@@ -0,0 +1,30 @@
+use serde::{Serialize, Deserialize};
+
+/// A methodology checklist instantiated on a box (see `crate::checklist`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checklist {
+    /// Id of the template it was created from
+    pub template: String,
+    pub name: String,
+    pub items: Vec<ChecklistItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub text: String,
+    /// Regexes of the commands that tick the item
+    #[serde(default)]
+    pub auto: Vec<String>,
+    #[serde(default)]
+    pub done: bool,
+    /// Command that ticked the item. Set once: an item unticked by hand
+    /// is not ticked again by the same or a later command.
+    #[serde(default)]
+    pub matched: Option<String>,
+}
+
+impl Checklist {
+    pub fn done_count(&self) -> usize {
+        self.items.iter().filter(|i| i.done).count()
+    }
+}
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlagKind {
+    User,
+    Root,
+    // Jeopardy challenges and extra flags
+    Other,
+}
+
+/// A flag captured on the box
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Flag {
+    pub kind: FlagKind,
+    pub value: String,
+    pub captured_date: DateTime<Utc>,
+}
+
+impl FlagKind {
+    pub const ALL: [FlagKind; 3] = [FlagKind::User, FlagKind::Root, FlagKind::Other];
+}
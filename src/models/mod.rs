@@ -1,7 +1,17 @@
 mod action;
+mod attachment;
+mod checklist;
+mod flag;
 mod r#box;
 mod note;
+mod outage;
+mod session;
 
 pub use action::*;
+pub use attachment::*;
+pub use checklist::*;
+pub use flag::*;
 pub use r#box::*;
 pub use note::*;
+pub use outage::*;
+pub use session::*;
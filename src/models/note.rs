@@ -19,4 +19,27 @@ pub struct Note {
     pub category: NoteCategory,
     pub content: String,
     pub created_date: DateTime<Utc>,
+
+    // sha256 of image attachments shown with this note (screenshots)
+    #[serde(default)]
+    pub images: Vec<String>,
+}
+
+impl NoteCategory {
+    /// Parse a category name, case-insensitively ("web", "Privesc", ...)
+    pub fn parse(name: &str) -> Option<Self> {
+        let all = [
+            NoteCategory::Recon,
+            NoteCategory::Foothold,
+            NoteCategory::Privesc,
+            NoteCategory::Web,
+            NoteCategory::Pwn,
+            NoteCategory::Crypto,
+            NoteCategory::Reversing,
+            NoteCategory::Stego,
+            NoteCategory::Misc,
+        ];
+        all.into_iter()
+            .find(|c| format!("{:?}", c).eq_ignore_ascii_case(name.trim()))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// A time the box stopped answering, seen by the TUI's reachability checks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Outage {
+    pub started: DateTime<Utc>,
+    /// None while the box is still unreachable
+    pub ended: Option<DateTime<Utc>>,
+    /// The VPN interface was gone when it started
+    pub vpn_down: bool,
+}
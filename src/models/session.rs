@@ -0,0 +1,9 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// A box shell launched from the TUI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellSession {
+    pub started: DateTime<Utc>,
+    pub ended: DateTime<Utc>,
+}
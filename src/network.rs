@@ -0,0 +1,150 @@
+use std::net::Ipv4Addr;
+use std::path::Path;
+
+/// The interface exists (a VPN interface disappears when it goes down)
+pub fn interface_up(name: &str) -> bool {
+    Path::new("/sys/class/net").join(name).exists()
+}
+
+/// IPv4 address of an interface, such as the VPN's `tun0`: the address of
+/// this host inside a network routed through the interface, read from the
+/// kernel's tables in /proc rather than by running `ip`
+pub fn interface_address(name: &str) -> Result<Ipv4Addr, String> {
+    if !interface_up(name) {
+        return Err(format!("Interface {} is not up (VPN not connected?)", name));
+    }
+    let read = |path: &str| {
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+    };
+    let networks = interface_networks(&read("/proc/net/route")?, name);
+    local_addresses(&read("/proc/net/fib_trie")?)
+        .into_iter()
+        .find(|address| {
+            networks
+                .iter()
+                .any(|(network, mask)| u32::from(*address) & mask == *network)
+        })
+        .ok_or_else(|| format!("Interface {} has no IPv4 address", name))
+}
+
+/// Networks (address, mask) the interface reaches directly, from
+/// /proc/net/route: `tun0  000E0A0A  00000000  0001 ... 00FEFFFF ...`
+/// where addresses are hexadecimal in the host's byte order
+fn interface_networks(route: &str, name: &str) -> Vec<(u32, u32)> {
+    let address = |hex: &str| {
+        u32::from_str_radix(hex, 16)
+            .ok()
+            .map(|raw| u32::from(Ipv4Addr::from(raw.to_ne_bytes())))
+    };
+    route
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // Without gateway, and not the default route
+            if fields.len() < 8 || fields[0] != name || address(fields[2])? != 0 {
+                return None;
+            }
+            let mask = address(fields[7])?;
+            (mask != 0).then_some((address(fields[1])? & mask, mask))
+        })
+        .collect()
+}
+
+/// Addresses of this host, the leaves of /proc/net/fib_trie followed by
+/// a `/32 host LOCAL` line
+fn local_addresses(fib_trie: &str) -> Vec<Ipv4Addr> {
+    let mut addresses: Vec<Ipv4Addr> = Vec::new();
+    let mut leaf: Option<Ipv4Addr> = None;
+    for line in fib_trie.lines() {
+        let line = line.trim();
+        if let Some(address) = line.strip_prefix("|-- ") {
+            leaf = address.parse().ok();
+        } else if line.starts_with("/32 host LOCAL")
+            && let Some(address) = leaf
+            && !addresses.contains(&address)
+        {
+            addresses.push(address);
+        }
+    }
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUTE: &str = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t00000000\t010200C0\t0003\t0\t0\t0\t00000000\t0\t0\t0
+eth0\t000200C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0
+tun0\t000E0A0A\t00000000\t0001\t0\t0\t0\t00FEFFFF\t0\t0\t0
+tun0\t00000A0A\t010E0A0A\t0003\t0\t0\t0\t00FEFFFF\t0\t0\t0
+";
+
+    const FIB_TRIE: &str = "\
+Main:
+  +-- 0.0.0.0/0 3 0 5
+     |-- 0.0.0.0
+        /0 universe UNICAST
+     +-- 10.10.14.0/23 2 0 2
+        |-- 10.10.14.0
+           /23 link UNICAST
+        |-- 10.10.14.5
+           /32 host LOCAL
+        |-- 10.10.15.255
+           /32 link BROADCAST
+     +-- 127.0.0.0/8 2 0 2
+        +-- 127.0.0.0/31 1 0 0
+           |-- 127.0.0.0
+              /8 host LOCAL
+           |-- 127.0.0.1
+              /32 host LOCAL
+     +-- 192.0.2.0/24 2 0 2
+        |-- 192.0.2.2
+           /32 host LOCAL
+Local:
+  +-- 0.0.0.0/0 3 0 5
+     |-- 10.10.14.5
+        /32 host LOCAL
+";
+
+    #[test]
+    fn networks_are_the_link_routes_of_the_interface() {
+        let network = |address: [u8; 4], mask: [u8; 4]| {
+            (u32::from(Ipv4Addr::from(address)), u32::from(Ipv4Addr::from(mask)))
+        };
+        // The table is in the byte order of the little-endian kernel that wrote it
+        if cfg!(target_endian = "little") {
+            assert_eq!(
+                interface_networks(ROUTE, "tun0"),
+                [network([10, 10, 14, 0], [255, 255, 254, 0])]
+            );
+            assert_eq!(
+                interface_networks(ROUTE, "eth0"),
+                [network([192, 0, 2, 0], [255, 255, 255, 0])]
+            );
+        }
+        assert!(interface_networks(ROUTE, "tun1").is_empty());
+        assert!(interface_networks("", "tun0").is_empty());
+        assert!(interface_networks("Iface\ntun0\tzz\n", "tun0").is_empty());
+    }
+
+    #[test]
+    fn local_addresses_are_the_host_leaves() {
+        assert_eq!(
+            local_addresses(FIB_TRIE),
+            [
+                Ipv4Addr::new(10, 10, 14, 5),
+                Ipv4Addr::new(127, 0, 0, 1),
+                Ipv4Addr::new(192, 0, 2, 2),
+            ]
+        );
+        assert!(local_addresses("").is_empty());
+    }
+
+    #[test]
+    fn missing_interface() {
+        assert!(interface_address("ctf-brain-none0").is_err());
+    }
+}
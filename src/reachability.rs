@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// How long a port or a ping may take to answer
+const TIMEOUT: Duration = Duration::from_secs(2);
+/// Known open ports tried before giving up on TCP
+const MAX_KNOWN_PORTS: usize = 3;
+/// Ports tried when no scan found any: a refused connection is an answer too
+const COMMON_PORTS: [u16; 5] = [22, 80, 443, 445, 3389];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Health {
+    /// Answered on `via` ("22/tcp", "ping") in this time
+    Up { via: String, rtt: Duration },
+    Down,
+}
+
+/// Outcome of one check of a box
+#[derive(Debug, Clone)]
+pub struct Probe {
+    pub box_id: i32,
+    pub checked: DateTime<Utc>,
+    pub health: Health,
+    /// The VPN interface exists
+    pub vpn_up: bool,
+}
+
+impl Probe {
+    pub fn is_up(&self) -> bool {
+        matches!(self.health, Health::Up { .. })
+    }
+
+    /// "up 34 ms (22/tcp)", "unreachable" or "VPN down"
+    pub fn label(&self) -> String {
+        match &self.health {
+            Health::Up { via, rtt } => format!("up {} ms ({})", rtt.as_millis(), via),
+            Health::Down if !self.vpn_up => "VPN down".to_string(),
+            Health::Down => "unreachable".to_string(),
+        }
+    }
+}
+
+/// Check the box in a thread: the known open TCP ports first, then a
+/// ping, then common ports. The receiver gets the probe when done.
+pub fn spawn(box_id: i32, ip: IpAddr, ports: Vec<u16>, interface: String) -> Receiver<Probe> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let health = ports
+            .iter()
+            .take(MAX_KNOWN_PORTS)
+            .find_map(|port| tcp(ip, *port))
+            .or_else(|| ping(ip))
+            .or_else(|| COMMON_PORTS.iter().find_map(|port| tcp(ip, *port)))
+            .map_or(Health::Down, |(via, rtt)| Health::Up { via, rtt });
+        let _ = sender.send(Probe {
+            box_id,
+            checked: Utc::now(),
+            health,
+            vpn_up: crate::network::interface_up(&interface),
+        });
+    });
+    receiver
+}
+
+/// Time to connect, or to be refused
+fn tcp(ip: IpAddr, port: u16) -> Option<(String, Duration)> {
+    let start = Instant::now();
+    match TcpStream::connect_timeout(&SocketAddr::new(ip, port), TIMEOUT) {
+        Ok(_) => Some((format!("{}/tcp", port), start.elapsed())),
+        Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+            Some((format!("{}/tcp closed", port), start.elapsed()))
+        }
+        Err(_) => None,
+    }
+}
+
+/// One ICMP echo, when `ping` is installed and allowed to send it
+fn ping(ip: IpAddr) -> Option<(String, Duration)> {
+    let start = Instant::now();
+    let status = Command::new("ping")
+        .args(["-c", "1", "-W", &TIMEOUT.as_secs().to_string()])
+        .arg(ip.to_string())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .ok()?;
+    status.success().then(|| ("ping".to_string(), start.elapsed()))
+}
@@ -0,0 +1,176 @@
+use crate::models::CtfBox;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// An open port found in recon output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    pub port: u16,
+    /// tcp or udp
+    pub protocol: String,
+    /// Service name as nmap reports it (ftp, ssl/http, microsoft-ds...)
+    pub name: String,
+    /// Product and version ("vsftpd 2.3.4"), empty without `-sV`
+    pub version: String,
+}
+
+/// `21/tcp   open  ftp     vsftpd 2.3.4`
+fn normal_line() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(\d{1,5})/(tcp|udp)\s+open\s+(\S+)(?:\s+(.*))?$").expect("valid regex")
+    })
+}
+
+/// `21/open/tcp//ftp//vsftpd 2.3.4/` entries of a `Ports:` field
+fn grepable_entry() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(\d{1,5})/open/(tcp|udp)//([^/]*)//([^/]*)/").expect("valid regex")
+    })
+}
+
+/// Open ports in nmap output, normal (`-oN`, stdout) or grepable (`-oG`)
+pub fn parse_nmap(output: &str) -> Vec<Service> {
+    let mut services = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(caps) = normal_line().captures(line) {
+            let Ok(port) = caps[1].parse() else {
+                continue;
+            };
+            services.push(Service {
+                port,
+                protocol: caps[2].to_string(),
+                name: caps[3].trim_end_matches('?').to_string(),
+                version: caps.get(4).map_or("", |m| m.as_str()).trim().to_string(),
+            });
+        } else if line.starts_with("Host:") && line.contains("Ports:") {
+            for caps in grepable_entry().captures_iter(line) {
+                let Ok(port) = caps[1].parse() else {
+                    continue;
+                };
+                services.push(Service {
+                    port,
+                    protocol: caps[2].to_string(),
+                    name: caps[3].trim_end_matches('?').to_string(),
+                    version: caps[4].trim().to_string(),
+                });
+            }
+        }
+    }
+    services
+}
+
+/// Services of a box from the output of every scan it logged, by port.
+/// A later scan replaces what an earlier one found for the same port,
+/// unless it has less detail (no version).
+pub fn box_services(ctf_box: &CtfBox) -> Vec<Service> {
+    let mut by_port: BTreeMap<(u16, String), Service> = BTreeMap::new();
+    let mut actions: Vec<_> = ctf_box.actions.iter().collect();
+    actions.sort_by_key(|a| a.timestamp);
+
+    for output in actions.iter().filter_map(|a| a.output.as_deref()) {
+        for service in parse_nmap(output) {
+            let key = (service.port, service.protocol.clone());
+            match by_port.get(&key) {
+                Some(known) if service.version.is_empty() && !known.version.is_empty() => {}
+                _ => {
+                    by_port.insert(key, service);
+                }
+            }
+        }
+    }
+    by_port.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Action, ActionResult};
+    use chrono::{Duration, Utc};
+
+    fn service(port: u16, protocol: &str, name: &str, version: &str) -> Service {
+        Service {
+            port,
+            protocol: protocol.to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn normal_output() {
+        let output = "\
+Starting Nmap 7.94 ( https://nmap.org )
+PORT     STATE    SERVICE     VERSION
+21/tcp   open     ftp         vsftpd 2.3.4
+22/tcp   closed   ssh
+139/tcp  open     netbios-ssn Samba smbd 3.X - 4.X (workgroup: WORKGROUP)
+161/udp  open     snmp?
+443/tcp  filtered https
+8080/tcp open     http-proxy
+99999/tcp open    bogus
+";
+        assert_eq!(
+            parse_nmap(output),
+            [
+                service(21, "tcp", "ftp", "vsftpd 2.3.4"),
+                service(139, "tcp", "netbios-ssn", "Samba smbd 3.X - 4.X (workgroup: WORKGROUP)"),
+                service(161, "udp", "snmp", ""),
+                service(8080, "tcp", "http-proxy", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn grepable_output() {
+        let output = "\
+# Nmap 7.94 scan initiated
+Host: 10.10.10.5 ()\tStatus: Up
+Host: 10.10.10.5 ()\tPorts: 22/open/tcp//ssh//OpenSSH 4.7p1 Debian 8ubuntu1 (protocol 2.0)/, 25/closed/tcp//smtp///, 445/open/tcp//netbios-ssn?///\tIgnored State: filtered (995)
+";
+        assert_eq!(
+            parse_nmap(output),
+            [
+                service(22, "tcp", "ssh", "OpenSSH 4.7p1 Debian 8ubuntu1 (protocol 2.0)"),
+                service(445, "tcp", "netbios-ssn", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_ports_in_other_output() {
+        assert!(parse_nmap("").is_empty());
+        assert!(parse_nmap("total 8\ndrwxr-xr-x 2 root root 4096 .\n").is_empty());
+    }
+
+    #[test]
+    fn later_scans_win_unless_they_know_less() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        let start = Utc::now();
+        let scan = |minutes, output: &str| Action {
+            timestamp: start + Duration::minutes(minutes),
+            command: "nmap".to_string(),
+            result: ActionResult::Success,
+            note: None,
+            output: Some(output.to_string()),
+        };
+        // Logged out of order: the -sV scan ran after the quick one
+        ctf_box.actions = vec![
+            scan(10, "21/tcp open ftp vsftpd 2.3.4\n80/tcp open http Apache httpd 2.2.8"),
+            scan(20, "80/tcp open http nginx 1.18\n21/tcp open ftp"),
+            scan(0, "21/tcp open ftp\n3632/tcp open distccd"),
+            Action { output: None, ..scan(30, "") },
+        ];
+        assert_eq!(
+            box_services(&ctf_box),
+            [
+                service(21, "tcp", "ftp", "vsftpd 2.3.4"),
+                service(80, "tcp", "http", "nginx 1.18"),
+                service(3632, "tcp", "distccd", ""),
+            ]
+        );
+    }
+}
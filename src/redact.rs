@@ -0,0 +1,300 @@
+use crate::config::Config;
+use crate::models::CtfBox;
+use regex::Regex;
+
+/// How a detected secret is rewritten in the exported text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RedactMode {
+    /// Leave the text untouched
+    Off,
+    /// Replace the secret with `[REDACTED:<rule>]`
+    Placeholder,
+    /// Keep the first few characters and mask the rest with `*`
+    Mask,
+}
+
+impl RedactMode {
+    pub fn next(self) -> Self {
+        match self {
+            RedactMode::Off => RedactMode::Placeholder,
+            RedactMode::Placeholder => RedactMode::Mask,
+            RedactMode::Mask => RedactMode::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RedactMode::Off => "Off",
+            RedactMode::Placeholder => "Placeholder",
+            RedactMode::Mask => "Mask",
+        }
+    }
+}
+
+/// A named pattern that identifies a secret
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub regex: Regex,
+}
+
+/// A single secret found in the text
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub rule: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Built-in detectors, checked in order (earlier rules win on overlap)
+const BUILTIN_RULES: &[(&str, &str)] = &[
+    ("JWT", r"eyJ[A-Za-z0-9_-]+\.eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*"),
+    ("AWS_KEY", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("NTLM", r"\b[a-fA-F0-9]{32}:[a-fA-F0-9]{32}\b"),
+    ("FLAG", r"\b(?:HTB|THM|picoCTF|CTF|FLAG|flag)\{[^}\s]+\}"),
+    ("HASH_FLAG", r"\b[a-f0-9]{32}\b"),
+];
+
+/// Values shorter than this are not redacted when they come from env vars,
+/// otherwise a value like "1" would wipe half the write-up
+const MIN_ENV_VALUE_LEN: usize = 4;
+
+pub struct Redactor {
+    pub mode: RedactMode,
+    pub rules: Vec<Rule>,
+    /// Lines of `redact.rules` that could not be used, to show the user
+    pub problems: Vec<String>,
+}
+
+impl Redactor {
+    /// Build a redactor for a box: built-in detectors, the box's env var
+    /// values, then the user's own rules from `~/.ctf-brain/redact.rules`
+    pub fn for_box(ctf_box: &CtfBox, mode: RedactMode, config: &Config) -> Self {
+        Self::for_boxes(std::slice::from_ref(ctf_box), mode, config)
+    }
+
+    /// Same as `for_box` with the env var values of every box, for
+    /// exports that cover them all
+    pub fn for_boxes(boxes: &[CtfBox], mode: RedactMode, config: &Config) -> Self {
+        let mut rules: Vec<Rule> = BUILTIN_RULES
+            .iter()
+            .filter_map(|(name, pattern)| {
+                Regex::new(pattern).ok().map(|regex| Rule {
+                    name: name.to_string(),
+                    regex,
+                })
+            })
+            .collect();
+
+        // Env var values are literal secrets (tokens, passwords, cookies)
+        for ctf_box in boxes {
+            let mut keys: Vec<&String> = ctf_box.env_vars.keys().collect();
+            keys.sort();
+            for key in keys {
+                let value = &ctf_box.env_vars[key];
+                if value.len() < MIN_ENV_VALUE_LEN {
+                    continue;
+                }
+                if let Ok(regex) = Regex::new(&regex::escape(value)) {
+                    rules.push(Rule {
+                        name: key.clone(),
+                        regex,
+                    });
+                }
+            }
+        }
+
+        let (user_rules, problems) = load_user_rules(config);
+        rules.extend(user_rules);
+
+        Self { mode, rules, problems }
+    }
+
+    /// Find every secret in the text, without overlaps, ordered by position
+    pub fn find(&self, text: &str) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = Vec::new();
+        for rule in &self.rules {
+            for m in rule.regex.find_iter(text) {
+                if m.start() == m.end() {
+                    continue;
+                }
+                let overlaps = findings
+                    .iter()
+                    .any(|f| m.start() < f.end && f.start < m.end());
+                if !overlaps {
+                    findings.push(Finding {
+                        rule: rule.name.clone(),
+                        start: m.start(),
+                        end: m.end(),
+                    });
+                }
+            }
+        }
+        findings.sort_by_key(|f| f.start);
+        findings
+    }
+
+    /// Return the text with every finding rewritten according to the mode
+    pub fn redact(&self, text: &str) -> String {
+        if self.mode == RedactMode::Off {
+            return text.to_string();
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for finding in self.find(text) {
+            out.push_str(&text[last..finding.start]);
+            out.push_str(&self.replacement(&finding, &text[finding.start..finding.end]));
+            last = finding.end;
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+
+    /// Redact every string of a JSON document, object keys included, so
+    /// the result is still valid JSON
+    pub fn redact_json(&self, value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::String(text) => Value::String(self.redact(&text)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.redact_json(v)).collect()),
+            Value::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, v)| (self.redact(&key), self.redact_json(v)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Summarize what would be redacted: (rule, masked sample, occurrences)
+    pub fn preview(&self, text: &str) -> Vec<(String, String, usize)> {
+        let mut summary: Vec<(String, String, usize)> = Vec::new();
+        for finding in self.find(text) {
+            let secret = &text[finding.start..finding.end];
+            match summary.iter_mut().find(|(rule, _, _)| *rule == finding.rule) {
+                Some(entry) => entry.2 += 1,
+                None => summary.push((finding.rule.clone(), mask(secret), 1)),
+            }
+        }
+        summary
+    }
+
+    fn replacement(&self, finding: &Finding, secret: &str) -> String {
+        match self.mode {
+            RedactMode::Off => secret.to_string(),
+            RedactMode::Placeholder => format!("[REDACTED:{}]", finding.rule),
+            RedactMode::Mask => mask(secret),
+        }
+    }
+}
+
+/// Characters kept at the start of a masked secret
+const MASK_PREFIX: usize = 4;
+/// Shorter secrets are masked entirely: the prefix would give most of them away
+const MASK_MIN_REVEAL_LEN: usize = 9;
+
+/// Keep the first characters of a long secret and replace the rest with `*`
+fn mask(secret: &str) -> String {
+    let reveal = if secret.chars().count() >= MASK_MIN_REVEAL_LEN {
+        MASK_PREFIX
+    } else {
+        0
+    };
+    secret
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if i < reveal { c } else { '*' })
+        .collect()
+}
+
+/// Load user rules from `redact.rules` in the base dir (`~/.ctf-brain` by default).
+/// One rule per line, `NAME = regex`; blank lines and `#` comments are ignored.
+/// Invalid regexes are left out and described in the returned problems.
+pub fn load_user_rules(config: &Config) -> (Vec<Rule>, Vec<String>) {
+    let content = match std::fs::read_to_string(config.base_dir().join("redact.rules")) {
+        Ok(c) => c,
+        Err(_) => return (Vec::new(), Vec::new()),
+    };
+    parse_rules(&content)
+}
+
+fn parse_rules(content: &str) -> (Vec<Rule>, Vec<String>) {
+    let mut rules = Vec::new();
+    let mut problems = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, pattern) = match line.split_once('=') {
+            Some((name, pattern)) => (name.trim().to_string(), pattern.trim()),
+            None => ("CUSTOM".to_string(), line),
+        };
+        match Regex::new(pattern) {
+            Ok(regex) => rules.push(Rule { name, regex }),
+            // The last line of regex's message says what is wrong
+            Err(e) => {
+                let message = e.to_string();
+                let reason = message.lines().last().unwrap_or_default().trim();
+                problems.push(format!(
+                    "redact.rules line {}: invalid regex for {}: {}",
+                    number + 1,
+                    name,
+                    reason.trim_start_matches("error: ")
+                ));
+            }
+        }
+    }
+    (rules, problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_hides_short_secrets_entirely() {
+        assert_eq!(mask("abc"), "***");
+        assert_eq!(mask("pass"), "****");
+        assert_eq!(mask("12345678"), "********");
+    }
+
+    #[test]
+    fn invalid_user_rules_are_reported() {
+        let (rules, problems) = parse_rules("# comment\nTOKEN = tok_[a-z]+\n\nBROKEN = (unclosed\n[a-\n");
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name, "TOKEN");
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("redact.rules line 4: invalid regex for BROKEN"), "{}", problems[0]);
+        assert!(problems[1].starts_with("redact.rules line 5: invalid regex for CUSTOM"), "{}", problems[1]);
+    }
+
+    #[test]
+    fn json_strings_and_keys_are_redacted() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.env_vars.insert("TOKEN".to_string(), "s3cr3t-value".to_string());
+        let base = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = base.path().display().to_string();
+        let redactor = Redactor::for_boxes(&[ctf_box], RedactMode::Placeholder, &config);
+        let value = serde_json::json!({
+            "tools": [{"name": "curl -H s3cr3t-value", "uses": 2}],
+            "s3cr3t-value": 1,
+            "flag": "HTB{abc}",
+        });
+        let redacted = redactor.redact_json(value).to_string();
+        assert!(!redacted.contains("s3cr3t-value"), "{}", redacted);
+        assert!(!redacted.contains("HTB{abc}"), "{}", redacted);
+        assert!(redacted.contains("[REDACTED:TOKEN]"));
+        assert!(redacted.contains("\"uses\":2"));
+        serde_json::from_str::<serde_json::Value>(&redacted).unwrap();
+    }
+
+    #[test]
+    fn mask_keeps_the_prefix_of_long_secrets() {
+        assert_eq!(mask("supersecret"), "supe*******");
+        assert_eq!(mask("123456789"), "1234*****");
+    }
+}
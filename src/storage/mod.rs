@@ -1,5 +1,10 @@
+#[allow(clippy::module_inception)]
 mod storage;
 mod environment;
+mod loot;
+mod shells;
 
 pub use storage::*;
 pub use environment::*;
+pub use loot::*;
+pub use shells::*;
@@ -0,0 +1,498 @@
+use crate::config::Config;
+use crate::models::CtfBox;
+use crate::quote;
+use std::net::Ipv4Addr;
+use std::path::Path;
+
+/// Shell families a box environment is generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+    Nu,
+}
+
+impl ShellKind {
+    /// Family of a shell program (`/usr/bin/fish`, `nu`...), bash when unknown
+    pub fn of(program: &str) -> Self {
+        let name = Path::new(program)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match name.as_str() {
+            n if n.contains("zsh") => ShellKind::Zsh,
+            n if n.contains("fish") => ShellKind::Fish,
+            "nu" | "nushell" => ShellKind::Nu,
+            _ => ShellKind::Bash,
+        }
+    }
+
+    /// Extension of its environment file, bash and zsh share the `.env`
+    pub fn extension(self) -> &'static str {
+        match self {
+            ShellKind::Bash | ShellKind::Zsh => "env",
+            ShellKind::Fish => "fish",
+            ShellKind::Nu => "nu",
+        }
+    }
+}
+
+/// Commands logged (without output) when typed without `ctf`
+const AUTO_LOGGED: &str = "^(nmap|gobuster|ffuf|nikto|sqlmap|hydra|john|hashcat|msfconsole|searchsploit)";
+
+/// Variables exported by the environment, the box's own last
+fn variables(
+    ctf_box: &CtfBox,
+    config: &Config,
+    brain_bin: &str,
+    lhost: Option<Ipv4Addr>,
+) -> Vec<(String, String)> {
+    let mut vars: Vec<(String, String)> = vec![
+        ("CTF_BOX".to_string(), ctf_box.title.clone()),
+        ("CTF_IP".to_string(), ctf_box.ip_address.to_string()),
+        ("CTF_ID".to_string(), ctf_box.id.to_string()),
+        ("CTF_PLATFORM".to_string(), ctf_box.platform.clone()),
+        ("CTF_LOG_DIR".to_string(), config.log_dir().display().to_string()),
+        ("CTF_WORDLIST".to_string(), config.wordlists.directories.clone()),
+        ("CTF_LPORT".to_string(), config.network.listener_port.to_string()),
+        ("LPORT".to_string(), config.network.listener_port.to_string()),
+        ("CTF_BRAIN_BIN".to_string(), brain_bin.to_string()),
+    ];
+    if let Some(lhost) = lhost {
+        vars.push(("LHOST".to_string(), lhost.to_string()));
+    }
+    vars.extend(
+        ctf_box
+            .env_vars
+            .iter()
+            .filter(|(k, _)| quote::is_name(k))
+            .map(|(k, v)| (k.clone(), v.clone())),
+    );
+    vars
+}
+
+/// Welcome banner of the fish and nushell environments
+fn welcome_lines(ctf_box: &CtfBox) -> Vec<String> {
+    let mut lines = vec![
+        String::new(),
+        format!("🎯 CTF Box: {}", ctf_box.title),
+        format!("📡 IP: {}", ctf_box.ip_address),
+        format!("🏷️  Platform: {}", ctf_box.platform),
+        format!("🔖 Tags: {}", ctf_box.tags.join(", ")),
+    ];
+    if !ctf_box.env_vars.is_empty() {
+        lines.push("🔧 Custom Variables:".to_string());
+        lines.extend(ctf_box.env_vars.iter().filter(|(k, _)| quote::is_name(k)).map(|(k, v)| {
+            let display_val: String = if v.chars().count() > 40 {
+                format!("{}...", v.chars().take(40).collect::<String>())
+            } else {
+                v.clone()
+            };
+            format!("  {} = {}", k, display_val)
+        }));
+    }
+    lines.extend(
+        [
+            "",
+            "📝 ctf <commande> capture la commande avec son output (ctf nmap -sV $CTF_IP)",
+            "⚡ cn, cna, cg, cff → nmap, gobuster et ffuf via ctf",
+            "🔧 ip, n, na, g, nc-listen, listen, serve, loot <f>, snip [-x]",
+            "",
+            "💾 Les commandes 'ctf' sont loggées pour le write-up",
+            "⚠️  Tapez 'exit' pour revenir à CTF Brain",
+            "",
+        ]
+        .map(String::from),
+    );
+    lines
+}
+
+/// Environment file of a box for fish
+pub fn fish_environment(
+    ctf_box: &CtfBox,
+    config: &Config,
+    brain_bin: &str,
+    lhost: Option<Ipv4Addr>,
+) -> String {
+    let hook = config.base_dir().join("shell-hook.fish");
+    let mut content = format!(
+        "# Auto-generated by ctf-brain for: {}\n# Do not edit manually - changes will be overwritten\n\n",
+        quote::comment(&ctf_box.title)
+    );
+    for (name, value) in variables(ctf_box, config, brain_bin, lhost) {
+        content.push_str(&format!("set -gx {} {}\n", name, quote::fish(&value)));
+    }
+    let hook = quote::fish(&hook.display().to_string());
+    content.push_str(&format!("\n# ========== Logging Hook ==========\ntest -f {}; and source {}\n", hook, hook));
+    content.push_str(&format!(
+        r#"
+# ========== Custom Prompt ==========
+function fish_prompt
+    set_color green
+    echo -n {}
+    set_color normal
+    echo -n " $USER@"(prompt_hostname):(prompt_pwd)'> '
+end
+"#,
+        quote::fish(&format!("[🧠 {}]", ctf_box.title))
+    ));
+    content.push_str(FISH_FUNCTIONS);
+    content.push_str("\n# ========== Welcome Message ==========\nclear\n");
+    for line in welcome_lines(ctf_box) {
+        content.push_str(&format!("echo {}\n", quote::fish(&line)));
+    }
+    content
+}
+
+const FISH_FUNCTIONS: &str = r#"
+# ========== Quick Aliases ==========
+alias ip 'echo $CTF_IP'
+alias n 'nmap -sV $CTF_IP'
+alias na 'nmap -sC -sV -A $CTF_IP'
+alias g 'gobuster dir -u http://$CTF_IP -w $CTF_WORDLIST'
+alias nc-listen 'rlwrap nc -lvnp $CTF_LPORT'
+
+# ========== Loot ==========
+function loot --description 'Store a file in the loot of the box'
+    if test (count $argv) -eq 0
+        echo "Usage: loot <file> [--note <text>] [--source <origin>]"
+        return 1
+    end
+    $CTF_BRAIN_BIN loot add $CTF_ID $argv
+end
+
+# ========== Listener ==========
+function listen --description 'Wait for a reverse shell and log the session'
+    $CTF_BRAIN_BIN listen $argv
+end
+
+# ========== File server ==========
+function serve --description 'Serve files on LHOST and log the requests'
+    $CTF_BRAIN_BIN serve $argv
+end
+
+# ========== Snippets ==========
+# snip [query] prints the filled command, snip -x [query] runs it with ctf
+function snip
+    set -l run
+    if test "$argv[1]" = -x
+        set run 1
+        set -e argv[1]
+    end
+    set -l cmd ($CTF_BRAIN_BIN snip $argv); or return 1
+    if test -n "$run"
+        _ctf_eval "$cmd"
+    else
+        echo $cmd
+    end
+end
+"#;
+
+/// Logging hook for fish: the `ctf` wrapper and the log lines come from
+/// the bash hook, so both shells write the same jsonl
+pub fn fish_hook(bash_hook: &Path, config: &Config) -> String {
+    let bash_hook = quote::fish(&bash_hook.display().to_string());
+    format!(
+        r#"# CTF Brain - Shell Hook for Command Logging (fish)
+# The ctf wrapper runs in bash, with shell-hook.sh
+
+set -q CTF_LOG_DIR; or set -gx CTF_LOG_DIR {}
+set -q CTF_WORDLIST; or set -gx CTF_WORDLIST {}
+
+# ========== CTF Command Wrapper ==========
+# Usage: ctf <command>
+function ctf --description 'Run a command and log it with its output'
+    bash -c 'source "$0"; ctf "$@"' {} $argv
+end
+
+# Run a command line (bash syntax) through ctf
+function _ctf_eval
+    bash -c 'source "$0"; eval "ctf $1"' {} $argv[1]
+end
+
+# ========== Quick CTF aliases ==========
+alias cn 'ctf nmap -sV $CTF_IP'
+alias cna 'ctf nmap -sC -sV -A $CTF_IP'
+alias cg 'ctf gobuster dir -u http://$CTF_IP -w $CTF_WORDLIST'
+alias cff 'ctf ffuf -u http://$CTF_IP/FUZZ -w $CTF_WORDLIST'
+
+# ========== Auto-log important commands ==========
+function _ctf_preexec --on-event fish_preexec
+    string match -qr {} -- $argv[1]
+    and bash -c 'source "$0"; _ctf_log_command "$1"' {} $argv[1]
+end
+"#,
+        quote::fish(&config.log_dir().display().to_string()),
+        quote::fish(&config.wordlists.directories),
+        bash_hook,
+        bash_hook,
+        quote::fish(AUTO_LOGGED),
+        bash_hook,
+    )
+}
+
+/// Environment file of a box for nushell
+pub fn nu_environment(
+    ctf_box: &CtfBox,
+    config: &Config,
+    brain_bin: &str,
+    lhost: Option<Ipv4Addr>,
+) -> String {
+    let hook = config.base_dir().join("shell-hook.nu");
+    let mut content = format!(
+        "# Auto-generated by ctf-brain for: {}\n# Do not edit manually - changes will be overwritten\n\n",
+        quote::comment(&ctf_box.title)
+    );
+    for (name, value) in variables(ctf_box, config, brain_bin, lhost) {
+        content.push_str(&format!("$env.{} = {}\n", name, quote::nu(&value)));
+    }
+    content.push_str(&format!(
+        "\n# ========== Logging Hook ==========\nsource {}\n",
+        quote::nu(&hook.display().to_string())
+    ));
+    content.push_str(&format!(
+        r#"
+# ========== Custom Prompt ==========
+$env.PROMPT_COMMAND = {{|| [(ansi green) {} (ansi reset) " " ($env.PWD | str replace $nu.home-path "~")] | str join }}
+$env.PROMPT_COMMAND_RIGHT = {{|| "" }}
+$env.PROMPT_INDICATOR = {{|| "> " }}
+"#,
+        quote::nu(&format!("[🧠 {}]", ctf_box.title))
+    ));
+    content.push_str(NU_COMMANDS);
+    content.push_str("\n# ========== Welcome Message ==========\nclear\n");
+    for line in welcome_lines(ctf_box) {
+        content.push_str(&format!("print {}\n", quote::nu(&line)));
+    }
+    content
+}
+
+const NU_COMMANDS: &str = r#"
+# ========== Quick Aliases ==========
+def ip [] { $env.CTF_IP }
+def --wrapped n [...rest] { ^nmap -sV $env.CTF_IP ...$rest }
+def --wrapped na [...rest] { ^nmap -sC -sV -A $env.CTF_IP ...$rest }
+def --wrapped g [...rest] { ^gobuster dir -u $"http://($env.CTF_IP)" -w $env.CTF_WORDLIST ...$rest }
+def --wrapped nc-listen [...rest] { ^rlwrap nc -lvnp $env.CTF_LPORT ...$rest }
+
+# ========== Loot ==========
+def --wrapped loot [...rest] {
+    if ($rest | is-empty) {
+        print "Usage: loot <file> [--note <text>] [--source <origin>]"
+        return
+    }
+    ^$env.CTF_BRAIN_BIN loot add $env.CTF_ID ...$rest
+}
+
+# ========== Listener ==========
+def --wrapped listen [...rest] { ^$env.CTF_BRAIN_BIN listen ...$rest }
+
+# ========== File server ==========
+def --wrapped serve [...rest] { ^$env.CTF_BRAIN_BIN serve ...$rest }
+
+# ========== Snippets ==========
+# snip [query] prints the filled command, snip -x [query] runs it with ctf
+def --wrapped snip [...rest] {
+    let run = ($rest | get 0? | default "") == "-x"
+    let query = if $run { $rest | skip 1 } else { $rest }
+    let cmd = (^$env.CTF_BRAIN_BIN snip ...$query | str trim)
+    if $run { _ctf_eval $cmd } else { $cmd }
+}
+"#;
+
+/// Logging hook for nushell, running the bash `ctf` wrapper like fish
+pub fn nu_hook(bash_hook: &Path, config: &Config) -> String {
+    let bash_hook = quote::nu(&bash_hook.display().to_string());
+    format!(
+        r#"# CTF Brain - Shell Hook for Command Logging (nushell)
+# The ctf wrapper runs in bash, with shell-hook.sh
+
+$env.CTF_LOG_DIR = ($env.CTF_LOG_DIR? | default {})
+$env.CTF_WORDLIST = ($env.CTF_WORDLIST? | default {})
+
+# ========== CTF Command Wrapper ==========
+# Usage: ctf <command>
+def --wrapped ctf [...rest] {{
+    ^bash -c 'source "$0"; ctf "$@"' {} ...$rest
+}}
+
+# Run a command line (bash syntax) through ctf
+def _ctf_eval [command: string] {{
+    ^bash -c 'source "$0"; eval "ctf $1"' {} $command
+}}
+
+# ========== Quick CTF aliases ==========
+def --wrapped cn [...rest] {{ ctf nmap -sV $env.CTF_IP ...$rest }}
+def --wrapped cna [...rest] {{ ctf nmap -sC -sV -A $env.CTF_IP ...$rest }}
+def --wrapped cg [...rest] {{ ctf gobuster dir -u $"http://($env.CTF_IP)" -w $env.CTF_WORDLIST ...$rest }}
+def --wrapped cff [...rest] {{ ctf ffuf -u $"http://($env.CTF_IP)/FUZZ" -w $env.CTF_WORDLIST ...$rest }}
+
+# ========== Auto-log important commands ==========
+$env.config = ($env.config | upsert hooks.pre_execution (
+    ($env.config.hooks.pre_execution? | default []) | append {{||
+        let line = (commandline)
+        if ($line =~ {}) {{
+            ^bash -c 'source "$0"; _ctf_log_command "$1"' {} $line
+        }}
+    }}
+))
+"#,
+        quote::nu(&config.log_dir().display().to_string()),
+        quote::nu(&config.wordlists.directories),
+        bash_hook,
+        bash_hook,
+        quote::nu(AUTO_LOGGED),
+        bash_hook,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn config(base: &Path) -> Config {
+        let mut config = Config::default();
+        config.paths.base_dir = base.display().to_string();
+        config
+    }
+
+    fn sample() -> CtfBox {
+        let mut ctf_box = CtfBox::sample(3, "Lame");
+        ctf_box.env_vars.insert("DOMAIN".to_string(), "lame.htb".to_string());
+        ctf_box.env_vars.insert("not a name".to_string(), "skipped".to_string());
+        ctf_box
+    }
+
+    const LHOST: Option<Ipv4Addr> = Some(Ipv4Addr::new(10, 10, 14, 2));
+
+    /// Files of the box environment and its hook, written in `base` as
+    /// `create_box_environment` does, for the syntax checks
+    fn write_files(base: &Path, kind: ShellKind) -> (std::path::PathBuf, std::path::PathBuf) {
+        let config = config(base);
+        let bash_hook = base.join("shell-hook.sh");
+        let (environment, hook) = match kind {
+            ShellKind::Fish => (
+                fish_environment(&sample(), &config, "/usr/bin/ctf-brain", LHOST),
+                fish_hook(&bash_hook, &config),
+            ),
+            ShellKind::Nu => (
+                nu_environment(&sample(), &config, "/usr/bin/ctf-brain", LHOST),
+                nu_hook(&bash_hook, &config),
+            ),
+            _ => unreachable!(),
+        };
+        let hook_path = base.join(format!("shell-hook.{}", kind.extension()));
+        let env_path = base.join(format!("box-3.{}", kind.extension()));
+        std::fs::write(&hook_path, hook).unwrap();
+        std::fs::write(&env_path, environment).unwrap();
+        (env_path, hook_path)
+    }
+
+    #[test]
+    fn shell_kind_from_program() {
+        assert_eq!(ShellKind::of("/usr/bin/fish"), ShellKind::Fish);
+        assert_eq!(ShellKind::of("nu"), ShellKind::Nu);
+        assert_eq!(ShellKind::of("/bin/zsh"), ShellKind::Zsh);
+        assert_eq!(ShellKind::of("/bin/bash"), ShellKind::Bash);
+        assert_eq!(ShellKind::of("dash"), ShellKind::Bash);
+        assert_eq!(ShellKind::Zsh.extension(), "env");
+    }
+
+    #[test]
+    fn variables_include_box_values_and_valid_custom_names() {
+        let base = tempfile::tempdir().unwrap();
+        let vars = variables(&sample(), &config(base.path()), "/usr/bin/ctf-brain", LHOST);
+        let get = |name: &str| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str());
+        assert_eq!(get("CTF_BOX"), Some("Lame"));
+        assert_eq!(get("CTF_IP"), Some("10.10.10.5"));
+        assert_eq!(get("CTF_ID"), Some("3"));
+        assert_eq!(get("CTF_PLATFORM"), Some("HTB"));
+        assert_eq!(get("LHOST"), Some("10.10.14.2"));
+        assert_eq!(get("LPORT"), get("CTF_LPORT"));
+        assert_eq!(get("CTF_BRAIN_BIN"), Some("/usr/bin/ctf-brain"));
+        assert_eq!(get("DOMAIN"), Some("lame.htb"));
+        assert_eq!(get("not a name"), None);
+        // The box's own variables come last, so they win
+        assert_eq!(vars.last().map(|(k, _)| k.as_str()), Some("DOMAIN"));
+
+        let vars = variables(&sample(), &config(base.path()), "ctf-brain", None);
+        assert!(vars.iter().all(|(k, _)| k != "LHOST"));
+    }
+
+    #[test]
+    fn fish_environment_exports_variables_and_aliases() {
+        let base = tempfile::tempdir().unwrap();
+        let content = fish_environment(&sample(), &config(base.path()), "/usr/bin/ctf-brain", LHOST);
+        assert!(content.contains("set -gx CTF_IP '10.10.10.5'\n"));
+        assert!(content.contains("set -gx LHOST '10.10.14.2'\n"));
+        assert!(content.contains("set -gx DOMAIN 'lame.htb'\n"));
+        assert!(!content.contains("skipped"));
+        for alias in ["ip", "n", "na", "g", "nc-listen"] {
+            assert!(content.contains(&format!("alias {} '", alias)), "{}", alias);
+        }
+        for function in ["loot", "listen", "serve", "snip"] {
+            assert!(content.contains(&format!("function {}", function)), "{}", function);
+        }
+        let hook = fish_hook(&base.path().join("shell-hook.sh"), &config(base.path()));
+        for alias in ["cn", "cna", "cg", "cff"] {
+            assert!(hook.contains(&format!("alias {} 'ctf ", alias)), "{}", alias);
+        }
+    }
+
+    #[test]
+    fn nu_environment_exports_variables_and_commands() {
+        let base = tempfile::tempdir().unwrap();
+        let content = nu_environment(&sample(), &config(base.path()), "/usr/bin/ctf-brain", LHOST);
+        assert!(content.contains("$env.CTF_IP = r#'10.10.10.5'#\n"));
+        assert!(content.contains("$env.LHOST = r#'10.10.14.2'#\n"));
+        assert!(content.contains("$env.DOMAIN = r#'lame.htb'#\n"));
+        assert!(!content.contains("skipped"));
+        for command in ["ip", "n", "na", "g", "nc-listen", "loot", "listen", "serve", "snip"] {
+            assert!(
+                content.contains(&format!("def {} [", command))
+                    || content.contains(&format!("def --wrapped {} [", command)),
+                "{}",
+                command
+            );
+        }
+        let hook = nu_hook(&base.path().join("shell-hook.sh"), &config(base.path()));
+        for command in ["ctf", "cn", "cna", "cg", "cff"] {
+            assert!(hook.contains(&format!("def --wrapped {} [", command)), "{}", command);
+        }
+    }
+
+    #[test]
+    fn fish_files_parse() {
+        if !crate::config::program_exists("fish") {
+            eprintln!("fish not installed, skipped");
+            return;
+        }
+        let base = tempfile::tempdir().unwrap();
+        for path in <[_; 2]>::from(write_files(base.path(), ShellKind::Fish)) {
+            let output = Command::new("fish").arg("-n").arg(&path).output().unwrap();
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        }
+    }
+
+    #[test]
+    fn nu_files_parse() {
+        if !crate::config::program_exists("nu") {
+            eprintln!("nu not installed, skipped");
+            return;
+        }
+        let base = tempfile::tempdir().unwrap();
+        for path in <[_; 2]>::from(write_files(base.path(), ShellKind::Nu)) {
+            let output = Command::new("nu")
+                .args(["--no-config-file", "--ide-check", "10"])
+                .arg(&path)
+                .current_dir(base.path())
+                .output()
+                .unwrap();
+            // --ide-check reports problems as JSON lines on stdout
+            let report = String::from_utf8_lossy(&output.stdout);
+            assert!(!report.contains(r#""severity":"Error""#), "{}", report);
+        }
+    }
+}
@@ -1,11 +1,13 @@
+use crate::config::Config;
 use crate::models::CtfBox;
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-/// Get the path to the data file where boxes are stored
-fn get_data_path() -> Result<PathBuf> {
+/// Get the data directory, where boxes and input history are stored
+fn get_data_dir() -> Result<PathBuf> {
     let proj_dirs = ProjectDirs::from("", "", "ctf-brain")
         .context("Unable to determine data directory")?;
     
@@ -15,7 +17,27 @@ fn get_data_path() -> Result<PathBuf> {
     fs::create_dir_all(data_dir)
         .context("Failed to create data directory")?;
     
-    Ok(data_dir.join("boxes.json"))
+    Ok(data_dir.to_path_buf())
+}
+
+/// Get the path to the data file where boxes are stored
+fn get_data_path() -> Result<PathBuf> {
+    Ok(get_data_dir()?.join("boxes.json"))
+}
+
+/// Values submitted in each input field of the TUI, empty when the file
+/// is missing or unreadable
+pub fn load_input_history() -> HashMap<String, Vec<String>> {
+    get_data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("history.json")).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_input_history(history: &HashMap<String, Vec<String>>) -> Result<()> {
+    let json = serde_json::to_string_pretty(history).context("Failed to serialize history")?;
+    fs::write(get_data_dir()?.join("history.json"), json).context("Failed to write history.json")
 }
 
 /// Load boxes from the JSON file
@@ -40,12 +62,12 @@ pub fn load_boxes() -> Result<Vec<CtfBox>> {
 }
 
 /// Save boxes to the JSON file (with automatic backup)
-pub fn save_boxes(boxes: &[CtfBox]) -> Result<()> {
+pub fn save_boxes(boxes: &[CtfBox], config: &Config) -> Result<()> {
     let path = get_data_path()?;
     
     // Create a backup before overwriting
     if path.exists() {
-        backup_data(&path)?;
+        backup_data(&path, config.storage.backup_count)?;
     }
     
     // Serialize to pretty JSON for readability
@@ -60,11 +82,14 @@ pub fn save_boxes(boxes: &[CtfBox]) -> Result<()> {
 }
 
 /// Create a rotating backup of the data file.
-/// Keeps up to 5 backups: boxes.json.bak.1 (newest) to boxes.json.bak.5 (oldest)
-fn backup_data(path: &PathBuf) -> Result<()> {
-    let max_backups = 5;
+/// Keeps up to `storage.backup_count` backups (5 by default):
+/// boxes.json.bak.1 (newest) to boxes.json.bak.N (oldest)
+fn backup_data(path: &PathBuf, max_backups: usize) -> Result<()> {
+    if max_backups == 0 {
+        return Ok(());
+    }
     
-    // Rotate existing backups: .bak.5 is deleted, .bak.4 → .bak.5, etc.
+    // Rotate existing backups: .bak.N is overwritten, .bak.N-1 → .bak.N, etc.
     for i in (1..max_backups).rev() {
         let older = path.with_extension(format!("json.bak.{}", i + 1));
         let newer = path.with_extension(format!("json.bak.{}", i));
@@ -82,12 +107,8 @@ fn backup_data(path: &PathBuf) -> Result<()> {
 }
 
 /// Import actions from shell logs for a specific box
-pub fn import_shell_logs(box_id: i32) -> Result<Vec<crate::models::Action>> {
-    let base_dir = dirs::home_dir()
-        .context("Unable to determine home directory")?
-        .join(".ctf-brain/logs");
-    
-    let log_file = base_dir.join(format!("box-{}.jsonl", box_id));
+pub fn import_shell_logs(box_id: i32, config: &Config) -> Result<Vec<crate::models::Action>> {
+    let log_file = shell_log_path(box_id, config)?;
     
     if !log_file.exists() {
         return Ok(Vec::new());
@@ -96,6 +117,11 @@ pub fn import_shell_logs(box_id: i32) -> Result<Vec<crate::models::Action>> {
     let content = fs::read_to_string(&log_file)
         .context("Failed to read log file")?;
     
+    Ok(parse_shell_log(&content))
+}
+
+/// Actions of a shell log, one JSON entry per line
+fn parse_shell_log(content: &str) -> Vec<crate::models::Action> {
     let mut actions = Vec::new();
     
     for line in content.lines() {
@@ -123,31 +149,168 @@ pub fn import_shell_logs(box_id: i32) -> Result<Vec<crate::models::Action>> {
                 timestamp,
                 command: entry.cmd,
                 result,
-                note: None,
+                note: entry.note,
                 output: entry.output,
             });
         }
     }
     
-    Ok(actions)
+    actions
 }
 
-/// Clear shell logs for a specific box (after import)
-pub fn clear_shell_logs(box_id: i32) -> Result<()> {
-    let base_dir = dirs::home_dir()
-        .context("Unable to determine home directory")?
-        .join(".ctf-brain/logs");
-    
-    let log_file = base_dir.join(format!("box-{}.jsonl", box_id));
-    
-    if log_file.exists() {
-        fs::remove_file(&log_file).context("Failed to remove log file")?;
-    }
-    
+/// Log an action recorded outside the box shell (e.g. `ctf-brain listen`),
+/// imported by the TUI like the `ctf` wrapper's commands
+pub fn log_action(box_id: i32, action: &crate::models::Action, config: &Config) -> Result<()> {
+    use std::io::Write;
+
+    let result = match action.result {
+        crate::models::ActionResult::Success => "success",
+        crate::models::ActionResult::Fail => "fail",
+        crate::models::ActionResult::Unknown => "unknown",
+    };
+    let entry = LogEntry {
+        time: action.timestamp.to_rfc3339(),
+        box_id,
+        cmd: action.command.clone(),
+        result: Some(result.to_string()),
+        output: action.output.clone(),
+        auto: None,
+        note: action.note.clone(),
+    };
+
+    let log_file = shell_log_path(box_id, config)?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_file)
+        .context("Failed to open log file")?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)
+        .context("Failed to write log file")?;
+
     Ok(())
 }
 
-#[derive(serde::Deserialize)]
+/// Queue a note for a box from outside the TUI (e.g. `ctf-brain screenshot`).
+/// The TUI picks pending notes up on start and after each shell session.
+pub fn queue_note(box_id: i32, note: &crate::models::Note, config: &Config) -> Result<()> {
+    use std::io::Write;
+
+    let path = pending_notes_path(box_id, config)?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context("Failed to open pending notes")?;
+    writeln!(file, "{}", serde_json::to_string(note)?)
+        .context("Failed to write pending note")?;
+
+    Ok(())
+}
+
+/// Read and remove the notes queued for a box
+pub fn take_pending_notes(box_id: i32, config: &Config) -> Result<Vec<crate::models::Note>> {
+    let path = pending_notes_path(box_id, config)?;
+    let content = take_file(&path).context("Failed to read pending notes")?;
+
+    Ok(parse_notes(&content.unwrap_or_default()))
+}
+
+fn parse_notes(content: &str) -> Vec<crate::models::Note> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Read and remove a file that other processes append to. The file is
+/// first moved to a unique name, so lines appended meanwhile go to a new
+/// file instead of being removed unread. A file that cannot be read is
+/// kept under that name.
+fn take_file(path: &Path) -> Result<Option<String>> {
+    let dir = path.parent().context("File without directory")?;
+    let taken = tempfile::Builder::new()
+        .prefix(".taking-")
+        .tempfile_in(dir)
+        .context("Failed to create temporary file")?
+        .into_temp_path();
+
+    match fs::rename(path, &taken) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).context(format!("Failed to move {}", path.display())),
+    }
+
+    // The temporary file is removed when `taken` is dropped
+    match fs::read(&taken) {
+        Ok(content) => Ok(Some(String::from_utf8_lossy(&content).into_owned())),
+        Err(e) => {
+            let kept = taken.keep().unwrap_or_default();
+            Err(e).context(format!("Failed to read {}, kept as {}", path.display(), kept.display()))
+        }
+    }
+}
+
+/// Append back the content of a taken file
+fn requeue(path: &Path, content: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    if content.is_empty() {
+        return Ok(());
+    }
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content))
+        .context(format!("Failed to restore {}", path.display()))
+}
+
+/// What was recorded for a box outside the TUI, to merge into it
+#[derive(Debug, Clone, Default)]
+pub struct BoxActivity {
+    pub loot: Vec<crate::models::Attachment>,
+    pub notes: Vec<crate::models::Note>,
+    pub actions: Vec<crate::models::Action>,
+}
+
+/// Read the loot index, the queued notes and the shell logs of a box.
+/// The queued notes and the logs are removed once read.
+pub fn take_box_activity(box_id: i32, config: &Config) -> Result<BoxActivity> {
+    // Reads that fail leave the logs and the notes queued
+    let loot = super::load_loot_index(box_id, config)?;
+    let log_file = shell_log_path(box_id, config)?;
+    let log = take_file(&log_file).context("Failed to read log file")?.unwrap_or_default();
+    let notes = match take_file(&pending_notes_path(box_id, config)?) {
+        Ok(notes) => notes.unwrap_or_default(),
+        Err(e) => {
+            let _ = requeue(&log_file, log.as_bytes());
+            return Err(e.context("Failed to read pending notes"));
+        }
+    };
+
+    Ok(BoxActivity {
+        loot,
+        notes: parse_notes(&notes),
+        actions: parse_shell_log(&log),
+    })
+}
+
+fn shell_log_path(box_id: i32, config: &Config) -> Result<PathBuf> {
+    Ok(super::environment::get_log_dir(config)?.join(format!("box-{}.jsonl", box_id)))
+}
+
+fn pending_notes_path(box_id: i32, config: &Config) -> Result<PathBuf> {
+    let dir = super::environment::get_base_dir(config)?
+        .join("boxes")
+        .join(box_id.to_string());
+
+    fs::create_dir_all(&dir).context("Failed to create box directory")?;
+
+    Ok(dir.join("pending-notes.jsonl"))
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
 struct LogEntry {
     time: String,
     #[allow(dead_code)]
@@ -155,7 +318,10 @@ struct LogEntry {
     cmd: String,
     result: Option<String>,
     output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     auto: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
 }
 
 /// Generate a write-up markdown for a box, skipping empty sections
@@ -244,7 +410,9 @@ pub fn generate_writeup(ctf_box: &CtfBox) -> String {
     if has_enum { toc.push(("Enumeration", "enumeration")); }
     if has_exploit { toc.push(("Exploitation", "exploitation")); }
     if has_privesc { toc.push(("Privilege Escalation", "privilege-escalation")); }
+    if !ctf_box.checklists.is_empty() { toc.push(("Methodology", "methodology")); }
     toc.push(("Flags", "flags"));
+    toc.push(("Timeline", "timeline"));
     if has_misc { toc.push(("Additional Notes", "additional-notes")); }
     if has_other { toc.push(("Command Log", "command-log")); }
 
@@ -258,9 +426,10 @@ pub fn generate_writeup(ctf_box: &CtfBox) -> String {
     if has_recon {
         md.push_str("## Reconnaissance\n\n");
         for note in &recon_notes {
-            md.push_str(&format!("- {}\n", note.content));
+            render_note_content(&mut md, "- ", &note.content);
+            render_note_images(&mut md, note, ctf_box);
         }
-        if !recon_notes.is_empty() { md.push_str("\n"); }
+        if !recon_notes.is_empty() { md.push('\n'); }
         for action in &recon_commands {
             render_action(&mut md, action, 3000);
         }
@@ -270,9 +439,10 @@ pub fn generate_writeup(ctf_box: &CtfBox) -> String {
     if has_enum {
         md.push_str("## Enumeration\n\n");
         for note in &web_notes {
-            md.push_str(&format!("- {}\n", note.content));
+            render_note_content(&mut md, "- ", &note.content);
+            render_note_images(&mut md, note, ctf_box);
         }
-        if !web_notes.is_empty() { md.push_str("\n"); }
+        if !web_notes.is_empty() { md.push('\n'); }
         for action in &enum_commands {
             render_action(&mut md, action, 3000);
         }
@@ -282,9 +452,10 @@ pub fn generate_writeup(ctf_box: &CtfBox) -> String {
     if has_exploit {
         md.push_str("## Exploitation\n\n");
         for note in &foothold_notes {
-            md.push_str(&format!("- {}\n", note.content));
+            render_note_content(&mut md, "- ", &note.content);
+            render_note_images(&mut md, note, ctf_box);
         }
-        if !foothold_notes.is_empty() { md.push_str("\n"); }
+        if !foothold_notes.is_empty() { md.push('\n'); }
         for action in &exploit_commands {
             render_action(&mut md, action, 2000);
         }
@@ -294,28 +465,41 @@ pub fn generate_writeup(ctf_box: &CtfBox) -> String {
     if has_privesc {
         md.push_str("## Privilege Escalation\n\n");
         for note in &privesc_notes {
-            md.push_str(&format!("- {}\n", note.content));
+            render_note_content(&mut md, "- ", &note.content);
+            render_note_images(&mut md, note, ctf_box);
         }
-        if !privesc_notes.is_empty() { md.push_str("\n"); }
+        if !privesc_notes.is_empty() { md.push('\n'); }
         for action in &privesc_commands {
             render_action(&mut md, action, 2000);
         }
     }
     
+    // Methodology checklists
+    if !ctf_box.checklists.is_empty() {
+        md.push_str("## Methodology\n\n");
+        md.push_str(&crate::checklist::markdown(ctf_box));
+    }
+
     // Flags — always present
     md.push_str("## Flags\n\n");
     md.push_str("### User Flag\n\n");
     md.push_str("```\n[USER FLAG HERE]\n```\n\n");
     md.push_str("### Root Flag\n\n");
     md.push_str("```\n[ROOT FLAG HERE]\n```\n\n");
+
+    // Timeline — always present, it starts with the box creation
+    md.push_str("---\n\n## Timeline\n");
+    md.push_str(&crate::timeline::markdown(ctf_box));
+    md.push('\n');
     
     // Additional Notes
     if has_misc {
         md.push_str("---\n\n## Additional Notes\n\n");
         for note in &misc_notes {
-            md.push_str(&format!("- **{:?}:** {}\n", note.category, note.content));
+            render_note_content(&mut md, &format!("- **{:?}:** ", note.category), &note.content);
+            render_note_images(&mut md, note, ctf_box);
         }
-        md.push_str("\n");
+        md.push('\n');
     }
     
     // Command Log
@@ -332,7 +516,7 @@ pub fn generate_writeup(ctf_box: &CtfBox) -> String {
                 md.push_str("```\n");
                 let truncated = truncate_output(output, 1500);
                 md.push_str(&truncated);
-                if !truncated.ends_with('\n') { md.push_str("\n"); }
+                if !truncated.ends_with('\n') { md.push('\n'); }
                 md.push_str("```\n\n");
             }
         }
@@ -342,6 +526,50 @@ pub fn generate_writeup(ctf_box: &CtfBox) -> String {
     md
 }
 
+/// First 12 characters of a sha256, or all of it when a hand-edited
+/// `boxes.json` holds a shorter one
+pub fn short_hash(sha256: &str) -> &str {
+    sha256.get(..12).unwrap_or(sha256)
+}
+
+/// File name used for an image in the write-up's `images/` directory
+pub fn writeup_image_name(attachment: &crate::models::Attachment) -> String {
+    // The name comes from the user: keep it to characters safe in a path
+    // and a markdown link
+    let name: String = attachment
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    format!("{}-{}", short_hash(&attachment.sha256), name)
+}
+
+/// Render a (possibly multi-line) markdown note as a list item
+fn render_note_content(md: &mut String, prefix: &str, content: &str) {
+    for (i, line) in content.lines().enumerate() {
+        if i == 0 {
+            md.push_str(&format!("{}{}\n", prefix, line));
+        } else if line.trim().is_empty() {
+            md.push('\n');
+        } else {
+            md.push_str(&format!("  {}\n", line));
+        }
+    }
+}
+
+/// Render the images attached to a note as markdown image links
+fn render_note_images(md: &mut String, note: &crate::models::Note, ctf_box: &CtfBox) {
+    for sha in &note.images {
+        if let Some(attachment) = ctf_box.attachments.iter().find(|a| &a.sha256 == sha) {
+            md.push_str(&format!(
+                "\n  ![{}](images/{})\n",
+                attachment.note.as_deref().unwrap_or(&attachment.name),
+                writeup_image_name(attachment)
+            ));
+        }
+    }
+}
+
 /// Render a single action as a markdown code block
 fn render_action(md: &mut String, action: &crate::models::Action, max_output: usize) {
     let tool_name = action.command.split_whitespace().next().unwrap_or("Command");
@@ -350,7 +578,7 @@ fn render_action(md: &mut String, action: &crate::models::Action, max_output: us
     if let Some(output) = &action.output {
         let truncated = truncate_output(output, max_output);
         md.push_str(&truncated);
-        if !truncated.ends_with('\n') { md.push_str("\n"); }
+        if !truncated.ends_with('\n') { md.push('\n'); }
     }
     md.push_str("```\n\n");
     if let Some(note) = &action.note {
@@ -366,3 +594,109 @@ fn truncate_output(output: &str, max_len: usize) -> String {
         output.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Attachment;
+
+    fn attachment(name: &str, sha256: &str) -> Attachment {
+        Attachment {
+            name: name.to_string(),
+            sha256: sha256.to_string(),
+            size: 0,
+            mime: "image/png".to_string(),
+            source: "screenshot".to_string(),
+            note: None,
+            created_date: chrono::Utc::now(),
+        }
+    }
+
+    fn action(command: &str) -> crate::models::Action {
+        crate::models::Action {
+            timestamp: chrono::Utc::now(),
+            command: command.to_string(),
+            result: crate::models::ActionResult::Success,
+            note: None,
+            output: None,
+        }
+    }
+
+    fn note(content: &str) -> crate::models::Note {
+        crate::models::Note {
+            category: crate::models::NoteCategory::Misc,
+            content: content.to_string(),
+            created_date: chrono::Utc::now(),
+            images: Vec::new(),
+        }
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn box_activity_is_taken_once() {
+        let base = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = base.path().display().to_string();
+
+        log_action(3, &action("nmap 10.10.10.3"), &config).unwrap();
+        log_action(3, &action("gobuster dir"), &config).unwrap();
+        queue_note(3, &note("port 80"), &config).unwrap();
+        log_action(4, &action("whoami"), &config).unwrap();
+
+        let activity = take_box_activity(3, &config).unwrap();
+        let commands: Vec<&str> = activity.actions.iter().map(|a| a.command.as_str()).collect();
+        assert_eq!(commands, ["nmap 10.10.10.3", "gobuster dir"]);
+        assert_eq!(activity.notes.len(), 1);
+        assert_eq!(activity.notes[0].content, "port 80");
+        // Nothing is left behind, and the other boxes keep their logs
+        assert_eq!(file_names(&config.log_dir()), ["box-4.jsonl"]);
+        assert!(file_names(&base.path().join("boxes").join("3")).is_empty());
+
+        let again = take_box_activity(3, &config).unwrap();
+        assert!(again.actions.is_empty() && again.notes.is_empty());
+
+        // What is written afterwards goes to new files, taken next time
+        log_action(3, &action("id"), &config).unwrap();
+        queue_note(3, &note("www-data"), &config).unwrap();
+        let later = take_box_activity(3, &config).unwrap();
+        assert_eq!(later.actions.len(), 1);
+        assert_eq!(later.actions[0].command, "id");
+        assert_eq!(take_pending_notes(3, &config).unwrap().len(), 0);
+        assert_eq!(later.notes[0].content, "www-data");
+    }
+
+    #[test]
+    fn taking_a_file_moves_it_away_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue.jsonl");
+        assert_eq!(take_file(&path).unwrap(), None);
+
+        fs::write(&path, "one\n").unwrap();
+        assert_eq!(take_file(&path).unwrap().as_deref(), Some("one\n"));
+        assert!(file_names(dir.path()).is_empty());
+
+        requeue(&path, b"two\n").unwrap();
+        requeue(&path, b"").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two\n");
+    }
+
+    #[test]
+    fn short_hash_tolerates_short_hashes() {
+        assert_eq!(short_hash("0123456789abcdef"), "0123456789ab");
+        assert_eq!(short_hash("abc"), "abc");
+    }
+
+    #[test]
+    fn writeup_image_name_is_safe_in_paths_and_links() {
+        let image = attachment("../my shot (1);rm.png", "0123456789abcdef");
+        assert_eq!(writeup_image_name(&image), "0123456789ab-.._my_shot__1__rm.png");
+    }
+}
@@ -0,0 +1,284 @@
+use crate::config::Config;
+use crate::models::CtfBox;
+use crate::recon::{self, Service};
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::OnceLock;
+
+/// Knowledge base shipped with ctf-brain
+const BUILTIN: &str = include_str!("../data/suggestions.toml");
+
+/// Next enumeration step for a kind of service, as written in a data file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Rule {
+    /// Service names as nmap reports them, matched as a prefix
+    #[serde(default)]
+    pub services: Vec<String>,
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    pub title: String,
+    /// Command with `$CTF_*` placeholders
+    pub command: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KnowledgeBase {
+    #[serde(default)]
+    suggestion: Vec<Rule>,
+}
+
+/// A rule applied to one service of a box
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub service: Service,
+    pub title: String,
+    /// Command with the placeholders filled in
+    pub command: String,
+    /// The command was already logged on the box
+    pub done: bool,
+}
+
+impl Rule {
+    fn applies_to(&self, service: &Service) -> bool {
+        // "ssl/http" is http over TLS
+        let name = service.name.rsplit('/').next().unwrap_or_default();
+        self.ports.contains(&service.port)
+            || (!name.is_empty() && self.services.iter().any(|s| name.starts_with(s.as_str())))
+    }
+}
+
+/// Built-in rules, then the user's from `suggestions.toml` in the base dir
+/// (`~/.ctf-brain` by default). An invalid user file is skipped.
+pub fn rules(config: &Config) -> Vec<Rule> {
+    let mut rules = toml::from_str::<KnowledgeBase>(BUILTIN)
+        .map(|kb| kb.suggestion)
+        .unwrap_or_default();
+    let user = std::fs::read_to_string(config.base_dir().join("suggestions.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<KnowledgeBase>(&content).ok());
+    if let Some(user) = user {
+        rules.extend(user.suggestion);
+    }
+    rules
+}
+
+/// Suggestions for the services found on the box, by port
+pub fn for_box(ctf_box: &CtfBox, rules: &[Rule], config: &Config) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for service in recon::box_services(ctf_box) {
+        for rule in rules.iter().filter(|r| r.applies_to(&service)) {
+            let command = expand(&rule.command, ctf_box, &service, config);
+            // The same command from two rules is shown once
+            if suggestions
+                .iter()
+                .any(|s: &Suggestion| s.service == service && s.command == command)
+            {
+                continue;
+            }
+            let done = ctf_box.actions.iter().any(|a| same_command(&a.command, &command));
+            suggestions.push(Suggestion {
+                service: service.clone(),
+                title: rule.title.clone(),
+                command,
+                done,
+            });
+        }
+    }
+    suggestions
+}
+
+/// The `ctf` wrapper logs `$*`: the words without their quotes
+fn same_command(logged: &str, command: &str) -> bool {
+    let words = |s: &str| -> Vec<String> {
+        s.split_whitespace()
+            .map(|w| w.replace(['\'', '"'], ""))
+            .filter(|w| !w.is_empty())
+            .collect()
+    };
+    words(logged) == words(command)
+}
+
+/// `$NAME` or `${NAME}`
+fn placeholder() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\$(?:\{([A-Za-z_][A-Za-z0-9_]*)\}|([A-Za-z_][A-Za-z0-9_]*))")
+            .expect("valid regex")
+    })
+}
+
+/// Fill in the box's variables, quoted for bash which runs the command
+/// (see `App::run_in_box_shell`). Unknown ones are left for the shell.
+pub fn expand(command: &str, ctf_box: &CtfBox, service: &Service, config: &Config) -> String {
+    let scheme = if service.name.starts_with("ssl/")
+        || service.name.starts_with("https")
+        || [443, 8443].contains(&service.port)
+    {
+        "https"
+    } else {
+        "http"
+    };
+    let mut values: HashMap<&str, String> = ctf_box
+        .env_vars
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+    values.insert("CTF_IP", ctf_box.ip_address.to_string());
+    values.insert("CTF_PORT", service.port.to_string());
+    // IPv6 addresses are bracketed in URLs
+    let host = match ctf_box.ip_address {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => format!("[{}]", ip),
+    };
+    values.insert("CTF_URL", format!("{}://{}:{}", scheme, host, service.port));
+    values.insert("CTF_WORDLIST", config.wordlists.directories.clone());
+    values.insert("CTF_PASSWORDS", config.wordlists.passwords.clone());
+    values.insert("CTF_LPORT", config.network.listener_port.to_string());
+
+    crate::quote::sh_substitute(command, placeholder(), |caps: &Captures| {
+        let name = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+        values.get(name).cloned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Action;
+
+    fn service(port: u16, name: &str) -> Service {
+        Service {
+            port,
+            protocol: "tcp".to_string(),
+            name: name.to_string(),
+            version: String::new(),
+        }
+    }
+
+    fn rule(services: &[&str], ports: &[u16]) -> Rule {
+        Rule {
+            services: services.iter().map(|s| s.to_string()).collect(),
+            ports: ports.to_vec(),
+            title: "Rule".to_string(),
+            command: "true".to_string(),
+        }
+    }
+
+    #[test]
+    fn rules_apply_by_port_or_service_prefix() {
+        let http = rule(&["http"], &[]);
+        assert!(http.applies_to(&service(8080, "http-proxy")));
+        assert!(http.applies_to(&service(443, "ssl/http")));
+        assert!(!http.applies_to(&service(80, "ftp")));
+        assert!(!http.applies_to(&service(80, "")));
+        assert!(!rule(&[""], &[]).applies_to(&service(80, "")));
+
+        let smb = rule(&[], &[445]);
+        assert!(smb.applies_to(&service(445, "")));
+        assert!(!smb.applies_to(&service(139, "netbios-ssn")));
+    }
+
+    #[test]
+    fn commands_are_filled_with_the_box_values() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.env_vars.insert("DOMAIN".to_string(), "lame.htb".to_string());
+        let config = Config::default();
+
+        assert_eq!(
+            expand(
+                "ffuf -u $CTF_URL/FUZZ -w ${CTF_WORDLIST} -H \"Host: FUZZ.$DOMAIN\" $UNSET",
+                &ctf_box,
+                &service(8443, "http"),
+                &config
+            ),
+            format!(
+                "ffuf -u https://10.10.10.5:8443/FUZZ -w {} -H \"Host: FUZZ.lame.htb\" $UNSET",
+                config.wordlists.directories
+            )
+        );
+        assert_eq!(
+            expand("curl $CTF_URL", &ctf_box, &service(8000, "ssl/http-alt"), &config),
+            "curl https://10.10.10.5:8000"
+        );
+        assert_eq!(
+            expand("nc $CTF_IP $CTF_PORT", &ctf_box, &service(21, "ftp"), &config),
+            "nc 10.10.10.5 21"
+        );
+    }
+
+    #[test]
+    fn filled_values_are_quoted_for_bash() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.env_vars.insert("USER".to_string(), "o'neil; id".to_string());
+        ctf_box.env_vars.insert("PASS".to_string(), "p\"$(id)".to_string());
+        let command = expand(
+            "nxc smb $CTF_IP -u $USER -p \"$PASS\"",
+            &ctf_box,
+            &service(445, "microsoft-ds"),
+            &Config::default(),
+        );
+        assert_eq!(command, r#"nxc smb 10.10.10.5 -u 'o'\''neil; id' -p "p\"\$(id)""#);
+    }
+
+    #[test]
+    fn ipv6_hosts_are_bracketed_in_urls() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.ip_address = "dead:beef::1".parse().unwrap();
+        let config = Config::default();
+        assert_eq!(
+            expand("curl $CTF_URL", &ctf_box, &service(80, "http"), &config),
+            "curl 'http://[dead:beef::1]:80'"
+        );
+        assert_eq!(
+            expand("ping $CTF_IP", &ctf_box, &service(80, "http"), &config),
+            "ping dead:beef::1"
+        );
+    }
+
+    #[test]
+    fn logged_commands_match_without_quotes() {
+        assert!(same_command("smbclient -L //10.10.10.5 -N", "smbclient  -L //10.10.10.5 -N"));
+        assert!(same_command(
+            "nxc smb 10.10.10.5 -u  -p  --shares",
+            "nxc smb 10.10.10.5 -u '' -p '' --shares"
+        ));
+        assert!(same_command("ffuf -H Host: FUZZ.box", "ffuf -H \"Host: FUZZ.box\""));
+        assert!(!same_command("nmap 10.10.10.5", "nmap -sV 10.10.10.5"));
+    }
+
+    #[test]
+    fn suggestions_follow_the_scanned_services() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.actions.push(Action {
+            timestamp: chrono::Utc::now(),
+            command: "nmap -sV 10.10.10.5".to_string(),
+            result: crate::models::ActionResult::Success,
+            note: None,
+            output: Some("21/tcp open ftp vsftpd 2.3.4\n80/tcp open http Apache".to_string()),
+        });
+        let config = Config::default();
+        let rules = vec![
+            Rule { command: "nikto -h $CTF_URL".to_string(), ..rule(&["http"], &[]) },
+            Rule { command: "nikto -h $CTF_URL".to_string(), ..rule(&[], &[80]) },
+            Rule { command: "ftp $CTF_IP".to_string(), ..rule(&["ftp"], &[]) },
+        ];
+        let before = for_box(&ctf_box, &rules, &config);
+        let commands: Vec<(&str, bool)> =
+            before.iter().map(|s| (s.command.as_str(), s.done)).collect();
+        assert_eq!(
+            commands,
+            [("ftp 10.10.10.5", false), ("nikto -h http://10.10.10.5:80", false)]
+        );
+
+        ctf_box.actions.push(Action {
+            command: "nikto -h http://10.10.10.5:80".to_string(),
+            output: None,
+            ..ctf_box.actions[0].clone()
+        });
+        let after = for_box(&ctf_box, &rules, &config);
+        assert!(after[1].done);
+        assert!(!after[0].done);
+    }
+}
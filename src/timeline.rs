@@ -0,0 +1,307 @@
+use crate::models::{ActionResult, BoxStatus, CtfBox, FlagKind};
+use chrono::{DateTime, Duration, Utc};
+
+/// Quiet time between two events shown as a break
+const BREAK_MINUTES: i64 = 60;
+
+/// Something that happened on a box
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub timestamp: DateTime<Utc>,
+    pub kind: EventKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventKind {
+    Created,
+    Action(ActionResult),
+    Note,
+    Flag(FlagKind),
+    Status(BoxStatus),
+    Session,
+    Outage,
+}
+
+impl EventKind {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            EventKind::Created => "📦",
+            EventKind::Action(ActionResult::Success) => "✅",
+            EventKind::Action(ActionResult::Fail) => "❌",
+            EventKind::Action(ActionResult::Unknown) => "❓",
+            EventKind::Note => "📝",
+            EventKind::Flag(_) => "🚩",
+            EventKind::Status(_) => "🏁",
+            EventKind::Session => "🐚",
+            EventKind::Outage => "📡",
+        }
+    }
+}
+
+/// An event, or a break between two events
+#[derive(Debug, Clone)]
+pub enum Item {
+    Event(Event),
+    Break(Duration),
+}
+
+/// Everything that happened on the box, oldest first, with the breaks
+pub fn build(ctf_box: &CtfBox) -> Vec<Item> {
+    let mut items: Vec<Item> = Vec::new();
+    let mut last: Option<DateTime<Utc>> = None;
+    for event in events(ctf_box) {
+        if let Some(last) = last {
+            let quiet = event.timestamp - last;
+            if quiet >= Duration::minutes(BREAK_MINUTES) {
+                items.push(Item::Break(quiet));
+            }
+        }
+        last = Some(event.timestamp);
+        items.push(Item::Event(event));
+    }
+    items
+}
+
+/// Events of the box sorted by time, with the status changes they caused
+fn events(ctf_box: &CtfBox) -> Vec<Event> {
+    let mut events = vec![Event {
+        timestamp: ctf_box.created_date,
+        kind: EventKind::Created,
+        text: format!("Box added ({}, {})", ctf_box.platform, ctf_box.ip_address),
+    }];
+
+    events.extend(ctf_box.actions.iter().map(|action| Event {
+        timestamp: action.timestamp,
+        kind: EventKind::Action(action.result.clone()),
+        text: match &action.note {
+            Some(note) => format!("{} - {}", action.command, note),
+            None => action.command.clone(),
+        },
+    }));
+    events.extend(ctf_box.notes.iter().map(|note| Event {
+        timestamp: note.created_date,
+        kind: EventKind::Note,
+        text: format!(
+            "{:?}: {}",
+            note.category,
+            note.content.lines().next().unwrap_or_default()
+        ),
+    }));
+    events.extend(ctf_box.flags.iter().map(|flag| Event {
+        timestamp: flag.captured_date,
+        kind: EventKind::Flag(flag.kind),
+        text: format!("{:?} flag captured", flag.kind),
+    }));
+    events.extend(ctf_box.sessions.iter().map(|session| Event {
+        timestamp: session.started,
+        kind: EventKind::Session,
+        text: format!(
+            "Shell session ({})",
+            crate::stats::format_hours((session.ended - session.started).num_minutes() as f64 / 60.0)
+        ),
+    }));
+    events.extend(ctf_box.outages.iter().map(|outage| {
+        let what = if outage.vpn_down {
+            "VPN down, box unreachable"
+        } else {
+            "Box unreachable"
+        };
+        Event {
+            timestamp: outage.started,
+            kind: EventKind::Outage,
+            text: match outage.ended {
+                Some(ended) => format!(
+                    "{} for {}",
+                    what,
+                    crate::stats::format_hours((ended - outage.started).num_minutes() as f64 / 60.0)
+                ),
+                None => format!("{} (ongoing)", what),
+            },
+        }
+    }));
+    events.sort_by_key(|e| e.timestamp);
+
+    // Replay the events to find when the status changed
+    let mut status = BoxStatus::New;
+    let mut activity = false;
+    let mut flags: Vec<FlagKind> = Vec::new();
+    let mut with_status = Vec::with_capacity(events.len());
+    for event in events {
+        match event.kind {
+            EventKind::Action(_) | EventKind::Note => activity = true,
+            EventKind::Flag(kind) => flags.push(kind),
+            _ => {}
+        }
+        let timestamp = event.timestamp;
+        with_status.push(event);
+
+        let now = BoxStatus::of(activity, flags.iter().copied());
+        if now != status {
+            status = now;
+            with_status.push(Event {
+                timestamp,
+                kind: EventKind::Status(now),
+                text: format!("Status: {}", now.label()),
+            });
+        }
+    }
+    with_status
+}
+
+/// "2h10 break"
+pub fn break_label(quiet: Duration) -> String {
+    format!(
+        "{} break",
+        crate::stats::format_hours(quiet.num_minutes() as f64 / 60.0)
+    )
+}
+
+/// Timeline section of the write-up
+pub fn markdown(ctf_box: &CtfBox) -> String {
+    let mut md = String::new();
+    let mut day = None;
+    for item in build(ctf_box) {
+        match item {
+            Item::Event(event) => {
+                let local = event.timestamp.with_timezone(&chrono::Local);
+                if day != Some(local.date_naive()) {
+                    day = Some(local.date_naive());
+                    md.push_str(&format!("\n**{}**\n\n", local.format("%Y-%m-%d")));
+                }
+                // Commands as code, they are full of markdown characters
+                let text = match event.kind {
+                    EventKind::Action(_) => format!("`{}`", event.text),
+                    _ => event.text,
+                };
+                md.push_str(&format!(
+                    "- {} {} {}\n",
+                    local.format("%H:%M"),
+                    event.kind.icon(),
+                    text
+                ));
+            }
+            Item::Break(quiet) => md.push_str(&format!("- *⏸ {}*\n", break_label(quiet))),
+        }
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Action, Flag, Note, NoteCategory, Outage};
+
+    fn action(ctf_box: &CtfBox, minutes: i64, command: &str) -> Action {
+        Action {
+            timestamp: ctf_box.created_date + Duration::minutes(minutes),
+            command: command.to_string(),
+            result: ActionResult::Success,
+            note: None,
+            output: None,
+        }
+    }
+
+    fn flag(ctf_box: &CtfBox, minutes: i64, kind: FlagKind) -> Flag {
+        Flag {
+            kind,
+            value: "flag".to_string(),
+            captured_date: ctf_box.created_date + Duration::minutes(minutes),
+        }
+    }
+
+    /// Items as "+minutes text" and "break minutes"
+    fn summary(ctf_box: &CtfBox) -> Vec<String> {
+        build(ctf_box)
+            .into_iter()
+            .map(|item| match item {
+                Item::Event(event) => format!(
+                    "+{} {}",
+                    (event.timestamp - ctf_box.created_date).num_minutes(),
+                    event.text
+                ),
+                Item::Break(quiet) => format!("break {}", quiet.num_minutes()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn events_are_sorted_with_the_status_changes() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.flags = vec![flag(&ctf_box, 50, FlagKind::User)];
+        ctf_box.actions = vec![
+            action(&ctf_box, 20, "smbclient -L //10.10.10.5"),
+            action(&ctf_box, 5, "nmap 10.10.10.5"),
+        ];
+        ctf_box.notes = vec![Note {
+            category: NoteCategory::Foothold,
+            content: "usermap_script\nsecond line".to_string(),
+            created_date: ctf_box.created_date + Duration::minutes(30),
+            images: Vec::new(),
+        }];
+        assert_eq!(
+            summary(&ctf_box),
+            [
+                "+0 Box added (HTB, 10.10.10.5)",
+                "+5 nmap 10.10.10.5",
+                "+5 Status: in progress",
+                "+20 smbclient -L //10.10.10.5",
+                "+30 Foothold: usermap_script",
+                "+50 User flag captured",
+                "+50 Status: user",
+            ]
+        );
+    }
+
+    #[test]
+    fn status_only_moves_up() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        // Root before user, as when the user flag is entered late
+        ctf_box.flags = vec![flag(&ctf_box, 10, FlagKind::Root), flag(&ctf_box, 20, FlagKind::User)];
+        ctf_box.actions = vec![action(&ctf_box, 30, "id")];
+        let statuses: Vec<String> = summary(&ctf_box)
+            .into_iter()
+            .filter(|s| s.contains("Status"))
+            .collect();
+        assert_eq!(statuses, ["+10 Status: rooted"]);
+    }
+
+    #[test]
+    fn quiet_hours_are_breaks() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.actions = vec![
+            action(&ctf_box, 59, "nmap 10.10.10.5"),
+            action(&ctf_box, 119, "whoami"),
+            action(&ctf_box, 300, "id"),
+        ];
+        ctf_box.outages = vec![Outage {
+            started: ctf_box.created_date + Duration::minutes(130),
+            ended: None,
+            vpn_down: false,
+        }];
+        assert_eq!(
+            summary(&ctf_box),
+            [
+                "+0 Box added (HTB, 10.10.10.5)",
+                "+59 nmap 10.10.10.5",
+                "+59 Status: in progress",
+                "break 60",
+                "+119 whoami",
+                "+130 Box unreachable (ongoing)",
+                "break 170",
+                "+300 id",
+            ]
+        );
+        assert_eq!(break_label(Duration::minutes(170)), "2h50 break");
+    }
+
+    #[test]
+    fn markdown_quotes_commands_and_shows_breaks() {
+        let mut ctf_box = CtfBox::sample(1, "Lame");
+        ctf_box.actions = vec![action(&ctf_box, 90, "ls *.txt")];
+        let md = markdown(&ctf_box);
+        assert!(md.contains("`ls *.txt`"), "{}", md);
+        assert!(md.contains("- *⏸ 1h30 break*\n"), "{}", md);
+        assert!(md.starts_with("\n**"), "{}", md);
+    }
+}
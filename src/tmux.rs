@@ -0,0 +1,210 @@
+use crate::config::Config;
+use crate::models::CtfBox;
+use crate::quote;
+use crate::storage::ShellKind;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+
+/// Session of a box: `ctf-` and its title as lowercase words joined by `-`
+/// (tmux forbids `.` and `:`), then its id since titles may repeat.
+/// Sessions are found by the id alone (`find`), so that one created
+/// before the box was renamed is still its session.
+pub fn session_name(ctf_box: &CtfBox) -> String {
+    let slug = ctf_box
+        .title
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        format!("ctf-{}", ctf_box.id)
+    } else {
+        format!("ctf-{}-{}", slug, ctf_box.id)
+    }
+}
+
+/// Names of the running sessions, empty when tmux is missing or has no server
+pub fn sessions() -> Vec<String> {
+    Command::new("tmux")
+        .args(["list-sessions", "-F", "#{session_name}"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// List the sessions in a thread, the receiver gets them when done
+pub fn spawn_sessions() -> Receiver<Vec<String>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(sessions());
+    });
+    receiver
+}
+
+/// Session of the box among `sessions`: `ctf-{id}` or `ctf-…-{id}`
+pub fn find(sessions: &[String], box_id: i32) -> Option<String> {
+    let bare = format!("ctf-{}", box_id);
+    let suffix = format!("-{}", box_id);
+    sessions
+        .iter()
+        .find(|name| **name == bare || (name.starts_with("ctf-") && name.ends_with(&suffix)))
+        .cloned()
+}
+
+// Targets start with `=` so tmux matches the session name exactly, not
+// a session whose name starts with it
+
+/// Create the box's session unless it runs already, then attach to it.
+/// Inside tmux the client switches to it and this returns at once,
+/// otherwise it returns when the session is detached or ends.
+pub fn open(ctf_box: &CtfBox, config: &Config) -> Result<(), String> {
+    let name = match find(&sessions(), ctf_box.id) {
+        Some(name) => name,
+        None => {
+            let name = session_name(ctf_box);
+            create(ctf_box, config, &name)?;
+            name
+        }
+    };
+
+    let attach = if std::env::var_os("TMUX").is_some() {
+        "switch-client"
+    } else {
+        "attach-session"
+    };
+    run(Command::new("tmux").args([attach, "-t", &format!("={}", name)]))
+}
+
+pub fn kill(name: &str) -> Result<(), String> {
+    run(Command::new("tmux").args(["kill-session", "-t", &format!("={}", name)]))
+}
+
+/// One window per task, each a box shell. The listener, notes and server
+/// windows get their command typed in (`listen` and `serve` are defined by
+/// the box environment), so the shell stays when it ends. A session left
+/// half built by a failing step is killed, so the next `open` starts over.
+fn create(ctf_box: &CtfBox, config: &Config, name: &str) -> Result<(), String> {
+    let shell = crate::storage::shell_command(ctf_box, config)
+        .map_err(|e| format!("Failed to prepare the shell: {}", e))?;
+    let kind = ShellKind::of(&shell.get_program().to_string_lossy());
+
+    // The box environment is loaded by the shell itself, only what the
+    // command sets (ZDOTDIR for zsh) has to be passed to tmux
+    let mut environment = Vec::new();
+    for (key, value) in shell.get_envs() {
+        if let Some(value) = value {
+            environment.push("-e".to_string());
+            environment.push(format!("{}={}", key.to_string_lossy(), value.to_string_lossy()));
+        }
+    }
+    let mut argv = vec!["--".to_string(), shell.get_program().to_string_lossy().to_string()];
+    argv.extend(shell.get_args().map(|arg| arg.to_string_lossy().to_string()));
+
+    let notes = scratchpad(ctf_box, config)?;
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let windows = [
+        ("listener", "listen".to_string()),
+        ("notes", format!("{} {}", editor, quote_path(kind, &notes))),
+        ("server", "serve".to_string()),
+    ];
+
+    run(Command::new("tmux")
+        .args(["new-session", "-d", "-s", name, "-n", "shell"])
+        .args(&environment)
+        .args(&argv))?;
+    let built = windows.into_iter().try_for_each(|(window, line)| {
+        let target = format!("={}:", name);
+        run(Command::new("tmux")
+            .args(["new-window", "-d", "-t", &target, "-n", window])
+            .args(&environment)
+            .args(&argv))?;
+        let pane = format!("={}:{}", name, window);
+        run(Command::new("tmux").args(["send-keys", "-t", &pane, &line, "Enter"]))
+    });
+    if built.is_err() {
+        let _ = kill(name);
+    }
+    built
+}
+
+/// Free-form notes of the box, kept next to its environment files
+fn scratchpad(ctf_box: &CtfBox, config: &Config) -> Result<std::path::PathBuf, String> {
+    let path = config
+        .base_dir()
+        .join("boxes")
+        .join(format!("box-{}-scratchpad.md", ctf_box.id));
+    if !path.exists() {
+        std::fs::write(&path, format!("# {}\n\n", ctf_box.title))
+            .map_err(|e| format!("Failed to create the scratchpad: {}", e))?;
+    }
+    Ok(path)
+}
+
+fn quote_path(kind: ShellKind, path: &Path) -> String {
+    let path = path.display().to_string();
+    match kind {
+        ShellKind::Bash | ShellKind::Zsh => quote::sh(&path),
+        ShellKind::Fish => quote::fish(&path),
+        ShellKind::Nu => quote::nu(&path),
+    }
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let output = command
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| format!("Failed to run tmux: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "tmux failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn session_name_from_title_and_id() {
+        assert_eq!(session_name(&CtfBox::sample(3, "Lame")), "ctf-lame-3");
+        assert_eq!(session_name(&CtfBox::sample(7, "Web: Gauntlet v1.2")), "ctf-web-gauntlet-v1-2-7");
+        assert_eq!(session_name(&CtfBox::sample(4, "🧠")), "ctf-4");
+    }
+
+    #[test]
+    fn session_found_after_a_rename() {
+        let running = names(&["main", "ctf-lame-3", "ctf-13"]);
+        let renamed = CtfBox::sample(3, "Lame (retired)");
+        assert_eq!(find(&running, renamed.id), Some("ctf-lame-3".to_string()));
+        assert_eq!(find(&running, 13), Some("ctf-13".to_string()));
+    }
+
+    #[test]
+    fn session_of_another_id_is_not_matched() {
+        let running = names(&["ctf-box-13", "ctf-box-3-7", "work-3"]);
+        assert_eq!(find(&running, 3), None);
+        assert_eq!(find(&running, 7), Some("ctf-box-3-7".to_string()));
+    }
+}
@@ -1,13 +1,16 @@
 use crate::app::{AddBoxForm, App};
+use crate::ui::input::TextInput;
+use crate::ui::theme::Theme;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
-pub fn render(f: &mut Frame, _app: &App, form: &AddBoxForm, area: Rect) {
+pub fn render(f: &mut Frame, app: &App, form: &AddBoxForm, area: Rect) {
+    let theme = app.theme;
     // Calculate center position for modal (make responsive to small terminals)
     let modal_width = std::cmp::min(60, area.width.saturating_sub(4));
     let modal_height = std::cmp::min(20, area.height.saturating_sub(2));
@@ -28,7 +31,7 @@ pub fn render(f: &mut Frame, _app: &App, form: &AddBoxForm, area: Rect) {
     let block = Block::default()
         .title("➕ Add New CTF Box")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.accent));
     
     let inner = block.inner(modal_area);
     f.render_widget(block, modal_area);
@@ -47,74 +50,60 @@ pub fn render(f: &mut Frame, _app: &App, form: &AddBoxForm, area: Rect) {
         .split(inner);
     
     // Render each field with a single-line display
-    render_field(f, chunks[0], "Title", &form.title, form.current_field == 0);
-    render_field(f, chunks[1], "Platform", &form.platform, form.current_field == 1);
-    render_field(f, chunks[2], "IP Address", &form.ip, form.current_field == 2);
-    render_field(f, chunks[3], "Tags", &form.tags, form.current_field == 3);
+    render_field(f, theme, chunks[0], "Title", &form.title, form.current_field == 0);
+    render_field(f, theme, chunks[1], "Platform", &form.platform, form.current_field == 1);
+    render_field(f, theme, chunks[2], "IP Address", &form.ip, form.current_field == 2);
+    render_field(f, theme, chunks[3], "Tags", &form.tags, form.current_field == 3);
     
     // Help text
     let help = Paragraph::new(vec![
         Line::from(vec![
-            Span::styled("Tab", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::styled("Tab", Style::default().fg(theme.success).add_modifier(Modifier::BOLD)),
             Span::raw("/"),
-            Span::styled("Shift+Tab", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::styled("Shift+Tab", Style::default().fg(theme.success).add_modifier(Modifier::BOLD)),
             Span::raw(": Navigate | "),
-            Span::styled("Enter", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::styled("Enter", Style::default().fg(theme.success).add_modifier(Modifier::BOLD)),
             Span::raw(": Submit | "),
-            Span::styled("Esc", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            Span::styled("Esc", Style::default().fg(theme.error).add_modifier(Modifier::BOLD)),
             Span::raw(": Cancel"),
         ]),
         Line::from(vec![
-            Span::styled("Platform: ", Style::default().fg(Color::DarkGray)),
-            Span::raw("HTB, picoCTF, TryHackMe, etc."),
+            Span::styled("Platform: ", Style::default().fg(theme.muted)),
+            Span::raw(app.config.platforms.known.join(", ")),
         ]),
     ])
     .alignment(Alignment::Center);
     f.render_widget(help, chunks[4]);
-
-    // Set cursor to active field
-    let (active_chunk, text) = match form.current_field {
-        0 => (chunks[0], &form.title),
-        1 => (chunks[1], &form.platform),
-        2 => (chunks[2], &form.ip),
-        3 => (chunks[3], &form.tags),
-        _ => return,
-    };
-    
-    // Position cursor at end of text in the input area
-    // The text starts after "Label: " which is calculated dynamically
-    let label = match form.current_field {
-        0 => "Title: ",
-        1 => "Platform: ",
-        2 => "IP Address: ",
-        3 => "Tags: ",
-        _ => "",
-    };
-    
-    f.set_cursor_position((
-        active_chunk.x + 1 + label.len() as u16 + text.chars().count() as u16,
-        active_chunk.y + 1
-    ));
 }
 
-/// Helper function to render a single field
-fn render_field(f: &mut Frame, area: Rect, label: &str, value: &str, is_active: bool) {
+/// Helper function to render a single field, with the cursor when active
+fn render_field(
+    f: &mut Frame,
+    theme: Theme,
+    area: Rect,
+    label: &str,
+    input: &TextInput,
+    is_active: bool,
+) {
     let style = if is_active {
-        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.text)
     };
     
     let border_style = if is_active {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.highlight)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme.muted)
     };
     
-    // Display label and value on the same line
+    // Display label and value on the same line, scrolled to keep the cursor visible
+    let prefix = format!("{}: ", label);
+    let width = area.width.saturating_sub(2 + prefix.len() as u16) as usize;
+    let (value, cursor) = input.visible(width);
     let content = Line::from(vec![
-        Span::styled(format!("{}: ", label), style),
-        Span::styled(value, Style::default().fg(Color::Cyan)),
+        Span::styled(prefix.clone(), style),
+        Span::styled(value, Style::default().fg(theme.accent)),
     ]);
     
     let field = Paragraph::new(content)
@@ -123,4 +112,11 @@ fn render_field(f: &mut Frame, area: Rect, label: &str, value: &str, is_active:
             .border_style(border_style));
     
     f.render_widget(field, area);
+
+    if is_active {
+        f.set_cursor_position((
+            area.x + 1 + prefix.len() as u16 + cursor as u16,
+            area.y + 1,
+        ));
+    }
 }
\ No newline at end of file
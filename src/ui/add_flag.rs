@@ -0,0 +1,91 @@
+use crate::app::{App, FlagForm};
+use crate::models::FlagKind;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &App, form: &FlagForm, area: Rect) {
+    let theme = app.theme;
+    let box_name = app.boxes.iter()
+        .find(|b| b.id == form.box_id)
+        .map(|b| b.title.clone())
+        .unwrap_or_else(|| String::from("Unknown Box"));
+
+    // Calculate center position for modal
+    let modal_width = std::cmp::min(60, area.width.saturating_sub(4));
+    let modal_height = std::cmp::min(10, area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(modal_width)) / 2;
+    let y = (area.height.saturating_sub(modal_height)) / 2;
+
+    let modal_area = Rect {
+        x,
+        y,
+        width: modal_width,
+        height: modal_height,
+    };
+
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(format!("🚩 Flag for {}", box_name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1),  // Kind
+            Constraint::Length(3),  // Value
+            Constraint::Length(1),  // Help text
+        ])
+        .split(inner);
+
+    // Kind selector: the current kind is highlighted
+    let mut kinds = vec![Span::styled("Kind: ", Style::default().fg(theme.text))];
+    for kind in FlagKind::ALL {
+        let style = if kind == form.kind {
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        kinds.push(Span::styled(format!(" {:?} ", kind), style));
+        kinds.push(Span::raw(" "));
+    }
+    f.render_widget(Paragraph::new(Line::from(kinds)), chunks[0]);
+
+    // Value, scrolled to keep the cursor visible
+    let prefix = "Flag: ";
+    let width = chunks[1].width.saturating_sub(2 + prefix.len() as u16) as usize;
+    let (value, cursor) = form.value.visible(width);
+    let field = Paragraph::new(Line::from(vec![
+        Span::styled(prefix, Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+        Span::styled(value, Style::default().fg(theme.accent)),
+    ]))
+    .block(Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.highlight)));
+    f.render_widget(field, chunks[1]);
+    f.set_cursor_position((
+        chunks[1].x + 1 + prefix.len() as u16 + cursor as u16,
+        chunks[1].y + 1,
+    ));
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Tab", Style::default().fg(theme.success).add_modifier(Modifier::BOLD)),
+        Span::raw(": Kind | "),
+        Span::styled("Enter", Style::default().fg(theme.success).add_modifier(Modifier::BOLD)),
+        Span::raw(": Save | "),
+        Span::styled("Esc", Style::default().fg(theme.error).add_modifier(Modifier::BOLD)),
+        Span::raw(": Cancel"),
+    ]))
+    .alignment(Alignment::Center);
+    f.render_widget(help, chunks[2]);
+}
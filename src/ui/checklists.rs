@@ -0,0 +1,154 @@
+use crate::app::{App, TemplatePicker};
+use crate::ui::Clickable;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+pub fn render(
+    f: &mut Frame,
+    app: &App,
+    picker: Option<&TemplatePicker>,
+    area: Rect,
+    box_id: i32,
+) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
+    let ctf_box = match app.boxes.iter().find(|b| b.id == box_id) {
+        Some(b) => b,
+        None => return Vec::new(),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(5),    // Checklists
+        ])
+        .split(area);
+
+    let done: usize = ctf_box.checklists.iter().map(|c| c.done_count()).sum();
+    let total: usize = ctf_box.checklists.iter().map(|c| c.items.len()).sum();
+    let header = Paragraph::new(format!("☑️  Checklists - {} ({}/{} done)", ctf_box.title, done, total))
+        .style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, chunks[0]);
+
+    // One row per checklist name, then its items. `rows` maps each row
+    // to the index of its item across all checklists.
+    let mut items = Vec::new();
+    let mut rows: Vec<Option<usize>> = Vec::new();
+    let mut index = 0;
+    for checklist in &ctf_box.checklists {
+        items.push(ListItem::new(Line::from(Span::styled(
+            format!("{} ({}/{})", checklist.name, checklist.done_count(), checklist.items.len()),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        ))));
+        rows.push(None);
+        for item in &checklist.items {
+            let (mark, style) = if item.done {
+                ("[x] ", Style::default().fg(theme.success))
+            } else {
+                ("[ ] ", Style::default().fg(theme.text))
+            };
+            let mut spans = vec![Span::styled(format!("  {}{}", mark, item.text), style)];
+            if let Some(command) = item.matched.as_ref().filter(|_| item.done) {
+                spans.push(Span::styled(
+                    format!("  ← {}", command),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            items.push(ListItem::new(Line::from(spans)));
+            rows.push(Some(index));
+            index += 1;
+        }
+    }
+
+    let empty = ctf_box.checklists.is_empty();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Methodology")
+        .border_style(Style::default().fg(theme.accent));
+    let mut clickables = Vec::new();
+    if empty {
+        let hint = Paragraph::new("No checklist yet, add one from the templates")
+            .style(Style::default().fg(theme.muted))
+            .block(block);
+        f.render_widget(hint, chunks[1]);
+    } else {
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD));
+        let selected_row = app
+            .selected_check
+            .and_then(|selected| rows.iter().position(|r| *r == Some(selected)));
+        let mut state = ListState::default().with_selected(selected_row);
+        f.render_stateful_widget(list, chunks[1], &mut state);
+
+        let inner = Block::default().borders(Borders::ALL).inner(chunks[1]);
+        clickables = rows
+            .iter()
+            .skip(state.offset())
+            .zip(inner.rows())
+            .filter_map(|(item, row)| item.map(|i| (row, Clickable::Row(i))))
+            .collect();
+    }
+
+    if let Some(picker) = picker {
+        render_picker(f, app, picker, ctf_box, area);
+        return Vec::new();
+    }
+    clickables
+}
+
+/// Popup listing the templates to add
+fn render_picker(
+    f: &mut Frame,
+    app: &App,
+    picker: &TemplatePicker,
+    ctf_box: &crate::models::CtfBox,
+    area: Rect,
+) {
+    let theme = app.theme;
+    let modal_width = std::cmp::min(50, area.width.saturating_sub(4));
+    let modal_height = std::cmp::min(picker.templates.len() as u16 + 2, area.height.saturating_sub(2));
+    let modal_area = Rect {
+        x: (area.width.saturating_sub(modal_width)) / 2,
+        y: (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+    f.render_widget(Clear, modal_area);
+
+    let items: Vec<ListItem> = picker
+        .templates
+        .iter()
+        .map(|template| {
+            let added = ctf_box.checklists.iter().any(|c| c.template == template.id);
+            let mut spans = vec![
+                Span::raw(template.name.clone()),
+                Span::styled(
+                    format!("  {} items", template.items.len()),
+                    Style::default().fg(theme.muted),
+                ),
+            ];
+            if added {
+                spans.push(Span::styled("  (added)", Style::default().fg(theme.success)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("➕ Add a checklist")
+                .border_style(Style::default().fg(theme.highlight)),
+        )
+        .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default().with_selected(Some(picker.selected));
+    f.render_stateful_widget(list, modal_area, &mut state);
+}
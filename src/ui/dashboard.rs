@@ -0,0 +1,186 @@
+use crate::app::{App, DashboardPane};
+use crate::models::{ActionResult, CtfBox, FlagKind};
+use crate::ui::Clickable;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) -> Vec<(Rect, Clickable)> {
+    let [boxes, info, feed] = app.dashboard.widths;
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(boxes),
+            Constraint::Percentage(info),
+            Constraint::Percentage(feed),
+        ])
+        .split(area);
+
+    let clickables = render_boxes(f, app, panes[0]);
+    render_info(f, app, panes[1]);
+    render_feed(f, app, panes[2]);
+    clickables
+}
+
+/// Pane block, highlighted when it has the focus
+fn pane_block(app: &App, pane: DashboardPane, title: &str) -> Block<'static> {
+    let color = if app.dashboard.focus == pane {
+        app.theme.highlight
+    } else {
+        app.theme.muted
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .title(title.to_string())
+        .border_style(Style::default().fg(color))
+}
+
+fn render_boxes(f: &mut Frame, app: &App, area: Rect) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
+    let items: Vec<ListItem> = app
+        .boxes
+        .iter()
+        .map(|ctf_box| {
+            let platform_icon = match ctf_box.platform.as_str() {
+                "HTB" => "🔴",
+                "picoCTF" => "🎯",
+                "TryHackMe" => "🟢",
+                _ => "📦",
+            };
+            let marker = |kind, letter| {
+                if ctf_box.flag(kind).is_some() {
+                    Span::styled(letter, Style::default().fg(theme.success))
+                } else {
+                    Span::styled("·", Style::default().fg(theme.muted))
+                }
+            };
+            ListItem::new(Line::from(vec![
+                marker(FlagKind::User, "U"),
+                marker(FlagKind::Root, "R"),
+                Span::raw(format!(" {} {}", platform_icon, ctf_box.title)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(pane_block(app, DashboardPane::Boxes, "🧩 Boxes"))
+        .highlight_style(
+            Style::default()
+                .bg(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    let mut list_state = ListState::default();
+    list_state.select(app.selected_box_id.map(|idx| idx as usize));
+    f.render_stateful_widget(list, area, &mut list_state);
+    crate::ui::list_rows(area, list_state.offset(), app.boxes.len())
+}
+
+fn render_info(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let block = pane_block(app, DashboardPane::Info, "ℹ️  Info");
+    let Some(ctf_box) = app
+        .selected_box_id
+        .and_then(|idx| app.boxes.get(idx as usize))
+    else {
+        let empty = Paragraph::new("No box selected")
+            .style(Style::default().fg(theme.muted))
+            .block(block);
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(theme.highlight));
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} - {}", ctf_box.title, ctf_box.platform),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![label("IP: "), Span::raw(ctf_box.ip_address.to_string())]),
+        Line::from(vec![label("Tags: "), Span::raw(ctf_box.tags.join(", "))]),
+        Line::from(""),
+        flag_line(app, ctf_box, FlagKind::User, "User: "),
+        flag_line(app, ctf_box, FlagKind::Root, "Root: "),
+        Line::from(""),
+    ];
+
+    let services = crate::recon::box_services(ctf_box);
+    if services.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No services (log an nmap scan from the box shell)",
+            Style::default().fg(theme.muted),
+        )));
+    } else {
+        lines.push(Line::from(label("Services:")));
+        lines.extend(services.iter().skip(app.dashboard.info_scroll).map(|s| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>5}/{:<3} ", s.port, s.protocol),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::raw(format!("{:<12} ", s.name)),
+                Span::styled(s.version.clone(), Style::default().fg(theme.muted)),
+            ])
+        }));
+    }
+
+    let info = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(info, area);
+}
+
+fn flag_line<'a>(app: &App, ctf_box: &CtfBox, kind: FlagKind, label: &'a str) -> Line<'a> {
+    let theme = app.theme;
+    let value = match ctf_box.flag(kind) {
+        Some(flag) => Span::styled(
+            format!("🚩 {}", flag.captured_date.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")),
+            Style::default().fg(theme.success),
+        ),
+        None => Span::styled("—", Style::default().fg(theme.muted)),
+    };
+    Line::from(vec![
+        Span::styled(label, Style::default().fg(theme.highlight)),
+        value,
+    ])
+}
+
+fn render_feed(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let items: Vec<ListItem> = app
+        .action_feed()
+        .into_iter()
+        .skip(app.dashboard.feed_scroll)
+        .map(|(ctf_box, action, live)| {
+            let result_icon = match action.result {
+                ActionResult::Success => "✅",
+                ActionResult::Fail => "❌",
+                ActionResult::Unknown => "❓",
+            };
+            // Commands still in the shell log are not imported yet
+            let live_marker = if live {
+                Span::styled("● ", Style::default().fg(theme.special))
+            } else {
+                Span::raw("  ")
+            };
+            ListItem::new(Line::from(vec![
+                live_marker,
+                Span::styled(
+                    format!("[{}] ", action.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S")),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::styled(
+                    format!("{} ", ctf_box.title),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::raw(format!("{} {}", result_icon, action.command)),
+            ]))
+        })
+        .collect();
+
+    let feed = List::new(items).block(pane_block(app, DashboardPane::Feed, "📡 Activity"));
+    f.render_widget(feed, area);
+}
@@ -1,13 +1,14 @@
 use crate::app::App;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
 pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
+    let theme = app.theme;
     // Determine box name
     let box_name = app.boxes.iter()
         .find(|b| b.id == box_id)
@@ -34,7 +35,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
     let block = Block::default()
         .title("⚠️  Delete Box")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red));
+        .border_style(Style::default().fg(theme.error));
     
     let inner = block.inner(modal_area);
     f.render_widget(block, modal_area);
@@ -59,15 +60,15 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
         Line::from("This action cannot be undone."),
     ])
     .alignment(Alignment::Center)
-    .style(Style::default().fg(Color::White));
+    .style(Style::default().fg(theme.text));
     
     f.render_widget(message, chunks[0]);
     
     // Controls
     let controls = Paragraph::new(Line::from(vec![
-        Span::styled("y", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        Span::styled("y", Style::default().fg(theme.error).add_modifier(Modifier::BOLD)),
         Span::raw(": Confirm | "),
-        Span::styled("n / Esc", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+        Span::styled("n / Esc", Style::default().fg(theme.success).add_modifier(Modifier::BOLD)),
         Span::raw(": Cancel"),
     ]))
     .alignment(Alignment::Center);
@@ -1,34 +1,31 @@
 use crate::app::App;
+use crate::config::Config;
+use crate::models::{Attachment, CtfBox, FlagKind};
+use crate::ui::{Clickable, DetailPane, markdown};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
-pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
+pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
     // Find the box by ID
     let ctf_box = match app.boxes.iter().find(|b| b.id == box_id) {
         Some(b) => b,
         None => {
             let error = Paragraph::new("Box not found!")
-                .style(Style::default().fg(Color::Red));
+                .style(Style::default().fg(theme.error));
             f.render_widget(error, area);
-            return;
+            return Vec::new();
         }
     };
 
-    // Create layout: header, info, notes, actions
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Header
-            Constraint::Length(6),  // Info block
-            Constraint::Min(5),     // Notes
-            Constraint::Min(5),     // Actions
-        ])
-        .split(area);
+    let candidates = crate::exploits::candidates(ctf_box, &app.config);
+    let (chunks, notes_area, image_area) =
+        layout(area, latest_image(ctf_box).is_some(), candidates.len());
 
     // Header with title
     let platform_icon = match ctf_box.platform.as_str() {
@@ -38,12 +35,31 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
         _ => "📦",
     };
     
-    let header = Paragraph::new(format!(
+    let mut title = vec![Span::raw(format!(
         "{} {} - {}",
         platform_icon, ctf_box.title, ctf_box.platform
-    ))
-    .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
-    .block(Block::default().borders(Borders::ALL));
+    ))];
+    if let Some(probe) = app.reachability.get(&box_id) {
+        let since = ctf_box
+            .outages
+            .last()
+            .filter(|o| o.ended.is_none())
+            .map(|o| format!(" since {}", o.started.with_timezone(&chrono::Local).format("%H:%M")))
+            .unwrap_or_default();
+        title.push(Span::styled(
+            format!("   ● {}{}", probe.label(), since),
+            Style::default().fg(crate::ui::reachability_color(app, probe)),
+        ));
+    }
+    if let Some(session) = app.tmux_session(box_id) {
+        title.push(Span::styled(
+            format!("   ⧉ {}", session),
+            Style::default().fg(theme.special),
+        ));
+    }
+    let header = Paragraph::new(Line::from(title))
+        .style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        .block(Block::default().borders(Borders::ALL));
     
     f.render_widget(header, chunks[0]);
 
@@ -51,20 +67,36 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
     let tags_str = ctf_box.tags.join(", ");
     let info_text = vec![
         Line::from(vec![
-            Span::styled("IP: ", Style::default().fg(Color::Yellow)),
+            Span::styled("IP: ", Style::default().fg(theme.highlight)),
             Span::raw(ctf_box.ip_address.to_string()),
+            Span::styled(
+                if ctf_box.hostnames.is_empty() {
+                    String::new()
+                } else {
+                    format!("  {}", ctf_box.hostnames.join(" "))
+                },
+                Style::default().fg(theme.muted),
+            ),
         ]),
         Line::from(vec![
-            Span::styled("Tags: ", Style::default().fg(Color::Yellow)),
+            Span::styled("Tags: ", Style::default().fg(theme.highlight)),
             Span::raw(tags_str),
         ]),
         Line::from(vec![
-            Span::styled("Created: ", Style::default().fg(Color::Yellow)),
-            Span::raw(ctf_box.created_date.format("%Y-%m-%d %H:%M").to_string()),
+            Span::styled("Flags: ", Style::default().fg(theme.highlight)),
+            Span::raw(flags_summary(ctf_box)),
+        ]),
+        Line::from(vec![
+            Span::styled("Checklists: ", Style::default().fg(theme.highlight)),
+            Span::raw(checklists_summary(ctf_box)),
+        ]),
+        Line::from(vec![
+            Span::styled("Created: ", Style::default().fg(theme.highlight)),
+            Span::raw(ctf_box.created_date.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()),
         ]),
         Line::from(vec![
-            Span::styled("Updated: ", Style::default().fg(Color::Yellow)),
-            Span::raw(ctf_box.updated_date.format("%Y-%m-%d %H:%M").to_string()),
+            Span::styled("Updated: ", Style::default().fg(theme.highlight)),
+            Span::raw(ctf_box.updated_date.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()),
         ]),
     ];
     
@@ -74,6 +106,35 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
     
     f.render_widget(info, chunks[1]);
 
+    // Exploit candidates for the service versions, when there are some
+    if !candidates.is_empty() {
+        let lines: Vec<Line> = candidates
+            .iter()
+            .map(|candidate| {
+                let reference = candidate.exploit.reference();
+                let noted = ctf_box.notes.iter().any(|n| n.content.contains(&reference));
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", if noted { "✓" } else { "•" }),
+                        Style::default().fg(if noted { theme.success } else { theme.muted }),
+                    ),
+                    Span::styled(
+                        format!("{}/{} ", candidate.service.port, candidate.service.protocol),
+                        Style::default().fg(theme.highlight),
+                    ),
+                    Span::styled(format!("{}  ", reference), Style::default().fg(theme.error)),
+                    Span::raw(candidate.exploit.title.clone()),
+                ])
+            })
+            .collect();
+        let exploits = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("💣 Exploit candidates ({})", candidates.len())),
+        );
+        f.render_widget(exploits, chunks[2]);
+    }
+
     // Notes section
     let note_items: Vec<ListItem> = ctf_box
         .notes
@@ -91,14 +152,48 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
                 crate::models::NoteCategory::Misc => "📝",
             };
             
-            ListItem::new(format!("{} {:?}: {}", category_icon, note.category, note.content))
+            // Markdown content, continuation lines indented under the category
+            let mut lines = markdown::render(&note.content, theme);
+            if lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines[0]
+                .spans
+                .insert(0, Span::raw(format!("{} {:?}: ", category_icon, note.category)));
+            for line in lines.iter_mut().skip(1) {
+                line.spans.insert(0, Span::raw("   "));
+            }
+            ListItem::new(lines)
         })
         .collect();
     
     let notes = List::new(note_items)
         .block(Block::default().borders(Borders::ALL).title("📝 Notes"));
     
-    f.render_widget(notes, chunks[2]);
+    let mut notes_state = ListState::default().with_offset(app.notes_scroll);
+    f.render_stateful_widget(notes, notes_area, &mut notes_state);
+
+    // Screenshot pane: the thumbnail itself is drawn after the frame (see ui::graphics)
+    if let (Some(image), Some(image_area)) = (latest_image(ctf_box), image_area) {
+        let caption = image.note.clone().unwrap_or_else(|| image.name.clone());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("🖼️  {}", caption));
+        if !app.graphics.is_supported() {
+            let text = Paragraph::new(vec![
+                Line::from(Span::raw(image.name.clone())),
+                Line::from(Span::styled(
+                    "No terminal graphics — open it from the loot view (o)",
+                    Style::default().fg(theme.muted),
+                )),
+            ])
+            .wrap(Wrap { trim: true })
+            .block(block);
+            f.render_widget(text, image_area);
+        } else {
+            f.render_widget(block, image_area);
+        }
+    }
 
     // Actions section
     let action_items: Vec<ListItem> = ctf_box
@@ -111,7 +206,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
                 crate::models::ActionResult::Unknown => "❓",
             };
             
-            let time = action.timestamp.format("%H:%M:%S");
+            let time = action.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S");
             let note_suffix = action.note.as_ref()
                 .map(|n| format!(" - {}", n))
                 .unwrap_or_default();
@@ -126,5 +221,81 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
     let actions = List::new(action_items)
         .block(Block::default().borders(Borders::ALL).title("🔧 Actions"));
     
-    f.render_widget(actions, chunks[3]);
-}
\ No newline at end of file
+    let mut actions_state = ListState::default().with_offset(app.actions_scroll);
+    f.render_stateful_widget(actions, chunks[4], &mut actions_state);
+
+    vec![
+        (notes_area, Clickable::Pane(DetailPane::Notes)),
+        (chunks[4], Clickable::Pane(DetailPane::Actions)),
+    ]
+}
+
+/// Split the details area: header, info, exploit candidates (hidden
+/// without any), notes (+ screenshot pane), actions
+fn layout(area: Rect, with_image: bool, exploits: usize) -> (Vec<Rect>, Rect, Option<Rect>) {
+    let exploits_height = if exploits == 0 { 0 } else { exploits.min(4) as u16 + 2 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Header
+            Constraint::Length(8),  // Info block
+            Constraint::Length(exploits_height), // Exploit candidates
+            Constraint::Min(5),     // Notes
+            Constraint::Min(5),     // Actions
+        ])
+        .split(area)
+        .to_vec();
+
+    if !with_image {
+        let notes_area = chunks[3];
+        return (chunks, notes_area, None);
+    }
+
+    let notes_row = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(chunks[3]);
+    (chunks, notes_row[0], Some(notes_row[1]))
+}
+
+/// `User 🚩  Root —`, plus the number of other flags
+fn flags_summary(ctf_box: &CtfBox) -> String {
+    let mark = |kind| if ctf_box.flag(kind).is_some() { "🚩" } else { "—" };
+    let mut summary = format!("User {}  Root {}", mark(FlagKind::User), mark(FlagKind::Root));
+    let others = ctf_box.flags.iter().filter(|f| f.kind == FlagKind::Other).count();
+    if others > 0 {
+        summary.push_str(&format!("  +{} other", others));
+    }
+    summary
+}
+
+/// `Linux privesc 3/9 · SMB 2/5`
+fn checklists_summary(ctf_box: &CtfBox) -> String {
+    if ctf_box.checklists.is_empty() {
+        return "—".to_string();
+    }
+    ctf_box
+        .checklists
+        .iter()
+        .map(|c| format!("{} {}/{}", c.name, c.done_count(), c.items.len()))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// The most recent image attached to one of the box's notes
+pub fn latest_image(ctf_box: &CtfBox) -> Option<&Attachment> {
+    ctf_box
+        .notes
+        .iter()
+        .rev()
+        .flat_map(|n| n.images.iter().rev())
+        .find_map(|sha| ctf_box.attachments.iter().find(|a| &a.sha256 == sha))
+}
+
+/// Cells where the screenshot thumbnail goes, matching the layout of `render`
+pub fn thumbnail_area(ctf_box: &CtfBox, area: Rect, config: &Config) -> Option<Rect> {
+    latest_image(ctf_box)?;
+    let exploits = crate::exploits::candidates(ctf_box, config).len();
+    let (_, _, image_area) = layout(area, true, exploits);
+    image_area.map(|a| Block::default().borders(Borders::ALL).inner(a))
+}
@@ -1,17 +1,25 @@
 use crate::app::{App, EnvVarForm};
+use crate::ui::Clickable;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
-pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, box_id: i32) {
+pub fn render(
+    f: &mut Frame,
+    app: &App,
+    form: Option<&EnvVarForm>,
+    area: Rect,
+    box_id: i32,
+) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
     // Find the box
     let ctf_box = match app.boxes.iter().find(|b| b.id == box_id) {
         Some(b) => b,
-        None => return,
+        None => return Vec::new(),
     };
 
     // Main layout
@@ -32,21 +40,21 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
     ))
     .style(
         Style::default()
-            .fg(Color::Cyan)
+            .fg(theme.accent)
             .add_modifier(Modifier::BOLD),
     )
     .block(Block::default().borders(Borders::ALL));
     f.render_widget(header, chunks[0]);
 
-    // Variables list with selection
-    let _keys: Vec<&String> = ctf_box.env_vars.keys().collect();
-    let var_items: Vec<ListItem> = ctf_box
-        .env_vars
-        .iter()
+    // Variables list with selection, by name like `delete_selected_env_var`
+    let mut vars: Vec<(&String, &String)> = ctf_box.env_vars.iter().collect();
+    vars.sort();
+    let var_items: Vec<ListItem> = vars
+        .into_iter()
         .enumerate()
         .map(|(i, (key, value))| {
-            let display_value = if value.len() > 50 {
-                format!("{}...", &value[..50])
+            let display_value = if value.chars().count() > 50 {
+                format!("{}...", value.chars().take(50).collect::<String>())
             } else {
                 value.clone()
             };
@@ -54,7 +62,7 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
             let is_selected = app.selected_env_var == Some(i) && form.is_none();
 
             let style = if is_selected {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
+                Style::default().bg(theme.selection).fg(theme.text)
             } else {
                 Style::default()
             };
@@ -63,9 +71,9 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
 
             ListItem::new(Line::from(vec![
                 Span::styled(prefix, style),
-                Span::styled(key, style.fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled(key, style.fg(theme.highlight).add_modifier(Modifier::BOLD)),
                 Span::styled(" = ", style),
-                Span::styled(display_value, style.fg(Color::White)),
+                Span::styled(display_value, style.fg(theme.text)),
             ]))
         })
         .collect();
@@ -77,6 +85,11 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
     );
 
     f.render_widget(var_list, chunks[1]);
+    // Rows are clickable unless the add form is open
+    let rows = match form {
+        Some(_) => Vec::new(),
+        None => crate::ui::list_rows(chunks[1], 0, ctf_box.env_vars.len()),
+    };
 
     // Form or instructions
     if let Some(form) = form {
@@ -84,7 +97,7 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
         let form_block = Block::default()
             .borders(Borders::ALL)
             .title("➕ Add Variable")
-            .border_style(Style::default().fg(Color::Green));
+            .border_style(Style::default().fg(theme.success));
 
         let form_inner = form_block.inner(chunks[2]);
         f.render_widget(form_block, chunks[2]);
@@ -102,37 +115,42 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
         // Key field
         let key_style = if form.current_field == 0 {
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.highlight)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme.text)
         };
 
-        let key_line = Line::from(vec![Span::styled("KEY: ", key_style), Span::raw(&form.key)]);
+        // "KEY: " = 5 chars, "VALUE: " = 7 chars
+        let key_width = form_chunks[0].width.saturating_sub(5) as usize;
+        let (key_text, key_cursor) = form.key.visible(key_width);
+        let key_line = Line::from(vec![Span::styled("KEY: ", key_style), Span::raw(key_text)]);
         f.render_widget(Paragraph::new(key_line), form_chunks[0]);
 
         // Value field
         let value_style = if form.current_field == 1 {
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.highlight)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme.text)
         };
 
+        let value_width = form_chunks[1].width.saturating_sub(7) as usize;
+        let (value_text, value_cursor) = form.value.visible(value_width);
         let value_line = Line::from(vec![
             Span::styled("VALUE: ", value_style),
-            Span::raw(&form.value),
+            Span::raw(value_text),
         ]);
         f.render_widget(Paragraph::new(value_line), form_chunks[1]);
 
         // Help
         let help = Line::from(vec![
-            Span::styled("Tab", Style::default().fg(Color::Green)),
+            Span::styled("Tab", Style::default().fg(theme.success)),
             Span::raw(": Switch | "),
-            Span::styled("Enter", Style::default().fg(Color::Green)),
+            Span::styled("Enter", Style::default().fg(theme.success)),
             Span::raw(": Add | "),
-            Span::styled("Esc", Style::default().fg(Color::Red)),
+            Span::styled("Esc", Style::default().fg(theme.error)),
             Span::raw(": Cancel"),
         ]);
         f.render_widget(
@@ -141,14 +159,14 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
         );
 
         // Cursor
-        let (x_offset, text) = if form.current_field == 0 {
-            (5, &form.key) // "KEY: " = 5 chars
+        let (x_offset, cursor) = if form.current_field == 0 {
+            (5, key_cursor)
         } else {
-            (7, &form.value) // "VALUE: " = 7 chars
+            (7, value_cursor)
         };
 
         f.set_cursor_position((
-            form_chunks[form.current_field].x + x_offset + text.len() as u16,
+            form_chunks[form.current_field].x + x_offset + cursor as u16,
             form_chunks[form.current_field].y,
         ));
     } else {
@@ -157,26 +175,26 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
             Span::styled(
                 "a",
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.success)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Add | "),
             Span::styled(
                 "d",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Delete | "),
             Span::styled(
                 "j/k",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Navigate | "),
             Span::styled(
                 "Esc",
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Back"),
@@ -186,4 +204,5 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&EnvVarForm>, area: Rect, b
 
         f.render_widget(help, chunks[2]);
     }
+    rows
 }
@@ -1,10 +1,11 @@
 use crate::app::{App, NoteForm};
+use crate::ui::{Clickable, markdown};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
 
 fn category_icon(cat: &crate::models::NoteCategory) -> &'static str {
@@ -35,18 +36,26 @@ fn category_name(cat: &crate::models::NoteCategory) -> &'static str {
     }
 }
 
-pub fn render(f: &mut Frame, app: &App, form: Option<&NoteForm>, area: Rect, box_id: i32) {
+pub fn render(
+    f: &mut Frame,
+    app: &App,
+    form: Option<&NoteForm>,
+    area: Rect,
+    box_id: i32,
+) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
     let ctf_box = match app.boxes.iter().find(|b| b.id == box_id) {
         Some(b) => b,
-        None => return,
+        None => return Vec::new(),
     };
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Header
-            Constraint::Min(5),    // Notes list
-            Constraint::Length(7), // Add form or instructions
+            Constraint::Length(3),      // Header
+            Constraint::Min(5),         // Notes list
+            Constraint::Percentage(40), // Selected note preview
+            Constraint::Length(6),      // Category picker or instructions
         ])
         .split(area);
 
@@ -58,7 +67,7 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&NoteForm>, area: Rect, box
     ))
     .style(
         Style::default()
-            .fg(Color::Cyan)
+            .fg(theme.accent)
             .add_modifier(Modifier::BOLD),
     )
     .block(Block::default().borders(Borders::ALL));
@@ -75,29 +84,58 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&NoteForm>, area: Rect, box
             let cat_name = category_name(&note.category);
 
             let style = if is_selected {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
+                Style::default().bg(theme.selection).fg(theme.text)
             } else {
                 Style::default()
             };
 
             let prefix = if is_selected { "▶ " } else { "  " };
-            let time = note.created_date.format("%m/%d %H:%M");
+            let time = note.created_date.with_timezone(&chrono::Local).format("%m/%d %H:%M");
 
-            ListItem::new(Line::from(vec![
+            let mut spans = vec![
                 Span::styled(prefix, style),
                 Span::styled(
                     format!("{} {}", icon, cat_name),
-                    style.fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    style.fg(theme.highlight).add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(format!(" [{}] ", time), style.fg(Color::DarkGray)),
-                Span::styled(&note.content, style.fg(Color::White)),
-            ]))
+                Span::styled(format!(" [{}] ", time), style.fg(theme.muted)),
+            ];
+            if !note.images.is_empty() {
+                spans.push(Span::styled("🖼️ ", style));
+            }
+            spans.extend(
+                markdown::summary(&note.content, theme)
+                    .into_iter()
+                    .map(|span| {
+                        let span_style = style.patch(span.style);
+                        span.style(span_style)
+                    }),
+            );
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
     let notes_list =
         List::new(note_items).block(Block::default().borders(Borders::ALL).title("📋 Notes"));
     f.render_widget(notes_list, chunks[1]);
+    // Rows are clickable unless the category picker is open
+    let rows = match form {
+        Some(_) => Vec::new(),
+        None => crate::ui::list_rows(chunks[1], 0, ctf_box.notes.len()),
+    };
+
+    // Full markdown of the selected note
+    let preview_lines = match app.selected_note.and_then(|i| ctf_box.notes.get(i)) {
+        Some(note) => markdown::render(&note.content, theme),
+        None => vec![Line::from(Span::styled(
+            "Select a note to preview it",
+            Style::default().fg(theme.muted),
+        ))],
+    };
+    let preview = Paragraph::new(preview_lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("👁️  Preview"));
+    f.render_widget(preview, chunks[2]);
 
     // Form or instructions
     if let Some(form) = form {
@@ -109,89 +147,87 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&NoteForm>, area: Rect, box
             category_name(current_cat)
         );
 
+        let title = if form.editing.is_some() {
+            "✏️  Edit Note"
+        } else {
+            "➕ Add Note"
+        };
         let form_block = Block::default()
             .borders(Borders::ALL)
-            .title("➕ Add Note")
-            .border_style(Style::default().fg(Color::Green));
+            .title(title)
+            .border_style(Style::default().fg(theme.success));
 
-        let form_inner = form_block.inner(chunks[2]);
-        f.render_widget(form_block, chunks[2]);
+        let form_inner = form_block.inner(chunks[3]);
+        f.render_widget(form_block, chunks[3]);
 
         let form_chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
             .constraints([
                 Constraint::Length(1), // Category
-                Constraint::Length(1), // Content
                 Constraint::Length(1), // Help
             ])
             .split(form_inner);
 
         // Category selector
         let cat_line = Line::from(vec![
-            Span::styled("CATEGORY: ", Style::default().fg(Color::Cyan)),
-            Span::styled("◀ ", Style::default().fg(Color::DarkGray)),
+            Span::styled("CATEGORY: ", Style::default().fg(theme.accent)),
+            Span::styled("◀ ", Style::default().fg(theme.muted)),
             Span::styled(
                 &cat_display,
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" ▶", Style::default().fg(Color::DarkGray)),
+            Span::styled(" ▶", Style::default().fg(theme.muted)),
         ]);
         f.render_widget(Paragraph::new(cat_line), form_chunks[0]);
 
-        // Content field
-        let content_line = Line::from(vec![
-            Span::styled("CONTENT: ", Style::default().fg(Color::Cyan)),
-            Span::raw(&form.content),
-        ]);
-        f.render_widget(Paragraph::new(content_line), form_chunks[1]);
-
         // Help
         let help = Line::from(vec![
-            Span::styled("←/→", Style::default().fg(Color::Green)),
+            Span::styled("←/→", Style::default().fg(theme.success)),
             Span::raw(": Category | "),
-            Span::styled("Enter", Style::default().fg(Color::Green)),
-            Span::raw(": Add | "),
-            Span::styled("Esc", Style::default().fg(Color::Red)),
+            Span::styled("Enter", Style::default().fg(theme.success)),
+            Span::raw(": Open $EDITOR | "),
+            Span::styled("Esc", Style::default().fg(theme.error)),
             Span::raw(": Cancel"),
         ]);
         f.render_widget(
             Paragraph::new(help).alignment(Alignment::Center),
-            form_chunks[2],
+            form_chunks[1],
         );
-
-        // Cursor position on content field
-        f.set_cursor_position((
-            form_chunks[1].x + 9 + form.content.len() as u16, // "CONTENT: " = 9 chars
-            form_chunks[1].y,
-        ));
     } else {
         let help = Paragraph::new(Line::from(vec![
             Span::styled(
                 "a",
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.success)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Add | "),
+            Span::styled(
+                "e",
+                Style::default()
+                    .fg(theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Edit | "),
             Span::styled(
                 "d",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Delete | "),
             Span::styled(
                 "j/k",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Navigate | "),
             Span::styled(
                 "Esc",
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Back"),
@@ -199,6 +235,7 @@ pub fn render(f: &mut Frame, app: &App, form: Option<&NoteForm>, area: Rect, box
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL).title("Commands"));
 
-        f.render_widget(help, chunks[2]);
+        f.render_widget(help, chunks[3]);
     }
+    rows
 }
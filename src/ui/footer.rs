@@ -1,4 +1,7 @@
-use crate::app::{AppView, StatusKind};
+use crate::app::{App, AppView, StatusKind};
+use crate::keymap::{Action, KeyContext};
+use crate::ui::Clickable;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -7,71 +10,186 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
 };
 
-/// Render a footer with keyboard shortcuts and optional status message
-pub fn render_footer(
-    f: &mut Frame,
-    view: &AppView,
-    status: Option<&(String, StatusKind, std::time::Instant)>,
-    area: Rect,
-) {
-    let shortcuts = match view {
+/// Keys bound to the actions, as shown in a hint: the first key of each
+/// action ("j/k"), or every key of a single action ("n/Esc")
+fn bound_keys(app: &App, context: KeyContext, actions: &[Action]) -> String {
+    let mut keys: Vec<String> = if let [action] = actions {
+        app.keymap.keys_for(context, *action)
+    } else {
+        actions
+            .iter()
+            .filter_map(|a| app.keymap.keys_for(context, *a).into_iter().next())
+            .collect()
+    };
+    // "y/Y" reads the same as "y"
+    keys.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    keys.join("/")
+}
+
+/// Render a footer with keyboard shortcuts and optional status message.
+/// Returns where each shortcut was drawn, for mouse clicks.
+pub fn render_footer(f: &mut Frame, app: &App, area: Rect) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
+    let status = app.status_message.as_ref();
+
+    // Views driven by the keymap show the keys actually bound
+    let hints: Vec<(&[Action], &str, Color)> = match app.view {
         AppView::List => vec![
-            ("j/k/↑/↓", "Navigate", Color::Green),
-            ("Enter", "Details", Color::Cyan),
-            ("a", "Add Box", Color::Yellow),
-            ("d", "Delete", Color::Red),
-            ("l", "Launch Shell", Color::Magenta),
-            ("q", "Quit", Color::Red),
+            (&[Action::Down, Action::Up], "Navigate", theme.success),
+            (&[Action::Open], "Details", theme.accent),
+            (&[Action::AddBox], "Add Box", theme.highlight),
+            (&[Action::DeleteBox], "Delete", theme.error),
+            (&[Action::Shell], "Launch Shell", theme.special),
+            (&[Action::Help], "Help", theme.accent),
+            (&[Action::Quit], "Quit", theme.error),
         ],
-        AppView::Details(_) => vec![
-            ("e", "Edit Vars", Color::Yellow),
-            ("n", "Edit Notes", Color::Yellow),
-            ("w", "Write-up", Color::Green),
-            ("l", "Shell", Color::Magenta),
-            ("Esc", "Back", Color::Cyan),
+        AppView::Dashboard => vec![
+            (&[Action::FocusNext], "Focus", theme.success),
+            (&[Action::GrowPane, Action::ShrinkPane], "Resize", theme.success),
+            (&[Action::Open], "Details", theme.accent),
+            (&[Action::AddFlag], "Flag", theme.highlight),
+            (&[Action::Shell], "Shell", theme.special),
+            (&[Action::Back], "List", theme.accent),
         ],
-        AppView::AddBox => vec![
-            ("Tab", "Next Field", Color::Green),
-            ("Shift+Tab", "Prev Field", Color::Green),
-            ("Enter", "Submit", Color::Cyan),
-            ("Esc", "Cancel", Color::Red),
+        AppView::Checklists(_, Some(_)) => vec![
+            (&[Action::Toggle], "Add", theme.highlight),
+            (&[Action::Down, Action::Up], "Navigate", theme.success),
+            (&[Action::Back], "Cancel", theme.accent),
         ],
-        AppView::DeleteBox(_) => vec![
-            ("y", "Confirm", Color::Red),
-            ("n/Esc", "Cancel", Color::Green),
+        AppView::Checklists(_, None) => vec![
+            (&[Action::Toggle], "Tick", theme.success),
+            (&[Action::Add], "Add Checklist", theme.highlight),
+            (&[Action::Delete], "Remove", theme.error),
+            (&[Action::Down, Action::Up], "Navigate", theme.success),
+            (&[Action::Back], "Back", theme.accent),
         ],
-        AppView::EditEnvVars(_) => vec![
-            ("a", "Add", Color::Yellow),
-            ("d", "Delete", Color::Red),
-            ("j/k", "Navigate", Color::Green),
-            ("Esc", "Back", Color::Cyan),
+        AppView::Suggestions(_, None) => vec![
+            (&[Action::Open], "Run", theme.special),
+            (&[Action::Down, Action::Up], "Navigate", theme.success),
+            (&[Action::Back], "Back", theme.accent),
         ],
-        AppView::EditNotes(_) => vec![
-            ("a", "Add", Color::Yellow),
-            ("d", "Delete", Color::Red),
-            ("j/k", "Navigate", Color::Green),
-            ("Esc", "Back", Color::Cyan),
+        AppView::Payloads(_) => vec![
+            (&[Action::Copy], "Copy", theme.special),
+            (&[Action::CycleEncoding], "Encoding", theme.highlight),
+            (&[Action::Down, Action::Up], "Navigate", theme.success),
+            (&[Action::Back], "Back", theme.accent),
         ],
-        AppView::WriteupExport(_) => vec![
-            ("Enter", "Export", Color::Green),
-            ("Esc", "Cancel", Color::Red),
+        AppView::Hosts(_, None) => vec![
+            (&[Action::WriteHosts], "Write", theme.special),
+            (&[Action::Edit], "Edit Hostnames", theme.highlight),
+            (&[Action::Back], "Back", theme.accent),
         ],
+        AppView::Stats => vec![(&[Action::Back], "Back", theme.accent)],
+        AppView::Timeline(_) => vec![
+            (&[Action::Down, Action::Up], "Scroll", theme.success),
+            (&[Action::Back], "Back", theme.accent),
+        ],
+        AppView::Details(_) => vec![
+            (&[Action::EditEnvVars], "Edit Vars", theme.highlight),
+            (&[Action::EditNotes], "Edit Notes", theme.highlight),
+            (&[Action::Writeup], "Write-up", theme.success),
+            (&[Action::Loot], "Loot", theme.highlight),
+            (&[Action::AddFlag], "Flag", theme.highlight),
+            (&[Action::Shell], "Shell", theme.special),
+            (&[Action::Back], "Back", theme.accent),
+        ],
+        AppView::DeleteBox(_) | AppView::KillTmux(_) => vec![
+            (&[Action::Confirm], "Confirm", theme.error),
+            (&[Action::Cancel], "Cancel", theme.success),
+        ],
+        AppView::EditEnvVars(..) => vec![
+            (&[Action::Add], "Add", theme.highlight),
+            (&[Action::Delete], "Delete", theme.error),
+            (&[Action::Down, Action::Up], "Navigate", theme.success),
+            (&[Action::Back], "Back", theme.accent),
+        ],
+        AppView::EditNotes(..) => vec![
+            (&[Action::Add], "Add", theme.highlight),
+            (&[Action::Edit], "Edit", theme.success),
+            (&[Action::Delete], "Delete", theme.error),
+            (&[Action::Down, Action::Up], "Navigate", theme.success),
+            (&[Action::Back], "Back", theme.accent),
+        ],
+        AppView::Loot(_) => vec![
+            (&[Action::Open], "Open", theme.success),
+            (&[Action::OpenHex], "Hex", theme.special),
+            (&[Action::Delete], "Delete", theme.error),
+            (&[Action::Down, Action::Up], "Navigate", theme.success),
+            (&[Action::Back], "Back", theme.accent),
+        ],
+        AppView::AddBox(_)
+        | AppView::AddFlag(_)
+        | AppView::WriteupExport(_)
+        | AppView::Suggestions(_, Some(_))
+        | AppView::Hosts(_, Some(_))
+        | AppView::Snippets(..) => Vec::new(),
     };
 
+    let shortcuts: Vec<(String, &str, Color, Clickable)> =
+        match (&app.view, KeyContext::for_view(&app.view)) {
+            (_, Some(context)) => hints
+                .into_iter()
+                .map(|(actions, label, color)| {
+                    (bound_keys(app, context, actions), label, color, Clickable::Action(actions[0]))
+                })
+                .collect(),
+            // Text input views have fixed keys
+            (AppView::AddBox(_), None) => vec![
+                ("Tab".to_string(), "Next Field", theme.success, key(KeyCode::Tab)),
+                ("Shift+Tab".to_string(), "Prev Field", theme.success, key(KeyCode::BackTab)),
+                ("Enter".to_string(), "Submit", theme.accent, key(KeyCode::Enter)),
+                ("Esc".to_string(), "Cancel", theme.error, key(KeyCode::Esc)),
+            ],
+            (AppView::AddFlag(_), None) => vec![
+                ("Tab".to_string(), "Kind", theme.success, key(KeyCode::Tab)),
+                ("Enter".to_string(), "Save", theme.accent, key(KeyCode::Enter)),
+                ("Esc".to_string(), "Cancel", theme.error, key(KeyCode::Esc)),
+            ],
+            (AppView::Snippets(_, picker), None) if picker.form.is_some() => vec![
+                ("Tab".to_string(), "Next Field", theme.success, key(KeyCode::Tab)),
+                ("Enter".to_string(), "Next / Run", theme.special, key(KeyCode::Enter)),
+                ("Esc".to_string(), "Back", theme.error, key(KeyCode::Esc)),
+            ],
+            (AppView::Snippets(..), None) => vec![
+                ("↑/↓".to_string(), "Navigate", theme.success, key(KeyCode::Down)),
+                ("Enter".to_string(), "Fill & Run", theme.special, key(KeyCode::Enter)),
+                ("Esc".to_string(), "Back", theme.error, key(KeyCode::Esc)),
+            ],
+            (AppView::Suggestions(_, Some(_)), None) => vec![
+                ("Enter".to_string(), "Run", theme.special, key(KeyCode::Enter)),
+                ("Esc".to_string(), "Cancel", theme.error, key(KeyCode::Esc)),
+            ],
+            (AppView::Hosts(_, Some(_)), None) => vec![
+                ("Enter".to_string(), "Save", theme.accent, key(KeyCode::Enter)),
+                ("Esc".to_string(), "Cancel", theme.error, key(KeyCode::Esc)),
+            ],
+            _ => vec![
+                ("Enter".to_string(), "Export", theme.success, key(KeyCode::Enter)),
+                ("Tab".to_string(), "Complete", theme.success, key(KeyCode::Tab)),
+                (
+                    "Ctrl+R".to_string(),
+                    "Redaction",
+                    theme.special,
+                    Clickable::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+                ),
+                ("Esc".to_string(), "Cancel", theme.error, key(KeyCode::Esc)),
+            ],
+        };
+
     // If there's a status message, split footer into 2 lines
-    if let Some((msg, kind, _)) = status {
+    let shortcuts_row = if let Some((msg, kind, _)) = status {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(1), // Status message
+                Constraint::Length(2), // Status message, below its border
                 Constraint::Length(1), // Shortcuts
             ])
             .split(area);
 
         let (icon, color) = match kind {
-            StatusKind::Success => ("✔ ", Color::Green),
-            StatusKind::Error => ("✗ ", Color::Red),
-            StatusKind::Info => ("ℹ ", Color::Cyan),
+            StatusKind::Success => ("✔ ", theme.success),
+            StatusKind::Error => ("✗ ", theme.error),
+            StatusKind::Info => ("ℹ ", theme.accent),
         };
 
         let status_line = Paragraph::new(Line::from(vec![
@@ -85,41 +203,76 @@ pub fn render_footer(
         );
         f.render_widget(status_line, chunks[0]);
 
-        let mut spans = Vec::new();
-        for (i, (key, action, color)) in shortcuts.iter().enumerate() {
-            if i > 0 {
-                spans.push(Span::raw(" │ "));
-            }
-            spans.push(Span::styled(
-                *key,
-                Style::default().fg(*color).add_modifier(Modifier::BOLD),
-            ));
-            spans.push(Span::raw(format!(": {}", action)));
-        }
-        let shortcut_line = Paragraph::new(Line::from(spans))
-            .style(Style::default().fg(Color::White));
+        let shortcut_line = Paragraph::new(shortcut_line(&shortcuts))
+            .style(Style::default().fg(theme.text));
         f.render_widget(shortcut_line, chunks[1]);
+        chunks[1]
     } else {
-        let mut spans = Vec::new();
-        for (i, (key, action, color)) in shortcuts.iter().enumerate() {
-            if i > 0 {
-                spans.push(Span::raw(" │ "));
-            }
-            spans.push(Span::styled(
-                *key,
-                Style::default().fg(*color).add_modifier(Modifier::BOLD),
-            ));
-            spans.push(Span::raw(format!(": {}", action)));
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(theme.muted));
+        let row = block.inner(area);
+        let footer = Paragraph::new(shortcut_line(&shortcuts))
+            .block(block)
+            .style(Style::default().fg(theme.text));
+
+        f.render_widget(footer, area);
+        row
+    };
+
+    // Running listeners, right-aligned: waiting (…) or connected (⇄)
+    let listeners = app
+        .listeners
+        .iter()
+        .map(|l| match &l.peer {
+            Some(peer) => format!("📞 {} ⇄ {}", l.port, peer),
+            None => format!("📞 {} …", l.port),
+        })
+        .collect::<Vec<_>>()
+        .join("  ");
+    if !listeners.is_empty() {
+        let indicator = Span::styled(
+            listeners,
+            Style::default().fg(theme.special).add_modifier(Modifier::BOLD),
+        );
+        let width = (indicator.width() as u16).min(shortcuts_row.width);
+        let cells = Rect::new(shortcuts_row.right() - width, shortcuts_row.y, width, 1);
+        f.render_widget(Paragraph::new(Line::from(indicator)), cells);
+    }
+
+    // Each shortcut is clickable, from its key to the end of its label
+    let mut x = shortcuts_row.x;
+    let mut clickables = Vec::new();
+    for (i, (keys, label, _, clickable)) in shortcuts.iter().enumerate() {
+        if i > 0 {
+            x += Span::raw(SEPARATOR).width() as u16;
         }
+        let width = Span::raw(format!("{}: {}", keys, label)).width() as u16;
+        let cells = Rect::new(x, shortcuts_row.y, width, 1).intersection(shortcuts_row);
+        clickables.push((cells, *clickable));
+        x += width;
+    }
+    clickables
+}
 
-        let footer = Paragraph::new(Line::from(spans))
-            .block(
-                Block::default()
-                    .borders(Borders::TOP)
-                    .border_style(Style::default().fg(Color::DarkGray)),
-            )
-            .style(Style::default().fg(Color::White));
+const SEPARATOR: &str = " │ ";
 
-        f.render_widget(footer, area);
+fn key(code: KeyCode) -> Clickable {
+    Clickable::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+/// "key: Label │ key: Label ..."
+fn shortcut_line<'a>(shortcuts: &'a [(String, &str, Color, Clickable)]) -> Line<'a> {
+    let mut spans = Vec::new();
+    for (i, (keys, label, color, _)) in shortcuts.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(SEPARATOR));
+        }
+        spans.push(Span::styled(
+            keys.as_str(),
+            Style::default().fg(*color).add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(format!(": {}", label)));
     }
+    Line::from(spans)
 }
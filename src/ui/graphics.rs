@@ -0,0 +1,172 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ratatui::layout::Rect;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Terminal graphics protocol used to show image thumbnails
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+    None,
+}
+
+impl GraphicsProtocol {
+    pub fn is_supported(self) -> bool {
+        self != GraphicsProtocol::None
+    }
+}
+
+/// Guess the graphics protocol from the environment.
+/// `CTF_BRAIN_GRAPHICS=kitty|iterm2|sixel|none` overrides the detection.
+pub fn detect() -> GraphicsProtocol {
+    if let Ok(forced) = std::env::var("CTF_BRAIN_GRAPHICS") {
+        return match forced.to_lowercase().as_str() {
+            "kitty" => GraphicsProtocol::Kitty,
+            "iterm2" => GraphicsProtocol::Iterm2,
+            "sixel" => GraphicsProtocol::Sixel,
+            _ => GraphicsProtocol::None,
+        };
+    }
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+
+    if std::env::var("KITTY_WINDOW_ID").is_ok()
+        || term.contains("kitty")
+        || term.contains("ghostty")
+        || term_program == "WezTerm"
+        || term_program == "ghostty"
+    {
+        GraphicsProtocol::Kitty
+    } else if term_program == "iTerm.app" {
+        GraphicsProtocol::Iterm2
+    } else if (term.contains("sixel") || term == "foot" || term.starts_with("mlterm"))
+        && Command::new("img2sixel").arg("--version").output().is_ok()
+    {
+        GraphicsProtocol::Sixel
+    } else {
+        GraphicsProtocol::None
+    }
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Width and height of a PNG, read from its IHDR chunk
+fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 24 || !bytes.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Largest cell box keeping the image aspect ratio (cells are ~twice as tall as wide)
+fn fit_cells(bytes: &[u8], area: Rect) -> (u16, u16) {
+    let Some((width, height)) = png_dimensions(bytes) else {
+        return (area.width, area.height);
+    };
+    if width == 0 || height == 0 {
+        return (area.width, area.height);
+    }
+
+    let ratio = width as f64 / (height as f64 * 2.0);
+    let cols = area.width as f64;
+    let rows = cols / ratio;
+    if rows <= area.height as f64 {
+        (area.width, rows.max(1.0) as u16)
+    } else {
+        let cols = (area.height as f64 * ratio).max(1.0) as u16;
+        (cols.min(area.width), area.height)
+    }
+}
+
+/// The image converted to PNG by ImageMagick (`magick`, `convert` before v7)
+fn to_png(path: &Path) -> Option<Vec<u8>> {
+    ["magick", "convert"].iter().find_map(|program| {
+        let output = Command::new(program)
+            .arg(path)
+            .arg("png:-")
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        (output.status.success() && output.stdout.starts_with(PNG_SIGNATURE)).then_some(output.stdout)
+    })
+}
+
+/// Draw an image file inside `area`, using the terminal's graphics protocol
+pub fn draw_image(
+    out: &mut impl Write,
+    protocol: GraphicsProtocol,
+    path: &Path,
+    area: Rect,
+) -> io::Result<()> {
+    if area.width == 0 || area.height == 0 {
+        return Ok(());
+    }
+    let mut bytes = std::fs::read(path)?;
+    // kitty's f=100 only takes PNG: other formats are converted, or not drawn
+    if protocol == GraphicsProtocol::Kitty && !bytes.starts_with(PNG_SIGNATURE) {
+        match to_png(path) {
+            Some(png) => bytes = png,
+            None => return Ok(()),
+        }
+    }
+    let (cols, rows) = fit_cells(&bytes, area);
+
+    // Move the cursor to the top-left cell of the area (1-based)
+    write!(out, "\x1b7\x1b[{};{}H", area.y + 1, area.x + 1)?;
+
+    match protocol {
+        GraphicsProtocol::Kitty => {
+            // Direct PNG transmission, split in 4096-byte chunks
+            let encoded = STANDARD.encode(&bytes);
+            let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let more = if i + 1 < chunks.len() { 1 } else { 0 };
+                if i == 0 {
+                    write!(out, "\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};", cols, rows, more)?;
+                } else {
+                    write!(out, "\x1b_Gm={};", more)?;
+                }
+                out.write_all(chunk)?;
+                write!(out, "\x1b\\")?;
+            }
+        }
+        GraphicsProtocol::Iterm2 => {
+            write!(
+                out,
+                "\x1b]1337;File=inline=1;width={};height={};preserveAspectRatio=1:{}\x07",
+                cols,
+                rows,
+                STANDARD.encode(&bytes)
+            )?;
+        }
+        GraphicsProtocol::Sixel => {
+            // Assume ~10x20 pixel cells, img2sixel does the decoding
+            let output = Command::new("img2sixel")
+                .arg("-w")
+                .arg((cols as u32 * 10).to_string())
+                .arg(path)
+                .output()?;
+            out.write_all(&output.stdout)?;
+        }
+        GraphicsProtocol::None => {}
+    }
+
+    write!(out, "\x1b8")?;
+    out.flush()
+}
+
+/// Remove images drawn with a protocol that keeps them above the text
+pub fn clear_images(out: &mut impl Write, protocol: GraphicsProtocol) -> io::Result<()> {
+    if protocol == GraphicsProtocol::Kitty {
+        write!(out, "\x1b_Ga=d,d=A,q=2\x1b\\")?;
+        out.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use crate::app::App;
+use crate::keymap::{self, KeyContext};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+fn context_title(context: KeyContext) -> &'static str {
+    match context {
+        KeyContext::Global => "Everywhere",
+        KeyContext::List => "Box list",
+        KeyContext::Dashboard => "Dashboard",
+        KeyContext::Details => "Details",
+        KeyContext::EnvVars => "Environment variables",
+        KeyContext::Notes => "Notes",
+        KeyContext::Loot => "Loot",
+        KeyContext::Checklists => "Checklists",
+        KeyContext::Suggestions => "Suggestions",
+        KeyContext::Payloads => "Payloads",
+        KeyContext::Hosts => "Hosts file",
+        KeyContext::Confirm => "Confirmation",
+    }
+}
+
+/// Render every binding of the keymap, the current view's first
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let current = KeyContext::for_view(&app.view).unwrap_or(KeyContext::Global);
+
+    let mut contexts = vec![current];
+    contexts.extend(KeyContext::ALL.into_iter().filter(|c| *c != current));
+
+    let mut lines: Vec<Line> = Vec::new();
+    for context in contexts {
+        let actions: Vec<_> = keymap::actions_for(context)
+            .into_iter()
+            .filter(|(ctx, _)| *ctx == context)
+            .collect();
+        if actions.is_empty() {
+            continue;
+        }
+
+        let title_style = if context == current {
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+        };
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(context_title(context), title_style)));
+
+        for (ctx, action) in actions {
+            let keys = app.keymap.keys_for(ctx, action);
+            let keys = if keys.is_empty() {
+                "(palette)".to_string()
+            } else {
+                keys.join(", ")
+            };
+            // The config name is what goes in `[keys.<context>]`
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<16}", keys), Style::default().fg(theme.success)),
+                Span::styled(action.description(), Style::default().fg(theme.text)),
+                Span::styled(format!("  {}", action.name()), Style::default().fg(theme.muted)),
+            ]));
+        }
+    }
+
+    let width = std::cmp::min(64, area.width.saturating_sub(4));
+    let height = std::cmp::min(lines.len() as u16 + 2, area.height.saturating_sub(2));
+    let modal_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, modal_area);
+
+    let help = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" ❓ Key bindings (any key to close) ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent)),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(help, modal_area);
+}
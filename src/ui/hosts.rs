@@ -0,0 +1,112 @@
+use crate::app::App;
+use crate::ui::input::TextInput;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+pub fn render(f: &mut Frame, app: &App, input: Option<&TextInput>, area: Rect, box_id: Option<i32>) {
+    let theme = app.theme;
+    let ctf_box = box_id.and_then(|id| app.boxes.iter().find(|b| b.id == id));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // Box hostnames and file
+            Constraint::Min(5),    // Block preview
+        ])
+        .split(area);
+
+    let hostnames = match ctf_box {
+        Some(ctf_box) if ctf_box.hostnames.is_empty() => {
+            Span::styled(format!("{}  no hostnames", ctf_box.title), Style::default().fg(theme.muted))
+        }
+        Some(ctf_box) => Span::styled(
+            format!("{}  {}", ctf_box.title, ctf_box.hostnames.join(" ")),
+            Style::default().fg(theme.text),
+        ),
+        None => Span::styled("Box deleted", Style::default().fg(theme.muted)),
+    };
+    let header = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled(
+                "🌍 Hosts - ",
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+            ),
+            hostnames,
+        ]),
+        Line::from(Span::styled(
+            app.config.hosts_path().display().to_string(),
+            Style::default().fg(theme.muted),
+        )),
+    ])
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, chunks[0]);
+
+    let preview = app.hosts_preview();
+    let changed = preview.iter().any(|(mark, _)| *mark != ' ');
+    let lines: Vec<Line> = match &app.hosts_content {
+        Err(e) => vec![Line::from(Span::styled(e.clone(), Style::default().fg(theme.error)))],
+        Ok(_) if preview.is_empty() => vec![Line::from(Span::styled(
+            "No box has hostnames, the ctf-brain block is absent",
+            Style::default().fg(theme.muted),
+        ))],
+        Ok(_) => preview
+            .iter()
+            .map(|(mark, line)| {
+                let color = match mark {
+                    '+' => theme.success,
+                    '-' => theme.error,
+                    _ => theme.text,
+                };
+                Line::from(Span::styled(
+                    format!("{} {}", mark, line.replace('\t', "  ")),
+                    Style::default().fg(color),
+                ))
+            })
+            .collect(),
+    };
+    let title = if changed {
+        "ctf-brain block (changes to write)"
+    } else {
+        "ctf-brain block (up to date)"
+    };
+    let block = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(if changed { theme.highlight } else { theme.accent })),
+    );
+    f.render_widget(block, chunks[1]);
+
+    if let Some(input) = input {
+        render_input(f, app, input, area);
+    }
+}
+
+/// Popup with the hostnames of the box, comma separated
+fn render_input(f: &mut Frame, app: &App, input: &TextInput, area: Rect) {
+    let theme = app.theme;
+    let modal_width = area.width.saturating_sub(8).min(80);
+    let modal_area = Rect {
+        x: area.x + (area.width.saturating_sub(modal_width)) / 2,
+        y: area.y + (area.height.saturating_sub(3)) / 2,
+        width: modal_width,
+        height: 3.min(area.height),
+    };
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Hostnames (comma separated)")
+        .border_style(Style::default().fg(theme.highlight));
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let (text, cursor) = input.visible(inner.width as usize);
+    f.render_widget(Paragraph::new(text).style(Style::default().fg(theme.text)), inner);
+    f.set_cursor_position((inner.x + cursor as u16, inner.y));
+}
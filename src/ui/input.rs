@@ -0,0 +1,286 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::{Path, PathBuf};
+
+/// Single-line text input with a cursor, word editing and history
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextInput {
+    value: String,
+    // Cursor position, in chars
+    cursor: usize,
+    history: Vec<String>,
+    // Position while browsing history with Up/Down
+    history_index: Option<usize>,
+    // What was typed before browsing history
+    draft: String,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_value(value: impl Into<String>) -> Self {
+        let mut input = Self::new();
+        input.set_value(value);
+        input
+    }
+
+    /// Past values, oldest first, recalled with Up/Down
+    pub fn with_history(mut self, history: Vec<String>) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Replace the content and move the cursor to the end
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.chars().count();
+    }
+
+    /// Insert text at the cursor (typing or bracketed paste).
+    /// Line breaks are dropped since the input is single-line.
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars().filter(|c| *c != '\n' && *c != '\r') {
+            let at = self.byte_index(self.cursor);
+            self.value.insert(at, c);
+            self.cursor += 1;
+        }
+    }
+
+    /// Handle an editing key, returns false if the key is not an editing key
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.len(),
+            KeyCode::Char('w') if ctrl => self.delete_word_before(),
+            KeyCode::Char('u') if ctrl => {
+                let at = self.byte_index(self.cursor);
+                self.value.replace_range(..at, "");
+                self.cursor = 0;
+            }
+            KeyCode::Char('k') if ctrl => {
+                let at = self.byte_index(self.cursor);
+                self.value.truncate(at);
+            }
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => self.insert_str(&c.to_string()),
+            KeyCode::Backspace if ctrl || alt => self.delete_word_before(),
+            KeyCode::Backspace => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                    let at = self.byte_index(self.cursor);
+                    self.value.remove(at);
+                }
+            }
+            KeyCode::Delete => {
+                if self.cursor < self.len() {
+                    let at = self.byte_index(self.cursor);
+                    self.value.remove(at);
+                }
+            }
+            KeyCode::Left if ctrl || alt => self.cursor = self.word_start_before(),
+            KeyCode::Right if ctrl || alt => self.cursor = self.word_end_after(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            KeyCode::Up => self.history_previous(),
+            KeyCode::Down => self.history_next(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Text to display in `width` columns, scrolled so the cursor stays
+    /// visible, and the cursor column within it
+    pub fn visible(&self, width: usize) -> (String, usize) {
+        if width == 0 {
+            return (String::new(), 0);
+        }
+        let start = self.cursor.saturating_sub(width - 1);
+        let text = self.value.chars().skip(start).take(width).collect();
+        (text, self.cursor - start)
+    }
+
+    /// Complete the value as a filesystem path (Tab in path fields).
+    /// Returns the candidates when the completion is ambiguous.
+    pub fn complete_path(&mut self) -> Vec<String> {
+        let typed = self.value.clone();
+        let expanded = expand_home(&typed);
+        let (dir, prefix) = match expanded.rfind('/') {
+            Some(i) => (expanded[..=i].to_string(), expanded[i + 1..].to_string()),
+            None => (String::new(), expanded.clone()),
+        };
+        let read_from = if dir.is_empty() { "." } else { dir.as_str() };
+
+        let mut matches: Vec<(String, bool)> = match std::fs::read_dir(read_from) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    let is_dir = e.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                    name.starts_with(&prefix).then_some((name, is_dir))
+                })
+                .filter(|(name, _)| !name.starts_with('.') || prefix.starts_with('.'))
+                .collect(),
+            Err(_) => return Vec::new(),
+        };
+        matches.sort();
+
+        // Keep what the user typed before the completed part (e.g. "~/"),
+        // the expanded directory when the prefix came from the expansion ("~")
+        let kept = typed.strip_suffix(prefix.as_str()).unwrap_or(&dir);
+        match matches.as_slice() {
+            [] => Vec::new(),
+            [(name, is_dir)] => {
+                let suffix = if *is_dir { "/" } else { "" };
+                self.set_value(format!("{}{}{}", kept, name, suffix));
+                Vec::new()
+            }
+            _ => {
+                let common = matches.iter().skip(1).fold(matches[0].0.clone(), |acc, (name, _)| {
+                    acc.chars()
+                        .zip(name.chars())
+                        .take_while(|(a, b)| a == b)
+                        .map(|(a, _)| a)
+                        .collect()
+                });
+                self.set_value(format!("{}{}", kept, common));
+                matches
+                    .into_iter()
+                    .map(|(name, is_dir)| if is_dir { format!("{}/", name) } else { name })
+                    .collect()
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_index)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+
+    fn word_start_before(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = self.cursor;
+        while i > 0 && !is_word_char(chars[i - 1]) {
+            i -= 1;
+        }
+        while i > 0 && is_word_char(chars[i - 1]) {
+            i -= 1;
+        }
+        i
+    }
+
+    fn word_end_after(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = self.cursor;
+        while i < chars.len() && !is_word_char(chars[i]) {
+            i += 1;
+        }
+        while i < chars.len() && is_word_char(chars[i]) {
+            i += 1;
+        }
+        i
+    }
+
+    fn delete_word_before(&mut self) {
+        let start = self.word_start_before();
+        let from = self.byte_index(start);
+        let to = self.byte_index(self.cursor);
+        self.value.replace_range(from..to, "");
+        self.cursor = start;
+    }
+
+    fn history_previous(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_index {
+            None => {
+                self.draft = self.value.clone();
+                self.history.len() - 1
+            }
+            Some(0) => 0,
+            Some(i) => i - 1,
+        };
+        self.history_index = Some(index);
+        self.set_value(self.history[index].clone());
+    }
+
+    fn history_next(&mut self) {
+        match self.history_index {
+            None => {}
+            Some(i) if i + 1 < self.history.len() => {
+                self.history_index = Some(i + 1);
+                self.set_value(self.history[i + 1].clone());
+            }
+            Some(_) => {
+                self.history_index = None;
+                let draft = std::mem::take(&mut self.draft);
+                self.set_value(draft);
+            }
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_home(path: &str) -> String {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{}", home.display(), rest)
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Same as `expand_home`, as a path
+pub fn expand_home_path(path: &str) -> PathBuf {
+    Path::new(&expand_home(path)).to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_path_of_a_lone_tilde_does_not_panic() {
+        let mut input = TextInput::with_value("~");
+        input.complete_path();
+        assert!(!input.value().is_empty());
+    }
+
+    #[test]
+    fn complete_path_extends_to_the_common_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("alpha.md"), "").unwrap();
+        std::fs::create_dir(dir.path().join("alpine")).unwrap();
+        let base = dir.path().display().to_string();
+
+        let mut input = TextInput::with_value(format!("{}/a", base));
+        let candidates = input.complete_path();
+        assert_eq!(input.value(), format!("{}/alp", base));
+        assert_eq!(candidates, vec!["alpha.md".to_string(), "alpine/".to_string()]);
+
+        let mut input = TextInput::with_value(format!("{}/alpi", base));
+        assert!(input.complete_path().is_empty());
+        assert_eq!(input.value(), format!("{}/alpine/", base));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::app::App;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
+    let theme = app.theme;
+    let session = app
+        .tmux_session(box_id)
+        .unwrap_or_else(|| "the session".to_string());
+
+    let modal_width = std::cmp::min(56, area.width.saturating_sub(4));
+    let modal_height = 8;
+    let modal_area = Rect {
+        x: area.x + (area.width.saturating_sub(modal_width)) / 2,
+        y: area.y + (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height.min(area.height),
+    };
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title("⚠️  End tmux Session")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.error));
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2), // Message
+            Constraint::Length(2), // Confirmation
+        ])
+        .split(inner);
+
+    let message = Paragraph::new(vec![
+        Line::from(vec![
+            Span::raw("End "),
+            Span::styled(session, Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("?"),
+        ]),
+        Line::from("Its shells, listener and file server are stopped."),
+    ])
+    .alignment(Alignment::Center)
+    .style(Style::default().fg(theme.text));
+    f.render_widget(message, chunks[0]);
+
+    let controls = Paragraph::new(Line::from(vec![
+        Span::styled("y", Style::default().fg(theme.error).add_modifier(Modifier::BOLD)),
+        Span::raw(": Confirm | "),
+        Span::styled("n / Esc", Style::default().fg(theme.success).add_modifier(Modifier::BOLD)),
+        Span::raw(": Cancel"),
+    ]))
+    .alignment(Alignment::Center);
+    f.render_widget(controls, chunks[1]);
+}
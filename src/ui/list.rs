@@ -1,13 +1,15 @@
 use crate::app::App;
+use crate::ui::Clickable;
 use ratatui::{
     layout::Rect,
-    style::{Color, Modifier, Style},
-    text::Line,
+    style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
 
-pub fn render(f: &mut Frame, app: &App, area: Rect) {
+pub fn render(f: &mut Frame, app: &App, area: Rect) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
     // Convert boxes to list items with icons and formatting
     let items: Vec<ListItem> = app
         .boxes
@@ -31,13 +33,28 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
             
             let style = if Some(idx) == app.selected_box_id.map(|id| id as usize) {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             };
             
-            ListItem::new(Line::from(content)).style(style)
+            // Result of the last reachability check
+            let reachability = match app.reachability.get(&ctf_box.id) {
+                Some(probe) => Span::styled(
+                    format!("  ● {}", probe.label()),
+                    Style::default().fg(crate::ui::reachability_color(app, probe)),
+                ),
+                None => Span::raw(""),
+            };
+
+            let tmux = if app.tmux_session(ctf_box.id).is_some() {
+                Span::styled("  ⧉ tmux", Style::default().fg(theme.special))
+            } else {
+                Span::raw("")
+            };
+
+            ListItem::new(Line::from(vec![Span::raw(content), reachability, tmux])).style(style)
         })
         .collect();
 
@@ -47,11 +64,11 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
             Block::default()
                 .borders(Borders::ALL)
                 .title("🧩 CTF Boxes")
-                .border_style(Style::default().fg(Color::Cyan)),
+                .border_style(Style::default().fg(theme.accent)),
         )
         .highlight_style(
             Style::default()
-                .bg(Color::DarkGray)
+                .bg(theme.selection)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("▶ ");
@@ -64,4 +81,5 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 
     // Render the widget
     f.render_stateful_widget(list, area, &mut list_state);
+    crate::ui::list_rows(area, list_state.offset(), app.boxes.len())
 }
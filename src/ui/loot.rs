@@ -0,0 +1,164 @@
+use crate::app::App;
+use crate::ui::Clickable;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+fn human_size(size: u64) -> String {
+    match size {
+        s if s >= 1024 * 1024 => format!("{:.1} MB", s as f64 / (1024.0 * 1024.0)),
+        s if s >= 1024 => format!("{:.1} KB", s as f64 / 1024.0),
+        s => format!("{} B", s),
+    }
+}
+
+pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
+    let ctf_box = match app.boxes.iter().find(|b| b.id == box_id) {
+        Some(b) => b,
+        None => return Vec::new(),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(5),    // Files list
+            Constraint::Length(6), // Selected file details
+            Constraint::Length(3), // Instructions
+        ])
+        .split(area);
+
+    // Header
+    let header = Paragraph::new(format!(
+        "📦 Loot - {} ({} files)",
+        ctf_box.title,
+        ctf_box.attachments.len()
+    ))
+    .style(
+        Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD),
+    )
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, chunks[0]);
+
+    // Files list with selection
+    let items: Vec<ListItem> = ctf_box
+        .attachments
+        .iter()
+        .enumerate()
+        .map(|(i, attachment)| {
+            let is_selected = app.selected_attachment == Some(i);
+
+            let style = if is_selected {
+                Style::default().bg(theme.selection).fg(theme.text)
+            } else {
+                Style::default()
+            };
+
+            let prefix = if is_selected { "▶ " } else { "  " };
+            let icon = if attachment.mime.starts_with("image/") {
+                "🖼️"
+            } else if attachment.is_text() {
+                "📄"
+            } else {
+                "💾"
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(prefix, style),
+                Span::styled(
+                    format!("{} {}", icon, attachment.name),
+                    style.fg(theme.highlight).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  {} ", human_size(attachment.size)),
+                    style.fg(theme.text),
+                ),
+                Span::styled(format!("[{}]", attachment.mime), style.fg(theme.muted)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title("📋 Files"));
+    f.render_widget(list, chunks[1]);
+
+    // Selected file details
+    let details = match app
+        .selected_attachment
+        .and_then(|i| ctf_box.attachments.get(i))
+    {
+        Some(attachment) => vec![
+            Line::from(vec![
+                Span::styled("SHA256: ", Style::default().fg(theme.highlight)),
+                Span::raw(&attachment.sha256),
+            ]),
+            Line::from(vec![
+                Span::styled("Source: ", Style::default().fg(theme.highlight)),
+                Span::raw(&attachment.source),
+            ]),
+            Line::from(vec![
+                Span::styled("Added: ", Style::default().fg(theme.highlight)),
+                Span::raw(attachment.created_date.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()),
+            ]),
+            Line::from(vec![
+                Span::styled("Note: ", Style::default().fg(theme.highlight)),
+                Span::raw(attachment.note.as_deref().unwrap_or("-")),
+            ]),
+        ],
+        None => vec![Line::from(Span::styled(
+            "Use `loot <file>` in the box shell or `ctf-brain loot add <id> <file>`.",
+            Style::default().fg(theme.muted),
+        ))],
+    };
+    let details = Paragraph::new(details)
+        .block(Block::default().borders(Borders::ALL).title("ℹ️  File"));
+    f.render_widget(details, chunks[2]);
+
+    // Instructions
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled(
+            "Enter",
+            Style::default()
+                .fg(theme.success)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(": Open | "),
+        Span::styled(
+            "x",
+            Style::default()
+                .fg(theme.special)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(": Hex view | "),
+        Span::styled(
+            "d",
+            Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(": Delete | "),
+        Span::styled(
+            "j/k",
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(": Navigate | "),
+        Span::styled(
+            "Esc",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(": Back"),
+    ]))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).title("Commands"));
+    f.render_widget(help, chunks[3]);
+
+    crate::ui::list_rows(chunks[1], 0, ctf_box.attachments.len())
+}
@@ -0,0 +1,126 @@
+use crate::ui::theme::Theme;
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Render note markdown as styled lines: headings, lists, quotes,
+/// fenced code blocks and inline `code`, **bold** and *italic*.
+pub fn render(text: &str, theme: Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", raw),
+                Style::default().fg(theme.success),
+            )));
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            lines.push(Line::from(Span::styled(
+                heading.to_string(),
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            )));
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            let indent = " ".repeat(raw.len() - trimmed.len());
+            let mut spans = vec![Span::styled(
+                format!("{}• ", indent),
+                Style::default().fg(theme.highlight),
+            )];
+            spans.extend(inline(item, Style::default(), theme));
+            lines.push(Line::from(spans));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let style = Style::default()
+                .fg(theme.muted)
+                .add_modifier(Modifier::ITALIC);
+            let mut spans = vec![Span::styled("│ ", style)];
+            spans.extend(inline(quote.trim_start(), style, theme));
+            lines.push(Line::from(spans));
+        } else {
+            lines.push(Line::from(inline(raw, Style::default(), theme)));
+        }
+    }
+
+    lines
+}
+
+/// First non-empty line of a note, formatted, for one-line list items
+pub fn summary(text: &str, theme: Theme) -> Vec<Span<'static>> {
+    let first = text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("```"))
+        .unwrap_or("");
+    let first = first.trim_start_matches('#').trim_start();
+    let first = first
+        .strip_prefix("- ")
+        .or_else(|| first.strip_prefix("* "))
+        .unwrap_or(first);
+
+    let mut spans = inline(first, Style::default(), theme);
+    let extra = text.lines().filter(|l| !l.trim().is_empty()).count();
+    if extra > 1 {
+        spans.push(Span::styled(
+            format!("  (+{} lines)", extra - 1),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    spans
+}
+
+/// Split a line on inline markers (`code`, **bold**, *italic*)
+fn inline(text: &str, base: Style, theme: Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let marker = [("`", "`"), ("**", "**"), ("*", "*")]
+            .into_iter()
+            .find(|(open, close)| {
+                rest.starts_with(open) && rest[open.len()..].find(close).is_some_and(|i| i > 0)
+            });
+
+        match marker {
+            Some((open, close)) => {
+                let inner_len = rest[open.len()..].find(close).unwrap_or(0);
+                let inner = &rest[open.len()..open.len() + inner_len];
+                let style = match open {
+                    "`" => base.fg(theme.highlight),
+                    "**" => base.add_modifier(Modifier::BOLD),
+                    _ => base.add_modifier(Modifier::ITALIC),
+                };
+                if !current.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut current), base));
+                }
+                spans.push(Span::styled(inner.to_string(), style));
+                rest = &rest[open.len() + inner_len + close.len()..];
+            }
+            None => {
+                let ch = rest.chars().next().unwrap_or(' ');
+                current.push(ch);
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        spans.push(Span::styled(current, base));
+    }
+    spans
+}
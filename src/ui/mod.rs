@@ -1,8 +1,552 @@
 pub mod add_box;
+pub mod add_flag;
+pub mod checklists;
+pub mod dashboard;
 pub mod delete_box;
 pub mod detail;
 pub mod edit_env_vars;
 pub mod edit_notes;
 pub mod footer;
+pub mod graphics;
+pub mod help;
+pub mod hosts;
+pub mod input;
+pub mod kill_tmux;
 pub mod list;
+pub mod loot;
+pub mod markdown;
+pub mod palette;
+pub mod payloads;
+pub mod snippets;
+pub mod stats;
+pub mod suggestions;
+pub mod theme;
+pub mod timeline;
 pub mod writeup_export;
+
+use crate::app::{App, AppView};
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Color,
+    widgets::{Block, Borders},
+};
+
+/// Something on screen the mouse acts on, recorded while drawing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Clickable {
+    /// Item of the list shown by the view (boxes, variables, notes, files)
+    Row(usize),
+    /// Pane of the details view scrolled with the wheel
+    Pane(DetailPane),
+    /// Footer shortcut of a keymap action
+    Action(Action),
+    /// Footer shortcut of a fixed key (forms)
+    Key(KeyEvent),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetailPane {
+    Notes,
+    Actions,
+}
+
+/// Clickable rows of a bordered list of single-line items scrolled to `offset`
+pub fn list_rows(area: Rect, offset: usize, count: usize) -> Vec<(Rect, Clickable)> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    (offset..count)
+        .zip(inner.rows())
+        .map(|(i, row)| (row, Clickable::Row(i)))
+        .collect()
+}
+
+/// Color of a reachability check: up, box down, or VPN down
+pub fn reachability_color(app: &App, probe: &crate::reachability::Probe) -> Color {
+    if probe.is_up() {
+        app.theme.success
+    } else if probe.vpn_up {
+        app.theme.error
+    } else {
+        app.theme.highlight
+    }
+}
+
+/// Draw the whole frame: the current view, overlays and the footer.
+/// Returns the area of the main view and what can be clicked in the
+/// current view.
+pub fn draw(f: &mut Frame, app: &App) -> (Rect, Vec<(Rect, Clickable)>) {
+    let area = f.area();
+
+    // Footer height: 3 if status message, 2 otherwise
+    let footer_height = if app.status_message.is_some() { 3 } else { 2 };
+
+    // Create layout with footer
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),                // Main content
+            Constraint::Length(footer_height), // Footer
+        ])
+        .split(area);
+
+    // Render main view (popups hide the clickable rows of the list below)
+    let mut clickables = match &app.view {
+        AppView::List => list::render(f, app, main_chunks[0]),
+        AppView::Dashboard => dashboard::render(f, app, main_chunks[0]),
+        AppView::Stats => {
+            stats::render(f, app, main_chunks[0]);
+            Vec::new()
+        }
+        AppView::Details(id) => detail::render(f, app, main_chunks[0], *id),
+        AppView::DeleteBox(id) => {
+            list::render(f, app, main_chunks[0]);
+            delete_box::render(f, app, main_chunks[0], *id);
+            Vec::new()
+        }
+        AppView::KillTmux(id) => {
+            detail::render(f, app, main_chunks[0], *id);
+            kill_tmux::render(f, app, main_chunks[0], *id);
+            Vec::new()
+        }
+        AppView::AddBox(form) => {
+            list::render(f, app, main_chunks[0]);
+            add_box::render(f, app, form, main_chunks[0]);
+            Vec::new()
+        }
+        AppView::EditEnvVars(id, form) => {
+            edit_env_vars::render(f, app, form.as_ref(), main_chunks[0], *id)
+        }
+        AppView::EditNotes(id, form) => {
+            edit_notes::render(f, app, form.as_ref(), main_chunks[0], *id)
+        }
+        AppView::WriteupExport(id) => {
+            writeup_export::render(f, app, main_chunks[0], *id);
+            Vec::new()
+        }
+        AppView::Loot(id) => loot::render(f, app, main_chunks[0], *id),
+        AppView::Checklists(id, picker) => {
+            checklists::render(f, app, picker.as_ref(), main_chunks[0], *id)
+        }
+        AppView::Suggestions(id, input) => {
+            suggestions::render(f, app, input.as_ref(), main_chunks[0], *id)
+        }
+        AppView::Payloads(id) => payloads::render(f, app, main_chunks[0], *id),
+        AppView::Hosts(id, input) => {
+            hosts::render(f, app, input.as_ref(), main_chunks[0], *id);
+            Vec::new()
+        }
+        AppView::Snippets(id, picker) => {
+            snippets::render(f, app, picker, main_chunks[0], *id);
+            Vec::new()
+        }
+        AppView::Timeline(id) => {
+            timeline::render(f, app, main_chunks[0], *id);
+            Vec::new()
+        }
+        AppView::AddFlag(form) => {
+            if form.from_dashboard {
+                dashboard::render(f, app, main_chunks[0]);
+            } else {
+                detail::render(f, app, main_chunks[0], form.box_id);
+            }
+            add_flag::render(f, app, form, main_chunks[0]);
+            Vec::new()
+        }
+    };
+
+    // Overlays
+    if app.palette.is_some() {
+        palette::render(f, app, main_chunks[0]);
+    } else if app.show_help {
+        help::render(f, app, main_chunks[0]);
+    }
+
+    // Render footer with shortcuts + optional status
+    clickables.extend(footer::render_footer(f, app, main_chunks[1]));
+    (main_chunks[0], clickables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::CtfBox;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn app(view: AppView) -> App {
+        let mut app = App::new(
+            vec![CtfBox::sample(1, "Lame"), CtfBox::sample(2, "Blue")],
+            Config::default(),
+        );
+        app.view = view;
+        app
+    }
+
+    /// Draw the app on a 120x40 terminal, returning its text row by row
+    fn render(app: &App) -> String {
+        render_at(app, 120, 40)
+    }
+
+    fn render_at(app: &App, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| {
+            draw(f, app);
+        })
+        .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn list_shows_every_box() {
+        let screen = render(&app(AppView::List));
+        assert!(screen.contains("Lame"));
+        assert!(screen.contains("Blue"));
+        assert!(screen.contains("10.10.10.5"));
+    }
+
+    #[test]
+    fn details_show_the_box() {
+        let screen = render(&app(AppView::Details(2)));
+        assert!(screen.contains("Blue"));
+        assert!(!screen.contains("Lame"));
+    }
+
+    #[test]
+    fn dashboard_and_stats_render() {
+        assert!(render(&app(AppView::Dashboard)).contains("Lame"));
+        assert!(render(&app(AppView::Stats)).contains('2'));
+    }
+
+    #[test]
+    fn add_box_form_is_drawn_over_the_list() {
+        let mut app = app(AppView::List);
+        app.start_add_box();
+        let screen = render(&app);
+        assert!(screen.contains("Lame"));
+        assert!(screen.contains("Title"));
+    }
+
+    #[test]
+    fn status_message_is_in_the_footer() {
+        let mut app = app(AppView::List);
+        app.set_status("Box added", crate::app::StatusKind::Success);
+        assert!(render(&app).contains("Box added"));
+    }
+
+    #[test]
+    fn help_overlay() {
+        let mut app = app(AppView::List);
+        app.show_help = true;
+        assert!(render(&app).contains("Quit"));
+    }
+
+    #[test]
+    fn dashboard_panes_follow_their_widths_on_a_small_terminal() {
+        let mut app = app(AppView::Dashboard);
+        let corners = |app: &App| {
+            let screen = render_at(app, 60, 12);
+            let top = screen.lines().next().unwrap().chars().collect::<Vec<_>>();
+            (0..top.len()).filter(|x| top[*x] == '┌').collect::<Vec<_>>()
+        };
+        assert_eq!(corners(&app), [0, 15, 40]);
+        app.dashboard.widths = [10, 45, 45];
+        assert_eq!(corners(&app), [0, 6, 34]);
+    }
+
+    #[test]
+    fn every_view_draws_on_tiny_terminals() {
+        let views: Vec<fn(&mut App)> = vec![
+            |app| app.view = AppView::List,
+            App::open_dashboard,
+            |app| app.view = AppView::Stats,
+            |app| app.view = AppView::Details(1),
+            App::start_add_box,
+            |app| app.start_add_flag(1),
+            App::start_delete_box,
+            |app| app.start_kill_tmux(1),
+            |app| app.start_edit_env_vars(1),
+            |app| app.start_edit_notes(1),
+            |app| app.start_writeup_export(1),
+            |app| app.start_loot_view(1),
+            |app| app.start_timeline(1),
+            |app| app.start_checklists(1),
+            |app| app.start_suggestions(1),
+            |app| app.start_snippets(1),
+            |app| app.start_payloads(1),
+            |app| app.start_hosts(Some(1)),
+        ];
+        for open in views {
+            let mut app = fixture();
+            open(&mut app);
+            for (width, height) in [(1, 1), (20, 5), (40, 10), (60, 12)] {
+                render_at(&app, width, height);
+            }
+            app.show_help = true;
+            render_at(&app, 20, 5);
+        }
+    }
+
+    // ========== Snapshots ==========
+
+    /// 2026-03-14 at the given local time, so times render the same in any zone
+    fn at(day: u32, hour: u32, minute: u32) -> chrono::DateTime<chrono::Utc> {
+        use chrono::TimeZone;
+        chrono::Local
+            .with_ymd_and_hms(2026, 3, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    }
+
+    /// Two boxes with something in every part of the first, and nothing
+    /// read from the machine running the tests
+    fn fixture() -> App {
+        use crate::models::*;
+
+        let action = |time, command: &str, result, output: Option<&str>| Action {
+            timestamp: time,
+            command: command.to_string(),
+            result,
+            note: None,
+            output: output.map(String::from),
+        };
+        let mut lame = CtfBox::sample(1, "Lame");
+        lame.tags = vec!["linux".to_string(), "easy".to_string()];
+        lame.created_date = at(14, 9, 0);
+        lame.updated_date = at(14, 11, 30);
+        lame.actions = vec![
+            action(
+                at(14, 9, 5),
+                "nmap -sV 10.10.10.5",
+                ActionResult::Success,
+                Some("21/tcp  open  ftp     vsftpd 2.3.4\n139/tcp open  netbios-ssn Samba smbd 3.0.20-Debian"),
+            ),
+            action(at(14, 9, 40), "smbclient -L //10.10.10.5 -N", ActionResult::Success, None),
+            action(at(14, 10, 55), "searchsploit samba 3.0.20", ActionResult::Unknown, None),
+            action(at(14, 11, 20), "msfconsole -q", ActionResult::Fail, None),
+        ];
+        lame.notes = vec![Note {
+            category: NoteCategory::Foothold,
+            content: "Samba usermap_script gives a root shell".to_string(),
+            created_date: at(14, 11, 25),
+            images: Vec::new(),
+        }];
+        lame.env_vars = [("LHOST", "10.10.14.2"), ("DOMAIN", "lame.htb")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        lame.attachments = vec![Attachment {
+            name: "smb.conf".to_string(),
+            sha256: "ab".repeat(32),
+            size: 1234,
+            mime: "text/plain".to_string(),
+            source: "/etc/samba/smb.conf".to_string(),
+            note: None,
+            created_date: at(14, 10, 0),
+        }];
+        lame.flags = vec![Flag {
+            kind: FlagKind::User,
+            value: "0123456789abcdef0123456789abcdef".to_string(),
+            captured_date: at(14, 11, 28),
+        }];
+        lame.sessions = vec![ShellSession { started: at(14, 9, 0), ended: at(14, 11, 30) }];
+        lame.checklists = vec![Checklist {
+            template: "linux".to_string(),
+            name: "Linux".to_string(),
+            items: vec![
+                ChecklistItem {
+                    text: "Scan the ports".to_string(),
+                    auto: vec!["^nmap".to_string()],
+                    done: true,
+                    matched: Some("nmap -sV 10.10.10.5".to_string()),
+                },
+                ChecklistItem {
+                    text: "Check sudo rights".to_string(),
+                    auto: Vec::new(),
+                    done: false,
+                    matched: None,
+                },
+            ],
+        }];
+        lame.hostnames = vec!["lame.htb".to_string()];
+        lame.outages = vec![Outage { started: at(14, 10, 0), ended: Some(at(14, 10, 20)), vpn_down: true }];
+
+        let mut blue = CtfBox::sample(2, "Blue");
+        blue.platform = "TryHackMe".to_string();
+        blue.ip_address = "10.10.10.40".parse().unwrap();
+        blue.created_date = at(13, 20, 0);
+        blue.updated_date = at(13, 20, 0);
+
+        let mut config = Config::default();
+        config.paths.base_dir = "/nonexistent/ctf-brain".to_string();
+        config.paths.exploitdb = "/nonexistent/files_exploits.csv".to_string();
+        config.paths.hosts_file = "/nonexistent/hosts".to_string();
+        let boxes = vec![lame, blue];
+        let stats = crate::stats::Stats::compute_at(&boxes, at(14, 12, 0));
+        let mut app = App::new(boxes, config);
+        app.stats = Some(stats);
+        app.tmux_sessions = vec!["ctf-lame-1".to_string()];
+        app
+    }
+
+    /// Compare the screen to `src/ui/snapshots/{name}.txt`. A differing
+    /// screen is written next to it as `.txt.new`; run with
+    /// UPDATE_SNAPSHOTS=1 to accept it.
+    fn assert_snapshot(name: &str, app: &App) {
+        let screen = render(app) + "\n";
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/ui/snapshots")
+            .join(format!("{name}.txt"));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &screen).unwrap();
+            return;
+        }
+        let stored = std::fs::read_to_string(&path).unwrap_or_default();
+        if stored != screen {
+            let new = path.with_extension("txt.new");
+            std::fs::write(&new, &screen).unwrap();
+            panic!("{name} differs from {}, see {}", path.display(), new.display());
+        }
+    }
+
+    #[test]
+    fn snapshot_list() {
+        assert_snapshot("list", &fixture());
+    }
+
+    #[test]
+    fn snapshot_dashboard() {
+        let mut app = fixture();
+        app.open_dashboard();
+        assert_snapshot("dashboard", &app);
+    }
+
+    #[test]
+    fn snapshot_stats() {
+        let mut app = fixture();
+        app.view = AppView::Stats;
+        assert_snapshot("stats", &app);
+    }
+
+    #[test]
+    fn snapshot_details() {
+        let mut app = fixture();
+        app.view = AppView::Details(1);
+        assert_snapshot("details", &app);
+    }
+
+    #[test]
+    fn snapshot_add_box() {
+        let mut app = fixture();
+        app.start_add_box();
+        assert_snapshot("add_box", &app);
+    }
+
+    #[test]
+    fn snapshot_add_flag() {
+        let mut app = fixture();
+        app.start_add_flag(1);
+        assert_snapshot("add_flag", &app);
+    }
+
+    #[test]
+    fn snapshot_delete_box() {
+        let mut app = fixture();
+        app.start_delete_box();
+        assert_snapshot("delete_box", &app);
+    }
+
+    #[test]
+    fn snapshot_kill_tmux() {
+        let mut app = fixture();
+        app.start_kill_tmux(1);
+        assert_snapshot("kill_tmux", &app);
+    }
+
+    #[test]
+    fn snapshot_edit_env_vars() {
+        let mut app = fixture();
+        app.start_edit_env_vars(1);
+        assert_snapshot("edit_env_vars_form", &app);
+        app.view = AppView::EditEnvVars(1, None);
+        assert_snapshot("edit_env_vars", &app);
+    }
+
+    #[test]
+    fn snapshot_edit_notes() {
+        let mut app = fixture();
+        app.start_edit_notes(1);
+        assert_snapshot("edit_notes", &app);
+        let form = app.start_note_form(1, false).unwrap();
+        app.view = AppView::EditNotes(1, Some(form));
+        assert_snapshot("edit_notes_category", &app);
+    }
+
+    #[test]
+    fn snapshot_writeup_export() {
+        let mut app = fixture();
+        app.start_writeup_export(1);
+        assert_snapshot("writeup_export", &app);
+    }
+
+    #[test]
+    fn snapshot_loot() {
+        let mut app = fixture();
+        app.start_loot_view(1);
+        assert_snapshot("loot", &app);
+    }
+
+    #[test]
+    fn snapshot_timeline() {
+        let mut app = fixture();
+        app.start_timeline(1);
+        assert_snapshot("timeline", &app);
+    }
+
+    #[test]
+    fn snapshot_checklists() {
+        let mut app = fixture();
+        app.start_checklists(1);
+        assert_snapshot("checklists", &app);
+        app.open_template_picker(1);
+        assert_snapshot("checklists_templates", &app);
+    }
+
+    #[test]
+    fn snapshot_suggestions() {
+        let mut app = fixture();
+        app.start_suggestions(1);
+        assert_snapshot("suggestions", &app);
+    }
+
+    #[test]
+    fn snapshot_snippets() {
+        let mut app = fixture();
+        app.start_snippets(1);
+        assert_snapshot("snippets", &app);
+    }
+
+    #[test]
+    fn snapshot_payloads() {
+        let mut app = fixture();
+        app.start_payloads(1);
+        assert_snapshot("payloads", &app);
+    }
+
+    #[test]
+    fn snapshot_hosts() {
+        let mut app = fixture();
+        app.start_hosts(Some(1));
+        assert_snapshot("hosts", &app);
+    }
+}
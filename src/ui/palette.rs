@@ -0,0 +1,102 @@
+use crate::app::App;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+/// Render the command palette over the current view
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let Some(palette) = &app.palette else {
+        return;
+    };
+    let matches = app.palette_matches();
+
+    let width = std::cmp::min(70, area.width.saturating_sub(4));
+    let height = std::cmp::min(matches.len() as u16 + 5, area.height.saturating_sub(2)).max(6);
+    let modal_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + area.height.saturating_sub(height) / 3,
+        width,
+        height,
+    };
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(" ⌘ Command palette ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Query
+            Constraint::Min(1),    // Matches
+        ])
+        .split(inner);
+
+    // Query line
+    let (query, cursor) = palette.input.visible(chunks[0].width.saturating_sub(2) as usize);
+    let query_line = Paragraph::new(Line::from(vec![
+        Span::styled("> ", Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+        Span::styled(query, Style::default().fg(theme.text)),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme.muted)),
+    );
+    f.render_widget(query_line, chunks[0]);
+    f.set_cursor_position((chunks[0].x + 2 + cursor as u16, chunks[0].y));
+
+    if matches.is_empty() {
+        f.render_widget(
+            Paragraph::new(Span::styled("No matching action", Style::default().fg(theme.muted))),
+            chunks[1],
+        );
+        return;
+    }
+
+    // Matches, with the matched characters highlighted and the bound keys on the right
+    let width = chunks[1].width as usize;
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|(action, positions)| {
+            let description = action.description();
+            let keys = app.keymap.keys_for(palette.context, *action).join("/");
+
+            let mut spans: Vec<Span> = description
+                .chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    if positions.contains(&i) {
+                        Span::styled(
+                            c.to_string(),
+                            Style::default()
+                                .fg(theme.highlight)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                    } else {
+                        Span::styled(c.to_string(), Style::default().fg(theme.text))
+                    }
+                })
+                .collect();
+            let used = description.chars().count() + keys.chars().count() + 2;
+            spans.push(Span::raw(" ".repeat(width.saturating_sub(used))));
+            spans.push(Span::styled(keys, Style::default().fg(theme.muted)));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default();
+    state.select(Some(palette.selected.min(matches.len() - 1)));
+    f.render_stateful_widget(list, chunks[1], &mut state);
+}
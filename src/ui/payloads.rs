@@ -0,0 +1,105 @@
+use crate::app::App;
+use crate::payloads::Encoding;
+use crate::ui::Clickable;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
+    let Some(ctf_box) = app.boxes.iter().find(|b| b.id == box_id) else {
+        return Vec::new();
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Listener
+            Constraint::Min(5),    // Catalog and payload
+        ])
+        .split(area);
+
+    let lport = crate::payloads::lport(ctf_box, &app.config);
+    let listener = match &app.lhost {
+        Ok(lhost) => Span::styled(
+            format!("LHOST {}  LPORT {}", lhost, lport),
+            Style::default().fg(theme.success).add_modifier(Modifier::BOLD),
+        ),
+        Err(e) => Span::styled(
+            format!("{} - set LHOST in the box variables  LPORT {}", e, lport),
+            Style::default().fg(theme.error),
+        ),
+    };
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!("🐚 Payloads - {}  ", ctf_box.title),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        ),
+        listener,
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, chunks[0]);
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(chunks[1]);
+
+    let items: Vec<ListItem> = app
+        .payloads
+        .iter()
+        .map(|payload| {
+            ListItem::new(Line::from(vec![
+                Span::styled(payload.name.clone(), Style::default().fg(theme.text)),
+                Span::styled(format!("  [{}]", payload.platform), Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Catalog ({})", app.payloads.len()))
+                .border_style(Style::default().fg(theme.accent)),
+        )
+        .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default().with_selected(app.selected_payload);
+    f.render_stateful_widget(list, body[0], &mut state);
+
+    // The encodings, the current one highlighted
+    let mut title = Vec::new();
+    for encoding in Encoding::ALL {
+        let style = if encoding == app.payload_encoding {
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        title.push(Span::styled(format!(" {} ", encoding.label()), style));
+    }
+    let text = app
+        .selected_payload
+        .and_then(|i| app.payloads.get(i))
+        .map(|payload| app.payload_text(box_id, payload))
+        .unwrap_or_default();
+    let preview = Paragraph::new(text)
+        .style(Style::default().fg(theme.text))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Line::from(title))
+                .border_style(Style::default().fg(theme.special)),
+        );
+    f.render_widget(preview, body[1]);
+
+    let inner = Block::default().borders(Borders::ALL).inner(body[0]);
+    (state.offset()..app.payloads.len())
+        .zip(inner.rows())
+        .map(|(i, row)| (row, Clickable::Row(i)))
+        .collect()
+}
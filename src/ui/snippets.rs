@@ -0,0 +1,174 @@
+use crate::app::{App, SnippetForm, SnippetPicker};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+pub fn render(f: &mut Frame, app: &App, picker: &SnippetPicker, area: Rect, box_id: i32) {
+    let theme = app.theme;
+    let values = app.snippet_values(box_id);
+    let matches = App::snippet_matches(picker);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Query
+            Constraint::Min(5),    // Matches
+            Constraint::Length(5), // Filled command
+        ])
+        .split(area);
+
+    let title = format!("📚 Snippets ({}/{})", matches.len(), picker.snippets.len());
+    let query_block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(theme.accent));
+    let query_area = query_block.inner(chunks[0]);
+    let (query, cursor) = picker.query.visible(query_area.width.saturating_sub(2) as usize);
+    let query_line = Paragraph::new(Line::from(vec![
+        Span::styled("> ", Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+        Span::styled(query, Style::default().fg(theme.text)),
+    ]))
+    .block(query_block);
+    f.render_widget(query_line, chunks[0]);
+
+    // Titles with the matched characters highlighted, then the tags
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|(snippet, positions)| {
+            let mut spans: Vec<Span> = snippet
+                .title
+                .chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    let style = if positions.contains(&i) {
+                        Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme.text)
+                    };
+                    Span::styled(c.to_string(), style)
+                })
+                .collect();
+            if !snippet.tags.is_empty() {
+                spans.push(Span::styled(
+                    format!("  [{}]", snippet.tags.join(", ")),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL))
+        .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    let selected = (!matches.is_empty()).then(|| picker.selected.min(matches.len() - 1));
+    let mut state = ListState::default().with_selected(selected);
+    f.render_stateful_widget(list, chunks[1], &mut state);
+
+    // The selected command as it would run, placeholders left to fill in color
+    let preview = selected
+        .map(|i| crate::snippets::fill(&matches[i].0.command, &values))
+        .unwrap_or_default();
+    let preview = Paragraph::new(placeholder_spans(app, &preview))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("$ ctf")
+                .border_style(Style::default().fg(theme.special)),
+        );
+    f.render_widget(preview, chunks[2]);
+
+    match &picker.form {
+        Some(form) => render_form(f, app, form, &values, area),
+        None => f.set_cursor_position((query_area.x + 2 + cursor as u16, query_area.y)),
+    }
+}
+
+/// A command with the placeholders still to fill highlighted
+fn placeholder_spans(app: &App, command: &str) -> Line<'static> {
+    let theme = app.theme;
+    let missing = crate::snippets::missing(command, &Default::default());
+    let mut spans = Vec::new();
+    let mut rest = command;
+    while let Some(start) = rest.find(['<', '{']) {
+        let tail = &rest[start..];
+        let found = missing
+            .iter()
+            .flat_map(|name| [format!("<{}>", name), format!("{{{{{}}}}}", name)])
+            .find(|p| tail.starts_with(p.as_str()));
+        let Some(placeholder) = found else {
+            spans.push(Span::raw(rest[..start + 1].to_string()));
+            rest = &rest[start + 1..];
+            continue;
+        };
+        spans.push(Span::raw(rest[..start].to_string()));
+        spans.push(Span::styled(
+            placeholder.clone(),
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+        ));
+        rest = &tail[placeholder.len()..];
+    }
+    spans.push(Span::raw(rest.to_string()));
+    Line::from(spans)
+}
+
+/// Popup asking for the placeholders the box has no value for
+fn render_form(
+    f: &mut Frame,
+    app: &App,
+    form: &SnippetForm,
+    values: &std::collections::HashMap<String, String>,
+    area: Rect,
+) {
+    let theme = app.theme;
+    let width = area.width.saturating_sub(8).min(90);
+    let height = (form.fields.len() as u16 + 5).min(area.height);
+    let modal_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("✏️  {}", form.snippet.title))
+        .border_style(Style::default().fg(theme.highlight));
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let label_width = form.fields.iter().map(|(n, _)| n.len()).max().unwrap_or(0) + 2;
+    let mut lines = Vec::new();
+    let mut cursor = None;
+    for (i, (name, input)) in form.fields.iter().enumerate() {
+        let active = i == form.current;
+        let (text, position) = input.visible(inner.width.saturating_sub(label_width as u16) as usize);
+        if active {
+            cursor = Some((inner.x + (label_width + position) as u16, inner.y + i as u16));
+        }
+        let label_style = if active {
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<width$}", format!("{}:", name), width = label_width), label_style),
+            Span::styled(text, Style::default().fg(theme.text)),
+        ]));
+    }
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        form.preview(values),
+        Style::default().fg(theme.special),
+    )));
+    f.render_widget(Paragraph::new(lines), inner);
+    if let Some(position) = cursor {
+        f.set_cursor_position(position);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::app::App;
+use crate::stats::{self, Durations, Stats};
+use chrono::{Datelike, Days, Local};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{BarChart, Block, Borders, Paragraph, Sparkline},
+    Frame,
+};
+
+/// Weeks shown in the activity heatmap
+const HEATMAP_WEEKS: u64 = 16;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let computed;
+    let stats = match &app.stats {
+        Some(stats) => stats,
+        None => {
+            computed = Stats::compute(&app.boxes);
+            &computed
+        }
+    };
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(9),  // Boxes
+            Constraint::Length(9),  // Time to flags, notes
+            Constraint::Min(11),    // Tools, activity
+        ])
+        .split(area);
+    let thirds = |area| {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+            ])
+            .split(area)
+    };
+
+    let top = thirds(rows[0]);
+    let platforms: Vec<(&str, usize)> = stats
+        .by_platform
+        .iter()
+        .map(|(name, count)| (name.as_str(), *count))
+        .collect();
+    bar_chart(f, app, top[0], &format!("📦 Platforms ({} boxes)", stats.boxes), &platforms);
+    bar_chart(f, app, top[1], "🏁 Status", &stats::status_counts(stats));
+    bar_chart(f, app, top[2], "📈 Difficulty", &stats::difficulty_counts(stats));
+
+    let middle = thirds(rows[1]);
+    durations_chart(f, app, middle[0], "⏱️  Time to user", &stats.time_to_user);
+    durations_chart(f, app, middle[1], "⏱️  Time to root", &stats.time_to_root);
+    let notes: Vec<(&str, usize)> = stats
+        .notes_by_category
+        .iter()
+        .map(|(name, count)| (name.as_str(), *count))
+        .collect();
+    bar_chart(f, app, middle[2], "📝 Notes", &notes);
+
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(rows[2]);
+    render_tools(f, app, stats, bottom[0]);
+    render_activity(f, app, stats, bottom[1]);
+}
+
+fn bar_chart(f: &mut Frame, app: &App, area: Rect, title: &str, data: &[(&str, usize)]) {
+    let theme = app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title.to_string())
+        .border_style(Style::default().fg(theme.accent));
+    if data.iter().all(|(_, count)| *count == 0) {
+        let empty = Paragraph::new("No data yet")
+            .style(Style::default().fg(theme.muted))
+            .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    // Bars as wide as the labels allow, within the block
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let bar_width = (inner_width / data.len().max(1)).saturating_sub(1).clamp(1, 10) as u16;
+    let data: Vec<(&str, u64)> = data.iter().map(|(label, count)| (*label, *count as u64)).collect();
+    let chart = BarChart::default()
+        .block(block)
+        .data(data.as_slice())
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(theme.highlight))
+        .value_style(Style::default().fg(theme.text).bg(theme.highlight).add_modifier(Modifier::BOLD))
+        .label_style(Style::default().fg(theme.muted));
+    f.render_widget(chart, area);
+}
+
+fn durations_chart(f: &mut Frame, app: &App, area: Rect, title: &str, durations: &Durations) {
+    let title = match durations.median_hours {
+        Some(median) => format!("{} (median {})", title, stats::format_hours(median)),
+        None => title.to_string(),
+    };
+    let buckets: Vec<(&str, usize)> = durations.buckets.iter().map(|b| (b.label, b.count)).collect();
+    bar_chart(f, app, area, &title, &buckets);
+}
+
+fn render_tools(f: &mut Frame, app: &App, stats: &Stats, area: Rect) {
+    let theme = app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("🔧 Tools (uses, success ratio)")
+        .border_style(Style::default().fg(theme.accent));
+    let rows = block.inner(area).height as usize;
+    let name_width = stats.tools.iter().map(|t| t.name.len()).max().unwrap_or(0).min(16);
+
+    let lines: Vec<Line> = stats
+        .tools
+        .iter()
+        .take(rows)
+        .map(|tool| {
+            // 10-cell gauge of the success ratio, empty when no result is known
+            let (gauge, percent) = match tool.success_ratio() {
+                Some(ratio) => {
+                    let filled = (ratio * 10.0).round() as usize;
+                    (
+                        format!("{}{}", "█".repeat(filled), "░".repeat(10 - filled)),
+                        format!("{:>4.0}%", ratio * 100.0),
+                    )
+                }
+                None => ("·".repeat(10), "   ?".to_string()),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<width$} ", tool.name, width = name_width),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::raw(format!("{:>4} ", tool.uses)),
+                Span::styled(gauge, Style::default().fg(theme.success)),
+                Span::styled(percent, Style::default().fg(theme.muted)),
+            ])
+        })
+        .collect();
+
+    let paragraph = if lines.is_empty() {
+        Paragraph::new("No commands logged yet").style(Style::default().fg(theme.muted))
+    } else {
+        Paragraph::new(lines)
+    };
+    f.render_widget(paragraph.block(block), area);
+}
+
+fn render_activity(f: &mut Frame, app: &App, stats: &Stats, area: Rect) {
+    let theme = app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("📅 Activity (actions + notes per day)")
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(8),  // Heatmap: header + 7 days
+            Constraint::Min(1),     // Sparkline of the last days
+        ])
+        .split(inner);
+
+    f.render_widget(Paragraph::new(heatmap(app, stats)), parts[0]);
+
+    let days = parts[1].width as usize;
+    let recent: Vec<u64> = stats
+        .recent_activity(days)
+        .into_iter()
+        .map(|(_, count)| count as u64)
+        .collect();
+    let sparkline = Sparkline::default()
+        .data(recent)
+        .style(Style::default().fg(theme.highlight));
+    f.render_widget(sparkline, parts[1]);
+}
+
+/// GitHub-style grid: one row per weekday, one column per week
+fn heatmap(app: &App, stats: &Stats) -> Vec<Line<'static>> {
+    let theme = app.theme;
+    let today = stats.generated.with_timezone(&Local).date_naive();
+    let weekday = today.weekday().num_days_from_monday() as u64;
+    let Some(first_monday) = today.checked_sub_days(Days::new(weekday + (HEATMAP_WEEKS - 1) * 7)) else {
+        return Vec::new();
+    };
+    let busiest = stats.activity.values().copied().max().unwrap_or(0).max(1);
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!("    {} → {}", first_monday.format("%d %b"), today.format("%d %b")),
+        Style::default().fg(theme.muted),
+    ))];
+    for (row, name) in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"].iter().enumerate() {
+        let mut spans = vec![Span::styled(format!("{} ", name), Style::default().fg(theme.muted))];
+        for week in 0..HEATMAP_WEEKS {
+            let Some(day) = first_monday.checked_add_days(Days::new(week * 7 + row as u64)) else {
+                continue;
+            };
+            if day > today {
+                spans.push(Span::raw("  "));
+                continue;
+            }
+            let count = stats.activity.get(&day).copied().unwrap_or(0);
+            spans.push(heat_cell(count, busiest, theme.success, theme.muted));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// A cell shaded by the activity of the day, relative to the busiest day
+fn heat_cell(count: usize, busiest: usize, color: Color, empty: Color) -> Span<'static> {
+    const SHADES: [&str; 4] = ["░ ", "▒ ", "▓ ", "█ "];
+    if count == 0 {
+        return Span::styled("· ", Style::default().fg(empty));
+    }
+    let level = (count * SHADES.len()).div_ceil(busiest).clamp(1, SHADES.len()) - 1;
+    Span::styled(SHADES[level], Style::default().fg(color))
+}
@@ -0,0 +1,137 @@
+use crate::app::App;
+use crate::ui::Clickable;
+use crate::ui::input::TextInput;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+pub fn render(
+    f: &mut Frame,
+    app: &App,
+    input: Option<&TextInput>,
+    area: Rect,
+    box_id: i32,
+) -> Vec<(Rect, Clickable)> {
+    let theme = app.theme;
+    let Some(ctf_box) = app.boxes.iter().find(|b| b.id == box_id) else {
+        return Vec::new();
+    };
+    let suggestions = app.suggestions(box_id);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(5),    // Suggestions
+        ])
+        .split(area);
+
+    let done = suggestions.iter().filter(|s| s.done).count();
+    let header = Paragraph::new(format!(
+        "💡 Next steps - {} ({}/{} run)",
+        ctf_box.title,
+        done,
+        suggestions.len()
+    ))
+    .style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, chunks[0]);
+
+    // One row per service, then its suggestions. `rows` maps each row to
+    // the index of its suggestion.
+    let mut items = Vec::new();
+    let mut rows: Vec<Option<usize>> = Vec::new();
+    let mut service = None;
+    for (index, suggestion) in suggestions.iter().enumerate() {
+        if service != Some(&suggestion.service) {
+            service = Some(&suggestion.service);
+            let s = &suggestion.service;
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{}/{} {}", s.port, s.protocol, s.name),
+                    Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("  {}", s.version), Style::default().fg(theme.muted)),
+            ])));
+            rows.push(None);
+        }
+        let (mark, style) = if suggestion.done {
+            ("✓ ", Style::default().fg(theme.muted))
+        } else {
+            ("  ", Style::default().fg(theme.text))
+        };
+        items.push(ListItem::new(Line::from(vec![
+            Span::styled(format!("  {}{}", mark, suggestion.title), style),
+            Span::styled(format!("  {}", suggestion.command), Style::default().fg(theme.muted)),
+        ])));
+        rows.push(Some(index));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Suggestions")
+        .border_style(Style::default().fg(theme.accent));
+    let mut clickables = Vec::new();
+    if suggestions.is_empty() {
+        let hint = Paragraph::new("No services known yet, log an nmap scan with `ctf nmap -sV $CTF_IP`")
+            .style(Style::default().fg(theme.muted))
+            .block(block);
+        f.render_widget(hint, chunks[1]);
+    } else {
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD));
+        let selected_row = app
+            .selected_suggestion
+            .and_then(|selected| rows.iter().position(|r| *r == Some(selected)));
+        let mut state = ListState::default().with_selected(selected_row);
+        f.render_stateful_widget(list, chunks[1], &mut state);
+
+        let inner = Block::default().borders(Borders::ALL).inner(chunks[1]);
+        clickables = rows
+            .iter()
+            .skip(state.offset())
+            .zip(inner.rows())
+            .filter_map(|(item, row)| item.map(|i| (row, Clickable::Row(i))))
+            .collect();
+    }
+
+    if let Some(input) = input {
+        render_command(f, app, input, area);
+        return Vec::new();
+    }
+    clickables
+}
+
+/// Popup with the command to run, editable
+fn render_command(f: &mut Frame, app: &App, input: &TextInput, area: Rect) {
+    let theme = app.theme;
+    let modal_width = area.width.saturating_sub(8).min(100);
+    let modal_area = Rect {
+        x: area.x + (area.width.saturating_sub(modal_width)) / 2,
+        y: area.y + (area.height.saturating_sub(3)) / 2,
+        width: modal_width,
+        height: 3.min(area.height),
+    };
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("▶ Run with ctf")
+        .border_style(Style::default().fg(theme.special));
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    // "$ ctf " = 6 chars
+    let (text, cursor) = input.visible(inner.width.saturating_sub(6) as usize);
+    let line = Line::from(vec![
+        Span::styled("$ ctf ", Style::default().fg(theme.muted)),
+        Span::styled(text, Style::default().fg(theme.text)),
+    ]);
+    f.render_widget(Paragraph::new(line), inner);
+    f.set_cursor_position((inner.x + 6 + cursor as u16, inner.y));
+}
@@ -0,0 +1,73 @@
+use ratatui::style::Color;
+
+/// Named color palette used by every view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Regular text
+    pub text: Color,
+    /// Secondary text, hints and inactive borders
+    pub muted: Color,
+    /// Titles and main borders
+    pub accent: Color,
+    /// Selected item, active field, key hints
+    pub highlight: Color,
+    pub success: Color,
+    pub error: Color,
+    /// Shell, redaction and other special actions
+    pub special: Color,
+    /// Background of the selected row
+    pub selection: Color,
+}
+
+impl Theme {
+    pub const NAMES: [&'static str; 3] = ["dark", "light", "high-contrast"];
+
+    pub const DARK: Theme = Theme {
+        name: "dark",
+        text: Color::White,
+        muted: Color::DarkGray,
+        accent: Color::Cyan,
+        highlight: Color::Yellow,
+        success: Color::Green,
+        error: Color::Red,
+        special: Color::Magenta,
+        selection: Color::DarkGray,
+    };
+
+    pub const LIGHT: Theme = Theme {
+        name: "light",
+        text: Color::Black,
+        muted: Color::Gray,
+        accent: Color::Blue,
+        highlight: Color::Rgb(175, 95, 0),
+        success: Color::Rgb(0, 128, 0),
+        error: Color::Rgb(190, 0, 0),
+        special: Color::Magenta,
+        selection: Color::Rgb(215, 215, 215),
+    };
+
+    pub const HIGH_CONTRAST: Theme = Theme {
+        name: "high-contrast",
+        text: Color::White,
+        muted: Color::Gray,
+        accent: Color::LightCyan,
+        highlight: Color::LightYellow,
+        success: Color::LightGreen,
+        error: Color::LightRed,
+        special: Color::LightMagenta,
+        selection: Color::Blue,
+    };
+
+    pub fn by_name(name: &str) -> Option<Theme> {
+        [Self::DARK, Self::LIGHT, Self::HIGH_CONTRAST]
+            .into_iter()
+            .find(|t| t.name == name)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
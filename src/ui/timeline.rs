@@ -0,0 +1,74 @@
+use crate::app::App;
+use crate::timeline::{self, EventKind, Item};
+use chrono::Local;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
+    let theme = app.theme;
+    let Some(ctf_box) = app.boxes.iter().find(|b| b.id == box_id) else {
+        let error = Paragraph::new("Box not found!").style(Style::default().fg(theme.error));
+        f.render_widget(error, area);
+        return;
+    };
+
+    let items = timeline::build(ctf_box);
+    let count = items.len();
+    let mut lines = Vec::new();
+    let mut day = None;
+    for item in items.into_iter().skip(app.timeline_scroll) {
+        match item {
+            Item::Event(event) => {
+                let local = event.timestamp.with_timezone(&Local);
+                // Day header, also above the first visible event
+                if day != Some(local.date_naive()) {
+                    day = Some(local.date_naive());
+                    lines.push(Line::from(Span::styled(
+                        format!("── {} ──", local.format("%a %d %b %Y")),
+                        Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+                    )));
+                }
+                let text_style = match event.kind {
+                    EventKind::Flag(_) | EventKind::Status(_) => {
+                        Style::default().fg(theme.success).add_modifier(Modifier::BOLD)
+                    }
+                    EventKind::Session => Style::default().fg(theme.special),
+                    EventKind::Note => Style::default().fg(theme.highlight),
+                    EventKind::Outage => Style::default().fg(theme.error),
+                    _ => Style::default().fg(theme.text),
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {} ", local.format("%H:%M:%S")),
+                        Style::default().fg(theme.muted),
+                    ),
+                    Span::raw(format!("{} ", event.kind.icon())),
+                    Span::styled(event.text, text_style),
+                ]));
+            }
+            Item::Break(quiet) => lines.push(Line::from(Span::styled(
+                format!("           ⏸  {}", timeline::break_label(quiet)),
+                Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+            ))),
+        }
+    }
+
+    let title = format!(
+        "🕒 Timeline - {} ({}/{})",
+        ctf_box.title,
+        (app.timeline_scroll + 1).min(count),
+        count
+    );
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(theme.accent)),
+    );
+    f.render_widget(paragraph, area);
+}
@@ -1,13 +1,15 @@
 use crate::app::App;
+use crate::redact::RedactMode;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
 };
 
 pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
+    let theme = app.theme;
     let ctf_box = match app.boxes.iter().find(|b| b.id == box_id) {
         Some(b) => b,
         None => return,
@@ -29,7 +31,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
     ))
     .style(
         Style::default()
-            .fg(Color::Cyan)
+            .fg(theme.accent)
             .add_modifier(Modifier::BOLD),
     )
     .block(Block::default().borders(Borders::ALL));
@@ -40,21 +42,58 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
     let note_count = ctf_box.notes.len();
     let actions_with_output = ctf_box.actions.iter().filter(|a| a.output.is_some()).count();
 
-    let info = Paragraph::new(vec![
+    let mut info_lines = vec![
         Line::from(vec![
-            Span::styled("Actions: ", Style::default().fg(Color::Yellow)),
+            Span::styled("Actions: ", Style::default().fg(theme.highlight)),
             Span::raw(format!("{} ({} with output)", action_count, actions_with_output)),
         ]),
         Line::from(vec![
-            Span::styled("Notes: ", Style::default().fg(Color::Yellow)),
+            Span::styled("Notes: ", Style::default().fg(theme.highlight)),
             Span::raw(format!("{}", note_count)),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "Only sections with content will be included.",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         )),
-    ])
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("🔒 Redaction: ", Style::default().fg(theme.highlight)),
+            Span::styled(
+                app.redact_mode.label(),
+                Style::default()
+                    .fg(theme.special)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  (Ctrl+R to change)", Style::default().fg(theme.muted)),
+        ]),
+    ];
+
+    // Preview of what will be redacted
+    if app.redact_mode != RedactMode::Off {
+        if app.redaction_preview.is_empty() {
+            info_lines.push(Line::from(Span::styled(
+                "   No secrets detected.",
+                Style::default().fg(theme.muted),
+            )));
+        }
+        for (rule, sample, count) in &app.redaction_preview {
+            info_lines.push(Line::from(vec![
+                Span::raw("   "),
+                Span::styled(format!("{:<12}", rule), Style::default().fg(theme.error)),
+                Span::raw(format!("{} ", sample)),
+                Span::styled(format!("×{}", count), Style::default().fg(theme.muted)),
+            ]));
+        }
+        for problem in &app.redaction_problems {
+            info_lines.push(Line::from(Span::styled(
+                format!("   ⚠ {}", problem),
+                Style::default().fg(theme.error),
+            )));
+        }
+    }
+
+    let info = Paragraph::new(info_lines)
     .block(
         Block::default()
             .borders(Borders::ALL)
@@ -66,7 +105,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
     let input_block = Block::default()
         .borders(Borders::ALL)
         .title("📁 Output Path")
-        .border_style(Style::default().fg(Color::Green));
+        .border_style(Style::default().fg(theme.success));
 
     let input_inner = input_block.inner(chunks[2]);
     f.render_widget(input_block, chunks[2]);
@@ -80,21 +119,28 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
         ])
         .split(input_inner);
 
+    // "File: " = 6 chars
+    let path_width = input_chunks[0].width.saturating_sub(6) as usize;
+    let (path_text, path_cursor) = app.writeup_path.visible(path_width);
     let path_line = Line::from(vec![
         Span::styled(
             "File: ",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.highlight)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(&app.writeup_path, Style::default().fg(Color::White)),
+        Span::styled(path_text, Style::default().fg(theme.text)),
     ]);
     f.render_widget(Paragraph::new(path_line), input_chunks[0]);
 
     let help = Line::from(vec![
-        Span::styled("Enter", Style::default().fg(Color::Green)),
+        Span::styled("Enter", Style::default().fg(theme.success)),
         Span::raw(": Export | "),
-        Span::styled("Esc", Style::default().fg(Color::Red)),
+        Span::styled("Tab", Style::default().fg(theme.success)),
+        Span::raw(": Complete | "),
+        Span::styled("Ctrl+R", Style::default().fg(theme.special)),
+        Span::raw(": Redaction | "),
+        Span::styled("Esc", Style::default().fg(theme.error)),
         Span::raw(": Cancel"),
     ]);
     f.render_widget(
@@ -102,9 +148,9 @@ pub fn render(f: &mut Frame, app: &App, area: Rect, box_id: i32) {
         input_chunks[1],
     );
 
-    // Cursor at end of path
+    // Cursor inside the path
     f.set_cursor_position((
-        input_chunks[0].x + 6 + app.writeup_path.len() as u16, // "File: " = 6
+        input_chunks[0].x + 6 + path_cursor as u16,
         input_chunks[0].y,
     ));
 }
@@ -0,0 +1,1277 @@
+use crate::app::{App, AppView, NoteForm, StatusKind};
+use crate::keymap::{Action, KeyContext, KeyResult};
+use crate::storage::BoxActivity;
+use crate::ui::{Clickable, DetailPane};
+use chrono::{DateTime, Utc};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::Position;
+
+/// Everything that changes the app: terminal input, the clock, and the
+/// outcome of programs run outside the TUI
+#[derive(Debug, Clone)]
+pub enum Message {
+    Key(KeyEvent),
+    /// Bracketed paste, inserted as a whole in the focused input
+    Paste(String),
+    /// Click or wheel, on the areas recorded in `App::clickables`
+    Mouse(MouseEvent),
+    /// No input for a while
+    Tick,
+    /// The box shell exited
+    ShellExited {
+        box_id: i32,
+        started: DateTime<Utc>,
+        result: Result<(), String>,
+    },
+    /// $EDITOR returned, with the note content (None when left empty)
+    NoteComposed {
+        box_id: i32,
+        form: NoteForm,
+        content: Result<Option<String>, String>,
+    },
+    /// The pager showing an attachment exited
+    PagerExited(Result<(), String>),
+    /// A suggested command ran in the box environment
+    CommandFinished {
+        box_id: i32,
+        result: Result<(), String>,
+    },
+    /// The hosts file was written with this content, or needs sudo
+    HostsWritten {
+        content: String,
+        result: Result<crate::hosts::Written, String>,
+    },
+    /// The client left the box's tmux session (or switched to it, in tmux)
+    TmuxDetached {
+        box_id: i32,
+        result: Result<(), String>,
+    },
+    /// What was recorded outside the TUI for a box, read by `Effect::ImportActivity`
+    ActivityImported {
+        box_id: i32,
+        activity: Result<BoxActivity, String>,
+        report: ImportReport,
+    },
+    /// The boxes were written, with the status to show on success
+    Saved {
+        result: Result<(), String>,
+        status: Option<String>,
+    },
+}
+
+/// Work outside the app state: disk writes and reads, and programs that
+/// need the real terminal. The event loop runs it (with the TUI suspended
+/// when `needs_terminal`) and feeds the outcome back as a `Message`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Effect {
+    /// Write the boxes, then show this status
+    Save(Option<String>),
+    /// Read the loot, queued notes and shell logs of a box
+    ImportActivity { box_id: i32, report: ImportReport },
+    LaunchShell(i32),
+    /// Create or attach the box's tmux session
+    OpenTmux(i32),
+    ComposeNote(i32, NoteForm),
+    OpenAttachment { box_id: i32, force_hex: bool },
+    RunCommand { box_id: i32, command: String },
+    /// Write this content to the hosts file
+    WriteHosts(String),
+    /// Same, through sudo (which may ask for a password)
+    WriteHostsWithSudo(String),
+}
+
+impl Effect {
+    pub fn needs_terminal(&self) -> bool {
+        !matches!(
+            self,
+            Effect::Save(_) | Effect::ImportActivity { .. } | Effect::WriteHosts(_)
+        )
+    }
+}
+
+/// Status shown once imported box activity is saved
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportReport {
+    /// None, the import happens in passing
+    Quiet,
+    /// How many commands were imported
+    Count,
+    Status(String),
+}
+
+impl App {
+    /// Apply a message to the app, returning the effects to run in order
+    pub fn update(&mut self, message: Message) -> Vec<Effect> {
+        let effect = self.apply(message);
+        let mut effects = std::mem::take(&mut self.pending_effects);
+        effects.extend(effect);
+        effects
+    }
+
+    fn apply(&mut self, message: Message) -> Option<Effect> {
+        match message {
+            Message::Key(key) => return self.handle_key(key),
+            Message::Paste(text) => self.handle_paste(&text),
+            Message::Mouse(mouse) => return self.handle_mouse(mouse),
+            Message::Tick => {
+                self.tick_status();
+                self.tick_dashboard();
+                if self.tick_reachability() {
+                    self.save();
+                }
+                self.tick_tmux();
+                for (box_id, port) in self.tick_listeners() {
+                    let status = format!("Listener session on port {} logged", port);
+                    self.import_activity(box_id, ImportReport::Status(status));
+                }
+            }
+            // Commands, loot and screenshots from the session
+            Message::ShellExited { box_id, started, result } => match result {
+                Ok(()) => {
+                    self.record_session(box_id, started);
+                    let status = "Shell session ended — commands imported".to_string();
+                    self.import_activity(box_id, ImportReport::Status(status));
+                }
+                Err(e) => {
+                    self.set_status(format!("Failed to launch shell: {}", e), StatusKind::Error);
+                    self.import_activity(box_id, ImportReport::Quiet);
+                }
+            },
+            Message::TmuxDetached { box_id, result } => {
+                self.refresh_tmux_sessions();
+                match result {
+                    Ok(()) => {
+                        let status = "Left the tmux session — commands imported".to_string();
+                        self.import_activity(box_id, ImportReport::Status(status));
+                    }
+                    Err(e) => {
+                        self.set_status(format!("Failed to open tmux: {}", e), StatusKind::Error);
+                        self.import_activity(box_id, ImportReport::Quiet);
+                    }
+                }
+            }
+            Message::NoteComposed { box_id, form, content } => {
+                let saved = match content {
+                    Ok(Some(content)) => match form.editing {
+                        Some(index) => self
+                            .update_note(box_id, index, form.category_index, content)
+                            .map(|_| "Note updated"),
+                        None => self
+                            .add_note(box_id, form.category_index, content)
+                            .map(|_| "Note added"),
+                    },
+                    Ok(None) => Err("Empty note discarded".to_string()),
+                    Err(e) => Err(e),
+                };
+                match saved {
+                    Ok(msg) => self.save_with_status(msg),
+                    Err(e) => self.set_status(e, StatusKind::Error),
+                }
+            }
+            Message::PagerExited(result) => {
+                if let Err(e) = result {
+                    self.set_status(e, StatusKind::Error);
+                }
+            }
+            Message::CommandFinished { box_id, result } => match result {
+                Ok(()) => self.import_activity(box_id, ImportReport::Status("Command logged".to_string())),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+            Message::HostsWritten { content, result } => match result {
+                Ok(crate::hosts::Written::Done) => {
+                    self.hosts_content = Ok(content);
+                    self.set_status("Hosts file updated", StatusKind::Success);
+                }
+                Ok(crate::hosts::Written::NeedsSudo) => return Some(Effect::WriteHostsWithSudo(content)),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+            Message::ActivityImported { box_id, activity, report } => match activity {
+                Ok(activity) => {
+                    let count = self.merge_activity(box_id, activity);
+                    match report {
+                        ImportReport::Quiet => self.save(),
+                        ImportReport::Count => self.save_with_status(&format!("{} command(s) imported", count)),
+                        ImportReport::Status(status) => self.save_with_status(&status),
+                    }
+                }
+                Err(e) => {
+                    self.set_status(e, StatusKind::Error);
+                    // A shell session may have been recorded
+                    self.save();
+                }
+            },
+            Message::Saved { result, status } => match result {
+                Ok(()) => {
+                    if let Some(status) = status {
+                        self.set_status(status, StatusKind::Success);
+                    }
+                }
+                Err(e) => self.set_status(format!("Save failed: {}", e), StatusKind::Error),
+            },
+        }
+        None
+    }
+
+    /// Persist the boxes once this message is handled
+    fn save(&mut self) {
+        self.pending_effects.push(Effect::Save(None));
+    }
+
+    /// Persist the boxes, then show `success` in the status bar
+    fn save_with_status(&mut self, success: &str) {
+        self.pending_effects.push(Effect::Save(Some(success.to_string())));
+    }
+
+    /// Pull in what was recorded outside the TUI for a box, then save
+    fn import_activity(&mut self, box_id: i32, report: ImportReport) {
+        self.pending_effects.push(Effect::ImportActivity { box_id, report });
+    }
+
+    fn handle_paste(&mut self, text: &str) {
+        if let Some(palette) = &mut self.palette {
+            palette.input.insert_str(text);
+            palette.selected = 0;
+            return;
+        }
+        match &mut self.view {
+            AppView::AddBox(form) => form.active_input().insert_str(text),
+            AppView::EditEnvVars(_, Some(form)) => form.active_input().insert_str(text),
+            AppView::AddFlag(form) => form.value.insert_str(text),
+            AppView::WriteupExport(_) => self.writeup_path.insert_str(text),
+            AppView::Suggestions(_, Some(input)) => input.insert_str(text),
+            AppView::Hosts(_, Some(input)) => input.insert_str(text),
+            AppView::Snippets(_, picker) => match &mut picker.form {
+                Some(form) => form.fields[form.current].1.insert_str(text),
+                None => {
+                    picker.query.insert_str(text);
+                    picker.selected = 0;
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Effect> {
+        // Only handle key press events, not release
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        // Help overlay: any key closes it
+        if self.show_help {
+            self.show_help = false;
+            return None;
+        }
+        // Command palette takes the keys while open, Enter runs the
+        // selected action as if its key had been pressed
+        let mut picked = None;
+        if let Some(palette) = &mut self.palette {
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => self.palette = None,
+                KeyCode::Enter => picked = self.take_palette_action(),
+                KeyCode::Down | KeyCode::Tab => self.palette_next(),
+                KeyCode::Char('n') if ctrl => self.palette_next(),
+                KeyCode::Up | KeyCode::BackTab => self.palette_previous(),
+                KeyCode::Char('p') if ctrl => self.palette_previous(),
+                _ => {
+                    if palette.input.handle_key(key) {
+                        palette.selected = 0;
+                    }
+                }
+            }
+            // Still typing the query
+            picked?;
+        }
+
+        // Forms and text inputs take keys before the keymap
+        match self.view {
+            AppView::AddBox(_) => self.add_box_key(key),
+            AppView::EditEnvVars(box_id, Some(_)) => self.env_var_form_key(box_id, key),
+            AppView::EditNotes(box_id, Some(_)) => return self.note_form_key(box_id, key),
+            AppView::AddFlag(_) => self.flag_form_key(key),
+            AppView::WriteupExport(box_id) => self.writeup_key(box_id, key),
+            AppView::Suggestions(box_id, Some(_)) => return self.suggestion_key(box_id, key),
+            AppView::Hosts(Some(box_id), Some(_)) => return self.hostnames_key(box_id, key),
+            AppView::Snippets(box_id, _) => return self.snippet_key(box_id, key),
+            _ => return self.action_key(picked, key),
+        }
+        None
+    }
+
+    fn add_box_key(&mut self, key: KeyEvent) {
+        let AppView::AddBox(form) = &mut self.view else {
+            return;
+        };
+        match key.code {
+            KeyCode::Tab => form.next_field(),
+            KeyCode::BackTab => form.previous_field(),
+            KeyCode::Enter => {
+                let form = form.clone();
+                match self.submit_add_box(&form) {
+                    Ok(_) => self.save_with_status("Box added"),
+                    Err(e) => self.set_status(e, StatusKind::Error),
+                }
+            }
+            KeyCode::Esc => self.cancel_form(),
+            _ => {
+                form.active_input().handle_key(key);
+            }
+        }
+    }
+
+    fn env_var_form_key(&mut self, box_id: i32, key: KeyEvent) {
+        let AppView::EditEnvVars(_, Some(form)) = &mut self.view else {
+            return;
+        };
+        match key.code {
+            KeyCode::Tab => form.next_field(),
+            KeyCode::BackTab => form.previous_field(),
+            KeyCode::Enter => {
+                let name = form.key.value().to_string();
+                let value = form.value.value().to_string();
+                match self.add_env_var(box_id, name, value) {
+                    Ok(_) => {
+                        self.save_with_status("Variable added");
+                        // Reset form (with the updated history)
+                        self.start_edit_env_vars(box_id);
+                    }
+                    Err(e) => self.set_status(e, StatusKind::Error),
+                }
+            }
+            KeyCode::Esc => self.view = AppView::Details(box_id),
+            _ => {
+                form.active_input().handle_key(key);
+            }
+        }
+    }
+
+    fn flag_form_key(&mut self, key: KeyEvent) {
+        let AppView::AddFlag(form) = &mut self.view else {
+            return;
+        };
+        match key.code {
+            KeyCode::Tab => form.next_kind(),
+            KeyCode::Enter => {
+                let form = form.clone();
+                match self.submit_flag(&form) {
+                    Ok(_) => self.save_with_status("Flag recorded"),
+                    Err(e) => self.set_status(e, StatusKind::Error),
+                }
+            }
+            KeyCode::Esc => {
+                let form = form.clone();
+                self.close_flag_form(&form);
+            }
+            _ => {
+                form.value.handle_key(key);
+            }
+        }
+    }
+
+    fn note_form_key(&mut self, box_id: i32, key: KeyEvent) -> Option<Effect> {
+        let AppView::EditNotes(_, Some(form)) = &mut self.view else {
+            return None;
+        };
+        let categories_len = App::note_categories().len();
+        match key.code {
+            KeyCode::Left => {
+                form.category_index = (form.category_index + categories_len - 1) % categories_len;
+            }
+            KeyCode::Right => {
+                form.category_index = (form.category_index + 1) % categories_len;
+            }
+            KeyCode::Enter => {
+                // Write the note in $EDITOR
+                let form = form.clone();
+                self.view = AppView::EditNotes(box_id, None);
+                return Some(Effect::ComposeNote(box_id, form));
+            }
+            KeyCode::Esc => self.view = AppView::EditNotes(box_id, None),
+            _ => {}
+        }
+        None
+    }
+
+    fn suggestion_key(&mut self, box_id: i32, key: KeyEvent) -> Option<Effect> {
+        let AppView::Suggestions(_, Some(input)) = &mut self.view else {
+            return None;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let command = input.value().trim().to_string();
+                if command.is_empty() {
+                    self.set_status("Nothing to run", StatusKind::Error);
+                    return None;
+                }
+                self.view = AppView::Suggestions(box_id, None);
+                return Some(Effect::RunCommand { box_id, command });
+            }
+            KeyCode::Esc => self.view = AppView::Suggestions(box_id, None),
+            _ => {
+                input.handle_key(key);
+            }
+        }
+        None
+    }
+
+    /// New hostnames are saved and written to the hosts file at once
+    fn hostnames_key(&mut self, box_id: i32, key: KeyEvent) -> Option<Effect> {
+        let AppView::Hosts(_, Some(input)) = &mut self.view else {
+            return None;
+        };
+        match key.code {
+            KeyCode::Enter => match self.submit_hostnames(box_id) {
+                Ok(()) => {
+                    self.save();
+                    return self.write_hosts();
+                }
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+            KeyCode::Esc => self.view = AppView::Hosts(Some(box_id), None),
+            _ => {
+                input.handle_key(key);
+            }
+        }
+        None
+    }
+
+    /// Write the regenerated hosts file, through sudo when it belongs to root
+    fn write_hosts(&mut self) -> Option<Effect> {
+        let content = match self.updated_hosts() {
+            Ok(Some(content)) => content,
+            Ok(None) => {
+                self.set_status("Hosts file already up to date", StatusKind::Info);
+                return None;
+            }
+            Err(e) => {
+                self.set_status(e, StatusKind::Error);
+                return None;
+            }
+        };
+        Some(Effect::WriteHosts(content))
+    }
+
+    fn snippet_key(&mut self, box_id: i32, key: KeyEvent) -> Option<Effect> {
+        let AppView::Snippets(_, picker) = &mut self.view else {
+            return None;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let command = match &mut picker.form {
+            // Values of the placeholders
+            Some(form) => match key.code {
+                KeyCode::Tab => {
+                    form.current = (form.current + 1) % form.fields.len();
+                    None
+                }
+                KeyCode::BackTab => {
+                    form.current = (form.current + form.fields.len() - 1) % form.fields.len();
+                    None
+                }
+                KeyCode::Enter => self.submit_snippet_field(box_id),
+                KeyCode::Esc => {
+                    picker.form = None;
+                    None
+                }
+                _ => {
+                    form.fields[form.current].1.handle_key(key);
+                    None
+                }
+            },
+            // Fuzzy search
+            None => match key.code {
+                KeyCode::Enter => self.pick_snippet(box_id),
+                KeyCode::Esc => {
+                    self.view = AppView::Details(box_id);
+                    None
+                }
+                KeyCode::Down | KeyCode::Tab => {
+                    self.move_snippet_selection(true);
+                    None
+                }
+                KeyCode::Char('n') if ctrl => {
+                    self.move_snippet_selection(true);
+                    None
+                }
+                KeyCode::Up | KeyCode::BackTab => {
+                    self.move_snippet_selection(false);
+                    None
+                }
+                KeyCode::Char('p') if ctrl => {
+                    self.move_snippet_selection(false);
+                    None
+                }
+                _ => {
+                    if picker.query.handle_key(key) {
+                        picker.selected = 0;
+                    }
+                    None
+                }
+            },
+        };
+        command.map(|command| Effect::RunCommand { box_id, command })
+    }
+
+    fn writeup_key(&mut self, box_id: i32, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.cycle_redact_mode(box_id);
+            }
+            KeyCode::Tab => {
+                let candidates = self.writeup_path.complete_path();
+                if !candidates.is_empty() {
+                    self.set_status(candidates.join("  "), StatusKind::Info);
+                }
+            }
+            KeyCode::Enter => match self.generate_writeup(box_id) {
+                Ok(path) if !self.redaction_problems.is_empty() => {
+                    self.set_status(
+                        format!(
+                            "Write-up exported → {} ({} invalid redact.rules line(s) ignored)",
+                            path.display(),
+                            self.redaction_problems.len()
+                        ),
+                        StatusKind::Error,
+                    );
+                    self.view = AppView::Details(box_id);
+                }
+                Ok(path) => {
+                    self.set_status(
+                        format!("Write-up exported → {}", path.display()),
+                        StatusKind::Success,
+                    );
+                    self.view = AppView::Details(box_id);
+                }
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+            KeyCode::Esc => self.view = AppView::Details(box_id),
+            _ => {
+                self.writeup_path.handle_key(key);
+            }
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Effect> {
+        let position = Position::new(mouse.column, mouse.row);
+        let target = self
+            .clickables
+            .iter()
+            .find(|(area, _)| area.contains(position))
+            .map(|(_, clickable)| *clickable);
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                // Overlays: a click closes the help, the palette is keyboard only
+                if self.show_help {
+                    self.show_help = false;
+                    return None;
+                }
+                if self.palette.is_some() {
+                    return None;
+                }
+                match target? {
+                    Clickable::Row(index) => self.click_row(index),
+                    Clickable::Action(action) => return self.run_action(action),
+                    Clickable::Key(key) => return self.handle_key(key),
+                    Clickable::Pane(_) => {}
+                }
+            }
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+                let down = mouse.kind == MouseEventKind::ScrollDown;
+                if self.palette.is_some() {
+                    if down {
+                        self.palette_next();
+                    } else {
+                        self.palette_previous();
+                    }
+                    return None;
+                }
+                if self.show_help {
+                    return None;
+                }
+                match target {
+                    Some(Clickable::Pane(pane)) => self.scroll_pane(pane, down),
+                    // Lists move their selection
+                    _ if matches!(
+                        self.view,
+                        AppView::List
+                            | AppView::Dashboard
+                            | AppView::EditEnvVars(_, None)
+                            | AppView::EditNotes(_, None)
+                            | AppView::Loot(_)
+                            | AppView::Timeline(_)
+                            | AppView::Checklists(..)
+                            | AppView::Suggestions(_, None)
+                            | AppView::Payloads(_)
+                    ) =>
+                    {
+                        return self.run_action(if down { Action::Down } else { Action::Up });
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Select the clicked item. Clicking the selected box opens it.
+    fn click_row(&mut self, index: usize) {
+        match self.view {
+            AppView::List | AppView::Dashboard => {
+                if self.selected_box_id == Some(index as i32) {
+                    self.select_current();
+                } else {
+                    self.selected_box_id = Some(index as i32);
+                }
+            }
+            AppView::EditEnvVars(_, None) => self.selected_env_var = Some(index),
+            AppView::EditNotes(_, None) => self.selected_note = Some(index),
+            AppView::Loot(_) => self.selected_attachment = Some(index),
+            AppView::Suggestions(box_id, None) => {
+                if self.selected_suggestion == Some(index) {
+                    self.edit_selected_suggestion(box_id);
+                } else {
+                    self.selected_suggestion = Some(index);
+                }
+            }
+            AppView::Payloads(box_id) => {
+                if self.selected_payload == Some(index) {
+                    self.copy_selected_payload(box_id);
+                } else {
+                    self.selected_payload = Some(index);
+                }
+            }
+            AppView::Checklists(box_id, None) => {
+                if self.selected_check == Some(index) {
+                    if let Err(e) = self.toggle_selected_check(box_id) {
+                        self.set_status(e, StatusKind::Error);
+                    } else {
+                        self.save();
+                    }
+                } else {
+                    self.selected_check = Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn scroll_pane(&mut self, pane: DetailPane, down: bool) {
+        let AppView::Details(box_id) = self.view else {
+            return;
+        };
+        let Some(ctf_box) = self.boxes.iter().find(|b| b.id == box_id) else {
+            return;
+        };
+        let (offset, count) = match pane {
+            DetailPane::Notes => (&mut self.notes_scroll, ctf_box.notes.len()),
+            DetailPane::Actions => (&mut self.actions_scroll, ctf_box.actions.len()),
+        };
+        *offset = if down {
+            (*offset + 1).min(count.saturating_sub(1))
+        } else {
+            offset.saturating_sub(1)
+        };
+    }
+
+    /// Keys of the views driven by the keymap
+    fn action_key(&mut self, picked: Option<Action>, key: KeyEvent) -> Option<Effect> {
+        let action = match picked {
+            Some(action) => action,
+            None => {
+                let context = KeyContext::for_view(&self.view).unwrap_or(KeyContext::Global);
+                match self.keymap.resolve(context, key) {
+                    KeyResult::Action(action) => action,
+                    _ => return None,
+                }
+            }
+        };
+        self.run_action(action)
+    }
+
+    fn run_action(&mut self, action: Action) -> Option<Effect> {
+        match (action, self.view.clone()) {
+            // Same in every view
+            (Action::Palette, view) => {
+                self.open_palette(KeyContext::for_view(&view).unwrap_or(KeyContext::Global))
+            }
+            (Action::Help, _) => self.show_help = true,
+            (Action::CycleTheme, _) => self.cycle_theme(),
+            (Action::Quit, _) => self.quit(),
+            (Action::Top, _) => self.select_edge(false),
+            (Action::Bottom, _) => self.select_edge(true),
+
+            // List
+            (Action::Down, AppView::List) => self.next(),
+            (Action::Up, AppView::List) => self.previous(),
+            (Action::Open, AppView::List) => self.select_current(),
+            (Action::AddBox, AppView::List) => self.start_add_box(),
+            (Action::DeleteBox, AppView::List) => self.start_delete_box(),
+            (Action::Shell, AppView::List) => return self.selected_box().map(Effect::LaunchShell),
+            (Action::Tmux, AppView::List) => return self.selected_box().map(Effect::OpenTmux),
+            (Action::ImportLogs, AppView::List) => {
+                if let Some(box_id) = self.selected_box() {
+                    self.import_activity(box_id, ImportReport::Count);
+                }
+            }
+
+            // Statistics
+            (Action::Stats, AppView::List) => self.open_stats(),
+            (Action::Back, AppView::Stats) => self.go_back(),
+
+            // Dashboard
+            (Action::Dashboard, AppView::List) => self.open_dashboard(),
+            (Action::Down, AppView::Dashboard) => self.dashboard_move(true),
+            (Action::Up, AppView::Dashboard) => self.dashboard_move(false),
+            (Action::Open, AppView::Dashboard) => self.select_current(),
+            (Action::FocusNext, AppView::Dashboard) => self.focus_pane(true),
+            (Action::FocusPrevious, AppView::Dashboard) => self.focus_pane(false),
+            (Action::GrowPane, AppView::Dashboard) => self.resize_pane(true),
+            (Action::ShrinkPane, AppView::Dashboard) => self.resize_pane(false),
+            (Action::AddFlag, AppView::Dashboard) => {
+                if let Some(box_id) = self.selected_box() {
+                    self.start_add_flag(box_id);
+                }
+            }
+            (Action::Shell, AppView::Dashboard) => return self.selected_box().map(Effect::LaunchShell),
+            (Action::Back, AppView::Dashboard) => self.close_dashboard(),
+
+            // Details
+            (Action::AddFlag, AppView::Details(id)) => self.start_add_flag(id),
+            (Action::Timeline, AppView::Details(id)) => self.start_timeline(id),
+            (Action::Checklists, AppView::Details(id)) => self.start_checklists(id),
+            (Action::Suggestions, AppView::Details(id)) => self.start_suggestions(id),
+            (Action::Snippets, AppView::Details(id)) => self.start_snippets(id),
+            (Action::Payloads, AppView::Details(id)) => self.start_payloads(id),
+            (Action::Hosts, AppView::Details(id)) => self.start_hosts(Some(id)),
+            (Action::ExploitNotes, AppView::Details(id)) => match self.add_exploit_notes(id) {
+                Ok(0) => self.set_status("Exploit candidates already in the notes", StatusKind::Info),
+                Ok(count) => self.save_with_status(&format!("{} Foothold note(s) added", count)),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+            (Action::EditEnvVars, AppView::Details(id)) => self.start_edit_env_vars(id),
+            (Action::EditNotes, AppView::Details(id)) => self.start_edit_notes(id),
+            (Action::Writeup, AppView::Details(id)) => self.start_writeup_export(id),
+            (Action::Loot, AppView::Details(id)) => {
+                self.start_loot_view(id);
+                self.import_activity(id, ImportReport::Quiet);
+            }
+            (Action::Shell, AppView::Details(id)) => return Some(Effect::LaunchShell(id)),
+            (Action::Tmux, AppView::Details(id)) => return Some(Effect::OpenTmux(id)),
+            (Action::KillTmux, AppView::Details(id)) => self.start_kill_tmux(id),
+            (Action::ImportLogs, AppView::Details(id)) => self.import_activity(id, ImportReport::Count),
+            (Action::Back, AppView::Details(_)) => self.go_back(),
+
+            // Delete confirmation
+            (Action::Confirm, AppView::DeleteBox(id)) => {
+                let had_hostnames = self
+                    .boxes
+                    .iter()
+                    .any(|b| b.id == id && !b.hostnames.is_empty());
+                self.confirm_delete_box(id);
+                self.save_with_status("Box deleted");
+                // Its entries are still in the hosts file
+                if had_hostnames && self.hosts_out_of_date() {
+                    self.start_hosts(None);
+                }
+            }
+            (Action::Back | Action::Cancel, AppView::DeleteBox(_)) => self.cancel_delete(),
+
+            // tmux session end confirmation
+            (Action::Confirm, AppView::KillTmux(id)) => match self.confirm_kill_tmux(id) {
+                // What its shells logged until the end
+                Ok(()) => self.import_activity(id, ImportReport::Status("tmux session ended".to_string())),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+            (Action::Back | Action::Cancel, AppView::KillTmux(id)) => self.view = AppView::Details(id),
+
+            // Environment variables
+            (Action::Add, AppView::EditEnvVars(id, _)) => self.start_edit_env_vars(id),
+            (Action::Down, AppView::EditEnvVars(id, _)) => self.next_env_var(id),
+            (Action::Up, AppView::EditEnvVars(id, _)) => self.previous_env_var(id),
+            (Action::Delete, AppView::EditEnvVars(id, _)) => match self.delete_selected_env_var(id) {
+                Ok(_) => self.save_with_status("Variable deleted"),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+
+            // Notes
+            (action @ (Action::Add | Action::Edit), AppView::EditNotes(id, _)) => {
+                match self.start_note_form(id, action == Action::Edit) {
+                    Ok(form) => self.view = AppView::EditNotes(id, Some(form)),
+                    Err(e) => self.set_status(e, StatusKind::Error),
+                }
+            }
+            (Action::Down, AppView::EditNotes(id, _)) => self.next_note(id),
+            (Action::Up, AppView::EditNotes(id, _)) => self.previous_note(id),
+            (Action::Delete, AppView::EditNotes(id, _)) => match self.delete_selected_note(id) {
+                Ok(_) => self.save_with_status("Note deleted"),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+
+            // Loot
+            (Action::Down, AppView::Loot(id)) => self.next_attachment(id),
+            (Action::Up, AppView::Loot(id)) => self.previous_attachment(id),
+            (action @ (Action::Open | Action::OpenHex), AppView::Loot(id)) => {
+                return Some(Effect::OpenAttachment {
+                    box_id: id,
+                    force_hex: action == Action::OpenHex,
+                });
+            }
+            (Action::Delete, AppView::Loot(id)) => match self.delete_selected_attachment(id) {
+                Ok(_) => self.save_with_status("File deleted"),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+
+            // Timeline
+            (Action::Down, AppView::Timeline(id)) => self.scroll_timeline(id, true),
+            (Action::Up, AppView::Timeline(id)) => self.scroll_timeline(id, false),
+
+            // Checklists: the template picker takes the moves while open
+            (Action::Down, AppView::Checklists(_, Some(_))) => self.move_template_picker(true),
+            (Action::Up, AppView::Checklists(_, Some(_))) => self.move_template_picker(false),
+            (Action::Toggle, AppView::Checklists(id, Some(_))) => match self.add_picked_checklist(id) {
+                Ok(msg) => self.save_with_status(&msg),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+            (Action::Back, AppView::Checklists(id, Some(_))) => self.view = AppView::Checklists(id, None),
+            (Action::Add, AppView::Checklists(id, None)) => self.open_template_picker(id),
+            (Action::Down, AppView::Checklists(id, None)) => self.next_check(id),
+            (Action::Up, AppView::Checklists(id, None)) => self.previous_check(id),
+            (Action::Toggle, AppView::Checklists(id, None)) => match self.toggle_selected_check(id) {
+                Ok(_) => self.save(),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+            (Action::Delete, AppView::Checklists(id, None)) => match self.delete_selected_checklist(id) {
+                Ok(msg) => self.save_with_status(&msg),
+                Err(e) => self.set_status(e, StatusKind::Error),
+            },
+
+            // Suggestions: the command opens in an input before it runs
+            (Action::Down, AppView::Suggestions(id, None)) => self.next_suggestion(id),
+            (Action::Up, AppView::Suggestions(id, None)) => self.previous_suggestion(id),
+            (Action::Open, AppView::Suggestions(id, None)) => self.edit_selected_suggestion(id),
+
+            // Payloads
+            (Action::Down, AppView::Payloads(_)) => self.next_payload(),
+            (Action::Up, AppView::Payloads(_)) => self.previous_payload(),
+            (Action::CycleEncoding, AppView::Payloads(_)) => self.cycle_payload_encoding(),
+            (Action::Copy, AppView::Payloads(id)) => self.copy_selected_payload(id),
+
+            // Hosts file
+            (Action::Edit, AppView::Hosts(Some(id), None)) => self.edit_hostnames(id),
+            (Action::WriteHosts, AppView::Hosts(_, None)) => return self.write_hosts(),
+            (Action::Back, AppView::Hosts(None, None)) => self.view = AppView::List,
+
+            // Sub-views of a box go back to its details
+            (
+                Action::Back,
+                AppView::EditEnvVars(id, _)
+                | AppView::EditNotes(id, _)
+                | AppView::Loot(id)
+                | AppView::Timeline(id)
+                | AppView::Checklists(id, None)
+                | AppView::Suggestions(id, None)
+                | AppView::Payloads(id)
+                | AppView::Hosts(Some(id), None),
+            ) => {
+                self.view = AppView::Details(id);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Id of the box selected in the list
+    fn selected_box(&self) -> Option<i32> {
+        self.selected_box_id
+            .and_then(|idx| self.boxes.get(idx as usize))
+            .map(|b| b.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::DashboardPane;
+    use crate::config::Config;
+    use crate::models::CtfBox;
+
+    fn app() -> App {
+        App::new(vec![CtfBox::sample(1, "Lame"), CtfBox::sample(2, "Blue")], Config::default())
+    }
+
+    fn key(code: KeyCode) -> Message {
+        Message::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    /// Feed the keys, returning the effects of the last one
+    fn press(app: &mut App, keys: &str) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        for c in keys.chars() {
+            effects = app.update(key(KeyCode::Char(c)));
+        }
+        effects
+    }
+
+    #[test]
+    fn moving_and_opening_a_box() {
+        let mut app = app();
+        press(&mut app, "j");
+        assert_eq!(app.selected_box_id, Some(1));
+        app.update(key(KeyCode::Enter));
+        assert_eq!(app.view, AppView::Details(2));
+        app.update(key(KeyCode::Esc));
+        assert_eq!(app.view, AppView::List);
+    }
+
+    #[test]
+    fn adding_a_box_queues_a_save_instead_of_writing() {
+        let mut app = app();
+        press(&mut app, "a");
+        assert!(matches!(app.view, AppView::AddBox(_)));
+        press(&mut app, "Jerry");
+        app.update(key(KeyCode::Tab));
+        app.update(key(KeyCode::Tab));
+        press(&mut app, "10.10.10.95");
+        let effects = app.update(key(KeyCode::Enter));
+
+        assert_eq!(effects, vec![Effect::Save(Some("Box added".to_string()))]);
+        assert_eq!(app.view, AppView::List);
+        assert_eq!(app.boxes.last().map(|b| b.title.as_str()), Some("Jerry"));
+        // The status waits for the save to succeed
+        assert!(app.status_message.is_none());
+    }
+
+    #[test]
+    fn invalid_box_form_reports_the_error_without_saving() {
+        let mut app = app();
+        press(&mut app, "a");
+        let effects = app.update(key(KeyCode::Enter));
+        assert!(effects.is_empty());
+        assert!(matches!(app.view, AppView::AddBox(_)));
+        let (message, kind, _) = app.status_message.clone().unwrap();
+        assert_eq!(message, "Title cannot be empty");
+        assert_eq!(kind, StatusKind::Error);
+    }
+
+    #[test]
+    fn saved_message_shows_the_status_or_the_failure() {
+        let mut app = app();
+        app.update(Message::Saved {
+            result: Ok(()),
+            status: Some("Box added".to_string()),
+        });
+        assert_eq!(app.status_message.as_ref().unwrap().1, StatusKind::Success);
+
+        app.update(Message::Saved {
+            result: Err("disk full".to_string()),
+            status: Some("Box added".to_string()),
+        });
+        let (message, kind, _) = app.status_message.clone().unwrap();
+        assert_eq!(message, "Save failed: disk full");
+        assert_eq!(kind, StatusKind::Error);
+    }
+
+    #[test]
+    fn shell_and_import_keys_return_effects() {
+        let mut app = app();
+        assert_eq!(press(&mut app, "l"), vec![Effect::LaunchShell(1)]);
+
+        app.update(key(KeyCode::Enter));
+        assert_eq!(
+            press(&mut app, "i"),
+            vec![Effect::ImportActivity { box_id: 1, report: ImportReport::Count }]
+        );
+        assert_eq!(
+            press(&mut app, "o"),
+            vec![Effect::ImportActivity { box_id: 1, report: ImportReport::Quiet }]
+        );
+        assert_eq!(app.view, AppView::Loot(1));
+    }
+
+    #[test]
+    fn imported_activity_is_merged_then_saved() {
+        let mut app = app();
+        let activity = BoxActivity {
+            actions: vec![crate::models::Action {
+                timestamp: Utc::now(),
+                command: "nmap -sV 10.10.10.5".to_string(),
+                result: crate::models::ActionResult::Success,
+                note: None,
+                output: None,
+            }],
+            ..Default::default()
+        };
+        let effects = app.update(Message::ActivityImported {
+            box_id: 1,
+            activity: Ok(activity.clone()),
+            report: ImportReport::Count,
+        });
+        assert_eq!(effects, vec![Effect::Save(Some("1 command(s) imported".to_string()))]);
+        assert_eq!(app.boxes[0].actions.len(), 1);
+
+        // The same commands again are not duplicated
+        app.update(Message::ActivityImported {
+            box_id: 1,
+            activity: Ok(activity),
+            report: ImportReport::Quiet,
+        });
+        assert_eq!(app.boxes[0].actions.len(), 1);
+    }
+
+    #[test]
+    fn shell_exit_records_the_session_and_imports() {
+        let mut app = app();
+        let effects = app.update(Message::ShellExited {
+            box_id: 2,
+            started: Utc::now(),
+            result: Ok(()),
+        });
+        assert_eq!(app.boxes[1].sessions.len(), 1);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::ImportActivity { box_id: 2, report: ImportReport::Status(_) }]
+        ));
+    }
+
+    #[test]
+    fn deleting_a_box_asks_first() {
+        let mut app = app();
+        press(&mut app, "d");
+        assert_eq!(app.view, AppView::DeleteBox(1));
+        let effects = press(&mut app, "y");
+        assert_eq!(effects, vec![Effect::Save(Some("Box deleted".to_string()))]);
+        assert_eq!(app.boxes.len(), 1);
+    }
+
+    #[test]
+    fn edited_hostnames_are_saved_and_written() {
+        let mut app = app();
+        app.hosts_content = Ok("127.0.0.1\tlocalhost\n".to_string());
+        app.view = AppView::Hosts(Some(1), None);
+        press(&mut app, "e");
+        press(&mut app, "lame.htb");
+        let effects = app.update(key(KeyCode::Enter));
+
+        assert_eq!(app.boxes[0].hostnames, vec!["lame.htb"]);
+        let [Effect::Save(None), Effect::WriteHosts(content)] = effects.as_slice() else {
+            panic!("unexpected effects {:?}", effects);
+        };
+        assert!(content.contains("10.10.10.5\tlame.htb\t# Lame (1)"));
+
+        // Written directly, or through sudo when denied
+        let effects = app.update(Message::HostsWritten {
+            content: content.clone(),
+            result: Ok(crate::hosts::Written::NeedsSudo),
+        });
+        assert_eq!(effects, vec![Effect::WriteHostsWithSudo(content.clone())]);
+        app.update(Message::HostsWritten {
+            content: content.clone(),
+            result: Ok(crate::hosts::Written::Done),
+        });
+        assert_eq!(app.hosts_content.as_ref(), Ok(content));
+    }
+
+    #[test]
+    fn dashboard_focus_cycles_both_ways() {
+        let mut app = app();
+        press(&mut app, "D");
+        assert_eq!(app.view, AppView::Dashboard);
+        assert_eq!(app.dashboard.focus, DashboardPane::Boxes);
+
+        let mut focus = Vec::new();
+        for _ in 0..3 {
+            app.update(key(KeyCode::Tab));
+            focus.push(app.dashboard.focus);
+        }
+        assert_eq!(focus, [DashboardPane::Info, DashboardPane::Feed, DashboardPane::Boxes]);
+
+        app.update(Message::Key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert_eq!(app.dashboard.focus, DashboardPane::Feed);
+    }
+
+    #[test]
+    fn dashboard_keys_move_in_the_focused_pane() {
+        let mut app = app();
+        for i in 0..3 {
+            app.boxes[0].actions.push(crate::models::Action {
+                timestamp: Utc::now(),
+                command: format!("echo {}", i),
+                result: crate::models::ActionResult::Success,
+                note: None,
+                output: None,
+            });
+        }
+        press(&mut app, "D");
+        press(&mut app, "j");
+        assert_eq!(app.selected_box_id, Some(1));
+        assert_eq!(app.dashboard.feed_scroll, 0);
+
+        app.update(key(KeyCode::BackTab));
+        press(&mut app, "jjjj");
+        // The feed stops on its last entry, the boxes do not move
+        assert_eq!(app.dashboard.feed_scroll, 2);
+        assert_eq!(app.selected_box_id, Some(1));
+        press(&mut app, "k");
+        assert_eq!(app.dashboard.feed_scroll, 1);
+    }
+
+    #[test]
+    fn dashboard_panes_resize_within_bounds() {
+        let mut app = app();
+        press(&mut app, "D");
+        app.update(key(KeyCode::Tab));
+        // The info pane takes from the widest other pane
+        press(&mut app, ">");
+        assert_eq!(app.dashboard.widths, [25, 45, 30]);
+        press(&mut app, "<<");
+        // and gives to the narrowest, the first of equals
+        assert_eq!(app.dashboard.widths, [35, 35, 30]);
+
+        for keys in [">".repeat(20), "<".repeat(20)] {
+            press(&mut app, &keys);
+            let widths = app.dashboard.widths;
+            assert!(widths.iter().all(|w| *w >= 10), "{:?}", widths);
+            assert_eq!(widths.iter().sum::<u16>(), 100);
+        }
+        assert_eq!(app.dashboard.widths[1], 10);
+    }
+
+    #[test]
+    fn quit_key() {
+        let mut app = app();
+        press(&mut app, "q");
+        assert!(app.should_quit);
+    }
+
+    /// Draw a 120x40 frame, recording its clickable areas like the main loop
+    fn draw(app: &mut App) {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 40)).unwrap();
+        let mut clickables = Vec::new();
+        terminal
+            .draw(|f| clickables = crate::ui::draw(f, app).1)
+            .unwrap();
+        app.clickables = clickables;
+    }
+
+    fn mouse(app: &mut App, kind: MouseEventKind, column: u16, row: u16) -> Vec<Effect> {
+        draw(app);
+        app.update(Message::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }))
+    }
+
+    fn click(app: &mut App, column: u16, row: u16) -> Vec<Effect> {
+        mouse(app, MouseEventKind::Down(MouseButton::Left), column, row)
+    }
+
+    /// Where the frame put the clickable
+    fn position_of(app: &mut App, clickable: Clickable) -> (u16, u16) {
+        draw(app);
+        let (area, _) = app
+            .clickables
+            .iter()
+            .find(|(_, c)| *c == clickable)
+            .unwrap_or_else(|| panic!("{:?} is not drawn", clickable));
+        (area.x, area.y)
+    }
+
+    #[test]
+    fn clicking_a_box_selects_it_then_opens_it() {
+        let mut app = app();
+        // Second row inside the list's border
+        click(&mut app, 10, 2);
+        assert_eq!(app.selected_box_id, Some(1));
+        assert_eq!(app.view, AppView::List);
+        click(&mut app, 10, 2);
+        assert_eq!(app.view, AppView::Details(2));
+    }
+
+    #[test]
+    fn clicking_outside_the_rows_does_nothing() {
+        let mut app = app();
+        let effects = click(&mut app, 10, 20);
+        assert!(effects.is_empty());
+        assert_eq!(app.selected_box_id, Some(0));
+        assert_eq!(app.view, AppView::List);
+    }
+
+    #[test]
+    fn wheel_moves_the_list_selection() {
+        let mut app = app();
+        mouse(&mut app, MouseEventKind::ScrollDown, 10, 10);
+        assert_eq!(app.selected_box_id, Some(1));
+        mouse(&mut app, MouseEventKind::ScrollUp, 10, 10);
+        assert_eq!(app.selected_box_id, Some(0));
+    }
+
+    #[test]
+    fn clicking_a_row_selects_the_item_of_the_view() {
+        let mut app = app();
+        app.boxes[0].env_vars.insert("LHOST".to_string(), "10.10.14.2".to_string());
+        app.boxes[0].env_vars.insert("DOMAIN".to_string(), "lame.htb".to_string());
+        app.view = AppView::EditEnvVars(1, None);
+        let (x, y) = position_of(&mut app, Clickable::Row(1));
+        click(&mut app, x, y);
+        assert_eq!(app.selected_env_var, Some(1));
+
+        // The selected variable is the one deleted, LHOST sorting after DOMAIN
+        app.delete_selected_env_var(1).unwrap();
+        assert!(app.boxes[0].env_vars.contains_key("DOMAIN"));
+        assert!(!app.boxes[0].env_vars.contains_key("LHOST"));
+    }
+
+    #[test]
+    fn wheel_over_a_details_pane_scrolls_it() {
+        let mut app = app();
+        for i in 0..3 {
+            app.boxes[0].actions.push(crate::models::Action {
+                timestamp: Utc::now(),
+                command: format!("echo {}", i),
+                result: crate::models::ActionResult::Success,
+                note: None,
+                output: None,
+            });
+        }
+        app.view = AppView::Details(1);
+        let (x, y) = position_of(&mut app, Clickable::Pane(DetailPane::Actions));
+        mouse(&mut app, MouseEventKind::ScrollDown, x + 1, y + 1);
+        assert_eq!(app.actions_scroll, 1);
+        assert_eq!(app.notes_scroll, 0);
+        mouse(&mut app, MouseEventKind::ScrollUp, x + 1, y + 1);
+        assert_eq!(app.actions_scroll, 0);
+    }
+
+    #[test]
+    fn clicking_a_footer_shortcut_runs_its_action() {
+        let mut app = app();
+        let (x, y) = position_of(&mut app, Clickable::Action(Action::AddBox));
+        click(&mut app, x, y);
+        assert!(matches!(app.view, AppView::AddBox(_)));
+
+        // Forms list fixed keys
+        let escape = Clickable::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        let (x, y) = position_of(&mut app, escape);
+        click(&mut app, x, y);
+        assert_eq!(app.view, AppView::List);
+
+        let (x, y) = position_of(&mut app, Clickable::Action(Action::Quit));
+        click(&mut app, x, y);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn a_click_closes_the_help_only() {
+        let mut app = app();
+        app.show_help = true;
+        click(&mut app, 10, 2);
+        assert!(!app.show_help);
+        assert_eq!(app.selected_box_id, Some(0));
+    }
+}